@@ -0,0 +1,283 @@
+use crate::gui_server::StatsSnapshot;
+use crate::messages::NodeId;
+use crate::node::CloudNode;
+use crate::worker_registry::{WorkerControl, WorkerRegistry, WorkerStatus};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Env var holding the shared token `POST /drain` and `POST /reconfigure`
+/// require in an `X-Admin-Token` header. Unset means the control routes
+/// reject every request (401) rather than being silently open - `/status` is
+/// read-only and never gated on this.
+const ADMIN_TOKEN_ENV: &str = "ADMIN_API_TOKEN";
+
+/// Env var that opts into binding `0.0.0.0` instead of the loopback-only
+/// default. Unset/anything other than "1"/"true" keeps the admin server
+/// off the network.
+const ADMIN_BIND_ALL_ENV: &str = "ADMIN_API_BIND_ALL";
+
+/// Shared state the admin HTTP server reads from - the exact same
+/// `Arc<RwLock<Option<StatsSnapshot>>>` the GUI's poller publishes into, so
+/// `/status` and the Network tab can never disagree about the cluster.
+#[derive(Clone)]
+struct AdminState {
+    stats: Arc<RwLock<Option<StatsSnapshot>>>,
+    peer_addresses: Arc<HashMap<NodeId, String>>,
+    monitored_node_id: Option<NodeId>,
+    /// `None` in standalone monitor mode, where there's no local node to
+    /// drain or reconfigure - `/drain` and `/reconfigure` then 503 rather
+    /// than silently no-op.
+    node: Option<Arc<CloudNode>>,
+    /// Required value of the `X-Admin-Token` header for `/drain` and
+    /// `/reconfigure`, read once from `ADMIN_API_TOKEN` at startup. `None`
+    /// means those routes are disabled outright.
+    admin_token: Option<String>,
+}
+
+/// Whether `headers` carries an `X-Admin-Token` matching `state.admin_token`.
+fn authorized(state: &AdminState, headers: &HeaderMap) -> bool {
+    match &state.admin_token {
+        Some(expected) => headers.get("X-Admin-Token").and_then(|v| v.to_str().ok()) == Some(expected.as_str()),
+        None => false,
+    }
+}
+
+#[derive(Serialize)]
+struct NodeRole {
+    coordinator: bool,
+}
+
+#[derive(Serialize)]
+struct NodeStatusJson {
+    id: NodeId,
+    role: NodeRole,
+    #[serde(rename = "isUp")]
+    is_up: bool,
+    #[serde(rename = "lastSeenSecsAgo")]
+    last_seen_secs_ago: u64,
+    // None for peers: this node only learns whether a peer is reachable,
+    // not its load/queue/state - those are only known for the monitored
+    // node itself, from its own NodeStats.
+    load: Option<f64>,
+    #[serde(rename = "queueLength")]
+    queue_length: Option<usize>,
+    state: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ClusterStatusJson {
+    #[serde(rename = "monitoredNodeId")]
+    monitored_node_id: Option<NodeId>,
+    nodes: Vec<NodeStatusJson>,
+}
+
+async fn get_status(State(state): State<AdminState>) -> Json<ClusterStatusJson> {
+    let snapshot = state.stats.read().await.clone();
+    let mut nodes = Vec::new();
+
+    match &snapshot {
+        Some(snapshot) => {
+            let last_seen_secs_ago = snapshot.polled_instant.elapsed().as_secs();
+
+            nodes.push(NodeStatusJson {
+                id: snapshot.node_id,
+                role: NodeRole { coordinator: snapshot.is_coordinator },
+                is_up: true,
+                last_seen_secs_ago,
+                load: Some(snapshot.load),
+                queue_length: Some(snapshot.queue_length),
+                state: Some(snapshot.state.clone()),
+            });
+
+            let mut peers: Vec<_> = snapshot.peer_status.iter().collect();
+            peers.sort_by_key(|(id, _)| *id);
+            for (&peer_id, &reachable) in peers {
+                nodes.push(NodeStatusJson {
+                    id: peer_id,
+                    role: NodeRole { coordinator: false },
+                    is_up: reachable,
+                    last_seen_secs_ago,
+                    load: None,
+                    queue_length: None,
+                    state: None,
+                });
+            }
+        }
+        None => {
+            // No poll has completed yet - fall back to the bootstrap peer
+            // list with everything unknown, so /status responds with the
+            // cluster's shape even before the first snapshot lands.
+            let mut ids: Vec<_> = state.peer_addresses.keys().copied().collect();
+            ids.sort_unstable();
+            for id in ids {
+                nodes.push(NodeStatusJson {
+                    id,
+                    role: NodeRole { coordinator: false },
+                    is_up: false,
+                    last_seen_secs_ago: 0,
+                    load: None,
+                    queue_length: None,
+                    state: None,
+                });
+            }
+        }
+    }
+
+    Json(ClusterStatusJson {
+        monitored_node_id: state.monitored_node_id,
+        nodes,
+    })
+}
+
+/// Body for `POST /reconfigure`: a node's identity/zone/capacity tags
+/// (see `cluster_layout::NodeCapability`), all optional so a caller can
+/// change just the zone, just the capacity, or both in one call.
+#[derive(Deserialize)]
+struct ReconfigureRequest {
+    capacity: Option<f64>,
+    zone: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ControlResponse {
+    ok: bool,
+    message: String,
+}
+
+async fn post_drain(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ControlResponse>) {
+    if !authorized(&state, &headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ControlResponse { ok: false, message: "missing or invalid X-Admin-Token".to_string() }),
+        );
+    }
+
+    match &state.node {
+        Some(node) => {
+            info!("Admin API: draining requested for Node {}", node.id);
+            Arc::clone(node).start_draining().await;
+            (StatusCode::OK, Json(ControlResponse { ok: true, message: "draining".to_string() }))
+        }
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ControlResponse { ok: false, message: "no local node to drain (standalone monitor mode)".to_string() }),
+        ),
+    }
+}
+
+async fn post_reconfigure(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(body): Json<ReconfigureRequest>,
+) -> (StatusCode, Json<ControlResponse>) {
+    if !authorized(&state, &headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ControlResponse { ok: false, message: "missing or invalid X-Admin-Token".to_string() }),
+        );
+    }
+
+    let Some(node) = &state.node else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ControlResponse {
+                ok: false,
+                message: "no local node to reconfigure (standalone monitor mode)".to_string(),
+            }),
+        );
+    };
+
+    let current = node.capability().await;
+    let capacity = body.capacity.unwrap_or(current.capacity);
+    let zone = body.zone.unwrap_or(current.zone);
+    info!("Admin API: reconfiguring Node {} to capacity={} zone={}", node.id, capacity, zone);
+    node.set_capability(capacity, zone).await;
+
+    (StatusCode::OK, Json(ControlResponse { ok: true, message: "layout recomputed".to_string() }))
+}
+
+/// Serve `GET /status` plus the drain/reconfigure control routes on `port`.
+/// `/status` returns the same cluster view the Network tab renders as JSON,
+/// sharing `stats` with the GUI's poller rather than polling the node again
+/// so the two can't drift apart. `POST /drain` puts `node` into
+/// `NodeState::Draining` (see `CloudNode::start_draining`); `POST
+/// /reconfigure` changes its capacity/zone tags and recomputes the cluster
+/// layout, both without a restart. `node` is `None` in standalone monitor
+/// mode, where the control routes have nothing local to act on.
+///
+/// Both control routes mutate live cluster state, so they require an
+/// `X-Admin-Token` header matching `ADMIN_API_TOKEN` - unset means they
+/// reject every request (401) rather than being open to anyone who can
+/// reach the port.
+/// The listener itself binds loopback-only unless `ADMIN_API_BIND_ALL` is
+/// set to "1"/"true", since `/status` alone is still useful information to
+/// keep off the network by default.
+///
+/// Intended to be spawned on the caller's existing Tokio runtime (the one
+/// `ServerMonitorApp` already holds) rather than given a runtime of its own.
+/// Registers itself with `registry` as "Admin HTTP Server" so the Workers
+/// tab can see it and cancel it (a graceful shutdown of the listener);
+/// Pause/Resume have no meaning for a listening socket and are ignored.
+pub async fn serve(
+    port: u16,
+    stats: Arc<RwLock<Option<StatsSnapshot>>>,
+    peer_addresses: HashMap<NodeId, String>,
+    monitored_node_id: Option<NodeId>,
+    node: Option<Arc<CloudNode>>,
+    registry: WorkerRegistry,
+) -> std::io::Result<()> {
+    let (reporter, mut control_rx) = registry.register("Admin HTTP Server").await;
+
+    let admin_token = std::env::var(ADMIN_TOKEN_ENV).ok().filter(|t| !t.is_empty());
+    if admin_token.is_none() {
+        warn!(
+            "Admin HTTP server: {} is not set - POST /drain and /reconfigure will be disabled",
+            ADMIN_TOKEN_ENV
+        );
+    }
+    let bind_all = matches!(std::env::var(ADMIN_BIND_ALL_ENV).as_deref(), Ok("1") | Ok("true"));
+    let bind_host = if bind_all { "0.0.0.0" } else { "127.0.0.1" };
+
+    let state = AdminState {
+        stats,
+        peer_addresses: Arc::new(peer_addresses),
+        monitored_node_id,
+        node,
+        admin_token,
+    };
+    let app = Router::new()
+        .route("/status", get(get_status))
+        .route("/drain", post(post_drain))
+        .route("/reconfigure", post(post_reconfigure))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind((bind_host, port)).await?;
+    info!("Admin HTTP server listening on {}:{} (GET /status, POST /drain, POST /reconfigure)", bind_host, port);
+    reporter.set_status(WorkerStatus::Active).await;
+
+    let shutdown = async move {
+        loop {
+            match control_rx.recv().await {
+                Some(WorkerControl::Cancel) | None => break,
+                Some(WorkerControl::Pause) | Some(WorkerControl::Resume) => continue,
+            }
+        }
+    };
+
+    if let Err(e) = axum::serve(listener, app).with_graceful_shutdown(shutdown).await {
+        warn!("Admin HTTP server on port {} stopped: {}", port, e);
+        reporter.report_error(e.to_string()).await;
+    }
+    reporter.set_status(WorkerStatus::Dead).await;
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use distributed_image_cloud::bootstrap::{ClusterConfig, ConsulDiscovery, DiscoveryBackend, KubernetesDiscovery};
 use distributed_image_cloud::node::CloudNode;
 use env_logger::Env;
 use log::info;
@@ -5,6 +6,40 @@ use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
 
+/// Build a discovery backend from environment variables, if configured. Kept
+/// out of positional CLI args since both the positional and `--config` launch
+/// forms should be able to opt into discovery the same way.
+fn discovery_backend_from_env() -> Option<(Box<dyn DiscoveryBackend>, u64)> {
+    let interval_secs: u64 = env::var("DISCOVERY_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(15);
+
+    if let (Ok(consul_addr), Ok(service_name)) = (env::var("DISCOVERY_CONSUL_ADDR"), env::var("DISCOVERY_CONSUL_SERVICE")) {
+        return Some((Box::new(ConsulDiscovery { consul_addr, service_name }), interval_secs));
+    }
+
+    if let (Ok(api_server), Ok(namespace), Ok(service_name)) = (
+        env::var("DISCOVERY_K8S_API_SERVER"),
+        env::var("DISCOVERY_K8S_NAMESPACE"),
+        env::var("DISCOVERY_K8S_SERVICE"),
+    ) {
+        let bearer_token = env::var("DISCOVERY_K8S_TOKEN").unwrap_or_default();
+        return Some((
+            Box::new(KubernetesDiscovery { api_server, namespace, service_name, bearer_token }),
+            interval_secs,
+        ));
+    }
+
+    None
+}
+
+/// `ALLOW_LOCAL_ADDRESSES=1` opts into dialing private/loopback/link-local
+/// peers, needed for the local multi-process examples below.
+fn allow_local_addresses_from_env() -> bool {
+    env::var("ALLOW_LOCAL_ADDRESSES").map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false)
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize logger
@@ -13,9 +48,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse command line arguments
     let args: Vec<String> = env::args().collect();
 
+    // `--config <path>` loads an explicit node_id/address/peers mapping from a
+    // JSON file instead of relying on positional args, for environments where
+    // peer addresses aren't known (or stable) at launch time.
+    if args.len() >= 3 && args[1] == "--config" {
+        let config = ClusterConfig::load(std::path::Path::new(&args[2]))?;
+
+        info!("Starting Cloud Node {} from config file {}", config.node_id, args[2]);
+        info!("Address: {}", config.bind_address);
+        info!("Peers: {:?}", config.peers);
+
+        let node = Arc::new(CloudNode::new_with_options(
+            config.node_id,
+            config.bind_address,
+            config.peers,
+            allow_local_addresses_from_env(),
+        ));
+
+        if let Some((backend, interval_secs)) = discovery_backend_from_env() {
+            let node_clone = node.clone();
+            tokio::spawn(async move {
+                node_clone.run_discovery(backend, interval_secs).await;
+            });
+        }
+
+        node.start().await?;
+        return Ok(());
+    }
+
     if args.len() < 3 {
         eprintln!("Usage: {} <node_id> <bind_address> <peer_addresses>", args[0]);
-        eprintln!("Example (local):      {} 1 127.0.0.1:8001 127.0.0.1:8002,127.0.0.1:8003", args[0]);
+        eprintln!("       {} --config <path-to-cluster-config.json>", args[0]);
+        eprintln!("Example (local):      ALLOW_LOCAL_ADDRESSES=1 {} 1 127.0.0.1:8001 127.0.0.1:8002,127.0.0.1:8003", args[0]);
         eprintln!("Example (multi-device): {} 1 0.0.0.0:8001 192.168.1.11:8002,192.168.1.12:8003", args[0]);
         std::process::exit(1);
     }
@@ -43,7 +107,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Peers: {:?}", peer_addresses);
 
     // Create and start the node
-    let node = Arc::new(CloudNode::new(node_id, my_address, peer_addresses));
+    let node = Arc::new(CloudNode::new_with_options(
+        node_id,
+        my_address,
+        peer_addresses,
+        allow_local_addresses_from_env(),
+    ));
+
+    if let Some((backend, interval_secs)) = discovery_backend_from_env() {
+        let node_clone = node.clone();
+        tokio::spawn(async move {
+            node_clone.run_discovery(backend, interval_secs).await;
+        });
+    }
 
     node.start().await?;
 
@@ -55,7 +55,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
-        let node = Arc::new(CloudNode::new(node_id, address.clone(), peer_addresses));
+        // This demo runs every node as 127.0.0.1:800x on one machine, so
+        // local/loopback addresses must be explicitly allowed.
+        let node = Arc::new(CloudNode::new_with_options(node_id, address.clone(), peer_addresses, true));
         let node_clone = node.clone();
 
         let handle = tokio::spawn(async move {
@@ -85,12 +87,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     for (node, _) in &node_handles {
         let stats = node.get_stats().await;
         println!(
-            "Node {}: {} | Load: {:.2} | Queue: {} | Coordinator: {}",
+            "Node {}: {} | Load: {:.2} | Queue: {} | Coordinator: {} (term {}) | Reachable peers: {}",
             stats.id,
             stats.state,
             stats.load,
             stats.queue_length,
-            if stats.is_coordinator { "YES" } else { "NO" }
+            if stats.is_coordinator { "YES" } else { "NO" },
+            stats.coordinator_term,
+            format_peer_status(&stats.peer_status),
         );
     }
     println!("{:-<60}", "");
@@ -120,7 +124,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         loop {
             sleep(Duration::from_secs(5)).await;
 
-            let m = metrics_monitor.lock().await;
+            let m = metrics_monitor.read().await;
             let current_count = m.total_requests;
 
             if current_count > last_count {
@@ -157,18 +161,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     for (node, _) in &node_handles {
         let stats = node.get_stats().await;
         println!(
-            "Node {}: {} | Load: {:.2} | Processed: {} | Coordinator: {}",
+            "Node {}: {} | Load: {:.2} | Processed: {} | Coordinator: {} (term {}) | Reachable peers: {}",
             stats.id,
             stats.state,
             stats.load,
             stats.processed_requests,
-            if stats.is_coordinator { "YES" } else { "NO" }
+            if stats.is_coordinator { "YES" } else { "NO" },
+            stats.coordinator_term,
+            format_peer_status(&stats.peer_status),
         );
     }
     println!("{:-<60}", "");
 
     // Print metrics summary
-    let final_metrics = metrics.lock().await;
+    let final_metrics = metrics.read().await;
     final_metrics.print_summary();
 
     println!();
@@ -197,7 +203,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         for (node, _) in &node_handles {
             let stats = node.get_stats().await;
             println!(
-                "  Node {}: {} | Load: {:.2} | {}",
+                "  Node {}: {} | Load: {:.2} | {} | Term: {} | Reachable peers: {}",
                 stats.id,
                 stats.state,
                 stats.load,
@@ -205,7 +211,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     "[COORDINATOR]"
                 } else {
                     ""
-                }
+                },
+                stats.coordinator_term,
+                format_peer_status(&stats.peer_status),
             );
         }
         println!();
@@ -219,3 +227,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Render a node's per-peer connection status as e.g. "1, 2 (3 down)", so
+/// the demo's status printouts show which peers it currently considers
+/// reachable without dumping the raw (NodeId, bool) pairs.
+fn format_peer_status(peer_status: &[(u32, bool)]) -> String {
+    if peer_status.is_empty() {
+        return "none known".to_string();
+    }
+
+    let mut up: Vec<u32> = peer_status.iter().filter(|(_, ok)| *ok).map(|(id, _)| *id).collect();
+    let mut down: Vec<u32> = peer_status.iter().filter(|(_, ok)| !*ok).map(|(id, _)| *id).collect();
+    up.sort_unstable();
+    down.sort_unstable();
+
+    if down.is_empty() {
+        up.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+    } else {
+        format!(
+            "{} ({} down)",
+            up.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
+            down.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+        )
+    }
+}
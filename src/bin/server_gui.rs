@@ -3,15 +3,25 @@ use eframe::egui;
 use std::env;
 
 fn main() -> Result<(), eframe::Error> {
-    env_logger::init();
+    // Logging is initialized inside `ServerMonitorApp::new` via
+    // `log_bridge::install`, which installs a logger that both prints to
+    // stdout (like a plain `env_logger::init()`) and mirrors every record
+    // into the Logs tab.
 
     // Parse command line arguments for optional node ID (for display only)
+    // and an optional admin HTTP port (`--admin-port <PORT>`) that serves
+    // the cluster status as JSON for external tooling.
     let args: Vec<String> = env::args().collect();
     let node_id: Option<u32> = if args.len() > 1 {
         args[1].parse().ok()
     } else {
         None
     };
+    let admin_port: Option<u16> = args
+        .iter()
+        .position(|a| a == "--admin-port")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|p| p.parse().ok());
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -33,6 +43,10 @@ fn main() -> Result<(), eframe::Error> {
                 app.set_monitored_node_id(id);
             }
 
+            if let Some(port) = admin_port {
+                app.start_admin_server(port);
+            }
+
             Ok(Box::new(app))
         }),
     )
@@ -1,4 +1,5 @@
-use distributed_image_cloud::encryption::{encrypt_image, decrypt_image};
+use distributed_image_cloud::encryption::{encrypt_image, decrypt_image, DEFAULT_BIT_PLANES};
+use distributed_image_cloud::identity::{self, NodeIdentity};
 use std::fs;
 use std::path::Path;
 
@@ -54,10 +55,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Viewing quota: {}", quota);
     println!();
 
+    // A throwaway identity is enough here - this harness just exercises the
+    // encrypt/decrypt round trip, not cluster membership.
+    let identity = NodeIdentity::load_or_generate(&identity::default_identity_path("test_encryption"))?;
+
     // Encrypt the image
     println!("   🔄 Encrypting image...");
     let start = std::time::Instant::now();
-    let encrypted_data = match encrypt_image(original_data.clone(), usernames.clone(), quota).await {
+    let encrypted_data = match encrypt_image(original_data.clone(), usernames.clone(), quota, &identity, DEFAULT_BIT_PLANES).await {
         Ok(data) => data,
         Err(e) => {
             eprintln!("   ❌ Encryption failed: {}", e);
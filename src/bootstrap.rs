@@ -0,0 +1,208 @@
+use crate::messages::NodeId;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Explicit `NodeId -> address` mapping loaded from a config file, so
+/// operators never have to rely on the positional peer-ID inference that
+/// `cloud_node`'s CLI parsing used to do.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ClusterConfig {
+    pub node_id: NodeId,
+    pub bind_address: String,
+    pub peers: HashMap<NodeId, String>,
+}
+
+impl ClusterConfig {
+    /// Parse a JSON config file in the same shape this struct derives,
+    /// consistent with how the rest of the crate already uses `serde_json`
+    /// for on-disk data rather than pulling in a separate config format.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse config file {}: {}", path.display(), e))
+    }
+}
+
+/// A backend that can be polled periodically to learn the current set of
+/// peer addresses, for environments where peer addresses aren't known at
+/// launch time (orchestrated deployments).
+#[async_trait::async_trait]
+pub trait DiscoveryBackend: Send + Sync {
+    async fn resolve(&self) -> Result<HashMap<NodeId, String>, String>;
+
+    /// Human-readable name, for logging which backend produced a given update.
+    fn name(&self) -> &'static str;
+}
+
+/// Polls a Consul catalog endpoint for healthy instances of a service name
+/// and maps them onto `NodeId`s positionally by catalog order (Consul itself
+/// has no notion of our integer `NodeId`, so this is best-effort like the
+/// old CLI parsing, but refreshed continuously instead of fixed at launch).
+pub struct ConsulDiscovery {
+    pub consul_addr: String,
+    pub service_name: String,
+}
+
+#[async_trait::async_trait]
+impl DiscoveryBackend for ConsulDiscovery {
+    async fn resolve(&self) -> Result<HashMap<NodeId, String>, String> {
+        let url = format!(
+            "http://{}/v1/health/service/{}?passing=true",
+            self.consul_addr, self.service_name
+        );
+
+        let body = reqwest::get(&url)
+            .await
+            .map_err(|e| format!("Consul catalog request failed: {}", e))?
+            .text()
+            .await
+            .map_err(|e| format!("Consul catalog response read failed: {}", e))?;
+
+        let entries: Vec<ConsulServiceEntry> =
+            serde_json::from_str(&body).map_err(|e| format!("Consul catalog parse failed: {}", e))?;
+
+        let mut peers = HashMap::new();
+        for (idx, entry) in entries.into_iter().enumerate() {
+            let address = format!("{}:{}", entry.service.address, entry.service.port);
+            peers.insert(idx as NodeId + 1, address);
+        }
+
+        Ok(peers)
+    }
+
+    fn name(&self) -> &'static str {
+        "consul"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsulServiceEntry {
+    #[serde(rename = "Service")]
+    service: ConsulServiceAddress,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsulServiceAddress {
+    #[serde(rename = "Address")]
+    address: String,
+    #[serde(rename = "Port")]
+    port: u16,
+}
+
+/// Looks up a Kubernetes Endpoints object for a service via the in-cluster
+/// API server, mapping ready pod IPs onto `NodeId`s positionally the same
+/// way `ConsulDiscovery` does.
+pub struct KubernetesDiscovery {
+    pub api_server: String,
+    pub namespace: String,
+    pub service_name: String,
+    pub bearer_token: String,
+}
+
+#[async_trait::async_trait]
+impl DiscoveryBackend for KubernetesDiscovery {
+    async fn resolve(&self) -> Result<HashMap<NodeId, String>, String> {
+        let url = format!(
+            "{}/api/v1/namespaces/{}/endpoints/{}",
+            self.api_server, self.namespace, self.service_name
+        );
+
+        let client = reqwest::Client::new();
+        let body = client
+            .get(&url)
+            .bearer_auth(&self.bearer_token)
+            .send()
+            .await
+            .map_err(|e| format!("Kubernetes endpoints request failed: {}", e))?
+            .text()
+            .await
+            .map_err(|e| format!("Kubernetes endpoints response read failed: {}", e))?;
+
+        let endpoints: K8sEndpoints =
+            serde_json::from_str(&body).map_err(|e| format!("Kubernetes endpoints parse failed: {}", e))?;
+
+        let mut peers = HashMap::new();
+        let mut idx: NodeId = 1;
+        for subset in endpoints.subsets {
+            for addr in subset.addresses {
+                let port = subset.ports.first().map(|p| p.port).unwrap_or(8000);
+                peers.insert(idx, format!("{}:{}", addr.ip, port));
+                idx += 1;
+            }
+        }
+
+        Ok(peers)
+    }
+
+    fn name(&self) -> &'static str {
+        "kubernetes"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct K8sEndpoints {
+    subsets: Vec<K8sSubset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct K8sSubset {
+    addresses: Vec<K8sAddress>,
+    ports: Vec<K8sPort>,
+}
+
+#[derive(Debug, Deserialize)]
+struct K8sAddress {
+    ip: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct K8sPort {
+    port: u16,
+}
+
+/// Poll a `DiscoveryBackend` on a fixed interval and feed the result into a
+/// callback (wired up by the caller to merge into the peering subsystem).
+pub async fn run_discovery_loop<F>(backend: Box<dyn DiscoveryBackend>, interval: std::time::Duration, mut on_update: F)
+where
+    F: FnMut(HashMap<NodeId, String>) + Send,
+{
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match backend.resolve().await {
+            Ok(peers) => {
+                info!("[{}] Discovery resolved {} peer(s)", backend.name(), peers.len());
+                on_update(peers);
+            }
+            Err(e) => {
+                warn!("[{}] Discovery poll failed: {}", backend.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cluster_config_round_trips_through_json() {
+        let mut peers = HashMap::new();
+        peers.insert(2, "10.0.0.2:8002".to_string());
+
+        let config = ClusterConfig {
+            node_id: 1,
+            bind_address: "10.0.0.1:8001".to_string(),
+            peers,
+        };
+
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: ClusterConfig = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.node_id, 1);
+        assert_eq!(parsed.peers.get(&2).unwrap(), "10.0.0.2:8002");
+    }
+}
@@ -1,34 +1,367 @@
 use crate::chunking::{ChunkedMessage, ChunkReassembler};
-use crate::messages::Message;
-use crate::metrics::MetricsCollector;
+use crate::compression::CompressionCodec;
+use crate::discovery::DiscoveryConfig;
+use crate::identity::{self, NodeIdentity};
+use crate::messages::{self, Message, NodeId};
+use crate::metrics::{MetricsCollector, StressTestMetrics};
 use crate::encryption;
+use crate::router;
+use crate::secure_session::{self, HandshakeState, Role, SessionReader, SessionWriter};
+use crate::user_directory;
 use log::{debug, error, info, warn};
 use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::UdpSocket;
+use tokio::runtime::Runtime;
+use tokio::sync::RwLock;
 use tokio::time::{sleep, Instant};
 
+/// How many of the queried cloud nodes to actually target per request, once
+/// load-weighted selection has picked out the least-loaded candidates.
+const ROUTING_FANOUT: usize = 2;
+
+/// Retry/backoff policy applied to a single node by `send_with_retry`
+/// before a client method fails over to the next address. Exponential
+/// backoff with jitter so a burst of retries across many client tasks
+/// don't all land on the node at the same instant.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub multiplier: f64,
+    /// Fraction (0.0-1.0) of each computed delay to randomly add or
+    /// subtract, e.g. `0.2` varies a 200ms delay between 160ms and 240ms.
+    pub jitter: f64,
+    /// How long to wait for a single attempt before treating it as failed
+    /// and moving on to the next retry (or the next address).
+    pub attempt_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            multiplier: 2.0,
+            jitter: 0.2,
+            attempt_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// An event pushed from `spawn_notification_listener`'s background task to
+/// the UI. `Connected`/`Disconnected` drive the "reconnecting" indicator;
+/// `NewImage` is what actually updates `received_images`.
+#[derive(Debug, Clone)]
+pub enum ClientEvent {
+    Connected,
+    Disconnected,
+    NewImage {
+        from_username: String,
+        image_id: String,
+        remaining_views: u32,
+    },
+}
+
+/// An established secure channel with one node (see `secure_session.rs`),
+/// cached per address the same way `negotiated` caches a codec.
+struct ClientPeerSession {
+    reader: SessionReader,
+    writer: SessionWriter,
+}
+
 /// Client that sends encryption requests to the cloud
 pub struct Client {
     pub id: usize,
     pub cloud_addresses: Vec<String>,
+    /// Addresses picked up from a discovery beacon since `start_discovery`
+    /// was called, if ever. Empty until then. See `effective_addresses`.
+    discovered: Arc<RwLock<Vec<String>>>,
+    /// Session tokens issued by `SessionRegisterResponse`, keyed by
+    /// username, presented back to nodes during `Handshake` to prove this
+    /// client actually owns the username it claims.
+    tokens: Arc<RwLock<HashMap<String, String>>>,
+    /// Codec already negotiated with a given node address, so repeat
+    /// requests to the same node don't re-handshake. Keyed by address
+    /// rather than node id since that's what `send_to_node` addresses by.
+    negotiated: Arc<RwLock<HashMap<String, CompressionCodec>>>,
+    /// Backoff/retry behavior applied by `send_with_retry`. Defaults to
+    /// `RetryPolicy::default()`; override with `with_retry_policy`.
+    retry_policy: RetryPolicy,
+    /// Addresses that exhausted `retry_policy` recently, mapped to when
+    /// their cooldown expires. Skipped by `healthy_addresses` until then.
+    unhealthy: Arc<RwLock<HashMap<String, Instant>>>,
+    /// This client's persistent static key (see `identity::NodeIdentity`),
+    /// presented during `ClientSecureHandshakeInit` and pinned server-side
+    /// to whatever username this client registers - see `ensure_secure_session`.
+    identity: Arc<NodeIdentity>,
+    /// Established per-node secure channels, keyed by address - the
+    /// client-side counterpart of `node::CloudNode`'s `secure_sessions`.
+    secure_sessions: Arc<RwLock<HashMap<String, ClientPeerSession>>>,
 }
 
 impl Client {
+    /// How long a node stays excluded from `healthy_addresses` after
+    /// exhausting `retry_policy` against it, before being given another
+    /// chance.
+    const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
     pub fn new(id: usize, cloud_addresses: Vec<String>) -> Self {
+        let identity_path = identity::default_client_identity_path(id);
+        let identity = match NodeIdentity::load_or_generate(&identity_path) {
+            Ok(identity) => identity,
+            Err(e) => {
+                warn!(
+                    "[Client {}] Failed to load/generate persistent identity ({}), using an ephemeral key",
+                    id, e
+                );
+                NodeIdentity { signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng) }
+            }
+        };
+        Self {
+            id,
+            cloud_addresses,
+            discovered: Arc::new(RwLock::new(Vec::new())),
+            tokens: Arc::new(RwLock::new(HashMap::new())),
+            negotiated: Arc::new(RwLock::new(HashMap::new())),
+            retry_policy: RetryPolicy::default(),
+            unhealthy: Arc::new(RwLock::new(HashMap::new())),
+            identity: Arc::new(identity),
+            secure_sessions: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Like `new`, but pre-seeds the session token for `username` so a
+    /// freshly constructed `Client` (e.g. one of `gui_client`'s per-request
+    /// ephemeral instances) can still handshake and compress without having
+    /// to re-register the session it already holds.
+    pub fn new_with_token(id: usize, cloud_addresses: Vec<String>, username: String, token: String) -> Self {
+        let mut tokens = HashMap::new();
+        tokens.insert(username, token);
+        let identity_path = identity::default_client_identity_path(id);
+        let identity = match NodeIdentity::load_or_generate(&identity_path) {
+            Ok(identity) => identity,
+            Err(e) => {
+                warn!(
+                    "[Client {}] Failed to load/generate persistent identity ({}), using an ephemeral key",
+                    id, e
+                );
+                NodeIdentity { signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng) }
+            }
+        };
         Self {
             id,
             cloud_addresses,
+            discovered: Arc::new(RwLock::new(Vec::new())),
+            tokens: Arc::new(RwLock::new(tokens)),
+            negotiated: Arc::new(RwLock::new(HashMap::new())),
+            retry_policy: RetryPolicy::default(),
+            unhealthy: Arc::new(RwLock::new(HashMap::new())),
+            identity: Arc::new(identity),
+            secure_sessions: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Register a session with a username
-    /// Returns Ok(()) if successful, Err with error message if username is taken
+    /// Override the default `RetryPolicy` used by `send_with_retry`.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Start refreshing this client's address list in the background from a
+    /// discovery beacon (see the `discovery` module) - a file or shell
+    /// command, rewritten periodically elsewhere with the cluster's current
+    /// membership, as an alternative to `mdns_discovery`'s LAN-multicast
+    /// browsing for environments (e.g. containers) multicast can't reach.
+    /// Takes `&self` rather than `&mut self` so it composes with `Client`
+    /// already being shared behind an `Arc` (e.g. `gateway::GatewayState`).
+    pub fn start_discovery(&self, config: DiscoveryConfig) {
+        crate::discovery::spawn_refresh_task(config, Arc::clone(&self.discovered));
+    }
+
+    /// The addresses this client currently considers live: the bootstrap
+    /// list it was constructed with, plus whatever `start_discovery` has
+    /// refreshed from the beacon since, deduplicated. Falls back to just
+    /// `cloud_addresses` when no discovery has been started (or none has
+    /// completed yet), so behavior is unchanged for callers that never call
+    /// `start_discovery`.
+    async fn effective_addresses(&self) -> Vec<String> {
+        let discovered = self.discovered.read().await;
+        if discovered.is_empty() {
+            return self.cloud_addresses.clone();
+        }
+
+        let mut addresses = self.cloud_addresses.clone();
+        for address in discovered.iter() {
+            if !addresses.contains(address) {
+                addresses.push(address.clone());
+            }
+        }
+        addresses
+    }
+
+    /// `effective_addresses`, minus any node currently serving out its
+    /// cooldown after exhausting `retry_policy` (see `send_with_retry`).
+    /// Expired cooldowns are pruned as a side effect.
+    async fn healthy_addresses(&self) -> Vec<String> {
+        let addresses = self.effective_addresses().await;
+        let now = Instant::now();
+        let mut unhealthy = self.unhealthy.write().await;
+        unhealthy.retain(|_, expiry| *expiry > now);
+        addresses.into_iter().filter(|a| !unhealthy.contains_key(a)).collect()
+    }
+
+    async fn mark_unhealthy(
+        client_id: usize,
+        unhealthy: &Arc<RwLock<HashMap<String, Instant>>>,
+        address: &str,
+    ) {
+        warn!(
+            "[Client {}] Marking {} unhealthy for {:?} after exhausting retries",
+            client_id, address, Self::UNHEALTHY_COOLDOWN
+        );
+        unhealthy.write().await.insert(address.to_string(), Instant::now() + Self::UNHEALTHY_COOLDOWN);
+    }
+
+    /// Send `message` to `address`, retrying with exponential backoff (plus
+    /// jitter) per `self.retry_policy` before giving up. A single dropped
+    /// UDP datagram no longer fails the whole request - only exhausting
+    /// every attempt does, at which point `address` is marked unhealthy so
+    /// callers iterating multiple addresses skip it for a cooldown window.
+    async fn send_with_retry(&self, address: &str, message: Message) -> Result<Message, String> {
+        Self::send_with_retry_as(self.id, address, message, &self.retry_policy, &self.unhealthy).await
+    }
+
+    /// Free-standing form of `send_with_retry` that doesn't borrow `&self`,
+    /// for use inside `tokio::spawn`ed tasks that need a `'static` future -
+    /// callers clone `self.retry_policy` (cheap - it's a handful of scalars)
+    /// and `Arc::clone(&self.unhealthy)` into the task instead.
+    async fn send_with_retry_as(
+        client_id: usize,
+        address: &str,
+        message: Message,
+        policy: &RetryPolicy,
+        unhealthy: &Arc<RwLock<HashMap<String, Instant>>>,
+    ) -> Result<Message, String> {
+        let mut delay = policy.base_delay;
+        let mut last_err = String::new();
+
+        for attempt in 1..=policy.max_attempts {
+            match tokio::time::timeout(policy.attempt_timeout, Self::send_to_node(client_id, address, message.clone()))
+                .await
+            {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(e)) => last_err = e,
+                Err(_) => last_err = format!("Timed out after {:?}", policy.attempt_timeout),
+            }
+
+            if attempt < policy.max_attempts {
+                let jitter_factor = 1.0 + rand::thread_rng().gen_range(-policy.jitter..=policy.jitter);
+                let backoff = delay.mul_f64(jitter_factor.max(0.0));
+                debug!(
+                    "[Client {}] Attempt {}/{} to {} failed ({}), retrying in {:?}",
+                    client_id, attempt, policy.max_attempts, address, last_err, backoff
+                );
+                sleep(backoff).await;
+                delay = delay.mul_f64(policy.multiplier);
+            }
+        }
+
+        Self::mark_unhealthy(client_id, unhealthy, address).await;
+        Err(format!("{} after {} attempt(s): {}", address, policy.max_attempts, last_err))
+    }
+
+    /// Establish (or reuse a cached) secure channel with `address`, proving
+    /// ownership of `username` by presenting `self.identity`'s static key -
+    /// the node pins that key to `username` on the first handshake it sees
+    /// (see `node::client_trusted_keys`), so a later handshake claiming the
+    /// same username with a different key is rejected as a spoof.
+    async fn ensure_secure_session(&self, address: &str, username: &str) -> Result<(), String> {
+        if self.secure_sessions.read().await.contains_key(address) {
+            return Ok(());
+        }
+
+        let (state, offer) = HandshakeState::begin(&self.identity);
+        let response = self
+            .send_with_retry(
+                address,
+                Message::ClientSecureHandshakeInit {
+                    client_username: username.to_string(),
+                    static_public: self.identity.public_key_bytes(),
+                    ephemeral_public: offer.ephemeral_public,
+                    signature: offer.signature,
+                },
+            )
+            .await?;
+
+        let Message::ClientSecureHandshakeAck { ephemeral_public, signature } = response else {
+            return Err(format!("{} did not complete the secure handshake", address));
+        };
+
+        let peer_message = secure_session::HandshakeMessage { ephemeral_public, signature };
+        let session = state
+            .finish(Role::Initiator, &self.identity.public_key_bytes(), &peer_message)
+            .map_err(|e| format!("Secure handshake with {} failed: {}", address, e))?;
+        let (reader, writer) = session.split();
+        self.secure_sessions.write().await.insert(address.to_string(), ClientPeerSession { reader, writer });
+        info!("[Client {}] Established secure channel with {}", self.id, address);
+        Ok(())
+    }
+
+    /// Like `send_with_retry`, but wraps `message` in a `ClientSecureEnvelope`
+    /// authenticated as `username`, establishing a secure session with
+    /// `address` on demand. For traffic that carries client credentials -
+    /// currently `SessionRegister`, the one this hardening targets (see
+    /// `ClientSecureHandshakeInit`'s doc comment) - so it can't be forged by
+    /// anyone without `self.identity`'s private key.
+    async fn send_secure_with_retry(&self, address: &str, username: &str, message: Message) -> Result<Message, String> {
+        self.ensure_secure_session(address, username).await?;
+
+        let plaintext = serde_json::to_vec(&message).map_err(|e| e.to_string())?;
+        let sealed = {
+            let mut sessions = self.secure_sessions.write().await;
+            let session = sessions
+                .get_mut(address)
+                .ok_or_else(|| format!("No secure session cached for {}", address))?;
+            session.writer.seal(&plaintext).map_err(|e| format!("Failed to seal message for {}: {}", address, e))?
+        };
+
+        let response = self
+            .send_with_retry(address, Message::ClientSecureEnvelope { client_username: username.to_string(), sealed })
+            .await?;
+
+        match response {
+            Message::ClientSecureEnvelope { sealed, .. } => {
+                let mut sessions = self.secure_sessions.write().await;
+                let session = sessions
+                    .get_mut(address)
+                    .ok_or_else(|| format!("No secure session cached for {}", address))?;
+                let plaintext =
+                    session.reader.open(&sealed).map_err(|e| format!("Failed to open response from {}: {}", address, e))?;
+                serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// Register a session with a username.
+    /// Returns the session token issued by the node on success (stashed in
+    /// `self.tokens` for later `Handshake`s) along with any images the
+    /// coordinator had queued for this username while it was offline (see
+    /// `Message::SessionRegisterResponse::pending_images`) - the caller
+    /// should treat each one as freshly delivered and unseen, the same as a
+    /// live `ImageNotification`. Err with error message if the username is
+    /// taken. Sent through `send_secure_with_retry` rather than plain
+    /// `send_with_retry` - this is the credential-bearing message the
+    /// client<->node secure channel exists to protect (see
+    /// `ClientSecureHandshakeInit`'s doc comment).
     pub async fn register_session(
         &self,
         client_id: String,
         username: String,
-    ) -> Result<(), String> {
+    ) -> Result<(String, Vec<crate::messages::ReceivedImageInfo>), String> {
         let message = Message::SessionRegister {
             client_id: client_id.clone(),
             username: username.clone(),
@@ -37,12 +370,17 @@ impl Client {
         info!("[Client {}] Registering username: {}", self.id, username);
 
         // Try to register with any available node
-        for address in &self.cloud_addresses {
-            match Self::send_to_node(self.id, address, message.clone()).await {
-                Ok(Message::SessionRegisterResponse { success, error }) => {
+        let addresses = self.healthy_addresses().await;
+        for address in &addresses {
+            match self.send_secure_with_retry(address, &username, message.clone()).await {
+                Ok(Message::SessionRegisterResponse { success, error, session_token, pending_images }) => {
                     if success {
+                        let token = session_token.ok_or_else(|| {
+                            "Registration succeeded but no session token was issued".to_string()
+                        })?;
+                        self.tokens.write().await.insert(username.clone(), token.clone());
                         info!("[Client {}] Successfully registered username: {}", self.id, username);
-                        return Ok(());
+                        return Ok((token, pending_images));
                     } else {
                         return Err(error.unwrap_or_else(|| "Registration failed".to_string()));
                     }
@@ -60,6 +398,43 @@ impl Client {
         Err("Failed to connect to any cloud node".to_string())
     }
 
+    /// Negotiate (or reuse a cached) compression codec with `address` for
+    /// `username`. A dropped or rejected handshake falls back to
+    /// `CompressionCodec::None` rather than failing the caller's actual
+    /// request - losing compression is fine, losing the request isn't.
+    async fn negotiate_codec(&self, address: &str, username: &str) -> CompressionCodec {
+        if let Some(codec) = self.negotiated.read().await.get(address) {
+            return *codec;
+        }
+
+        let session_token = self.tokens.read().await.get(username).cloned();
+        let message = Message::Handshake {
+            client_username: username.to_string(),
+            session_token,
+            supported_codecs: vec![CompressionCodec::Zstd, CompressionCodec::Deflate],
+        };
+
+        let codec = match self.send_with_retry(address, message).await {
+            Ok(Message::HandshakeResponse { accepted: true, codec, .. }) => codec,
+            Ok(Message::HandshakeResponse { accepted: false, error, .. }) => {
+                warn!(
+                    "[Client {}] Handshake with {} rejected: {}",
+                    self.id, address,
+                    error.unwrap_or_else(|| "unknown error".to_string())
+                );
+                CompressionCodec::None
+            }
+            Ok(_) => CompressionCodec::None,
+            Err(e) => {
+                warn!("[Client {}] Handshake with {} failed: {}", self.id, address, e);
+                CompressionCodec::None
+            }
+        };
+
+        self.negotiated.write().await.insert(address.to_string(), codec);
+        codec
+    }
+
     /// Unregister a session
     pub async fn unregister_session(&self, client_id: String, username: String) {
         let message = Message::SessionUnregister {
@@ -70,7 +445,7 @@ impl Client {
         info!("[Client {}] Unregistering username: {}", self.id, username);
 
         // Send to all nodes (fire and forget)
-        for address in &self.cloud_addresses {
+        for address in &self.effective_addresses().await {
             let address = address.clone();
             let message = message.clone();
             let id = self.id;
@@ -80,8 +455,41 @@ impl Client {
         }
     }
 
-    /// Send an encryption request by multicasting to all cloud nodes
-    /// Returns the first successful response
+    /// Ask every known cloud node for its current load via the same
+    /// `LoadQuery`/`LoadResponse` pair nodes use to poll each other during an
+    /// election, so client-side routing respects the same load picture the
+    /// election does. Nodes that don't respond (down, or mid-election) are
+    /// simply absent from the result rather than failing the whole query.
+    async fn query_loads(&self) -> HashMap<NodeId, (String, f64)> {
+        let mut handles = vec![];
+
+        for address in &self.healthy_addresses().await {
+            let address = address.clone();
+            let client_id = self.id;
+
+            handles.push(tokio::spawn(async move {
+                let message = Message::LoadQuery { from_node: 0 };
+                let response = Self::send_to_node(client_id, &address, message).await;
+                (address, response)
+            }));
+        }
+
+        let mut loads = HashMap::new();
+        for handle in handles {
+            if let Ok((address, Ok(Message::LoadResponse { node_id, load, .. }))) = handle.await {
+                loads.insert(node_id, (address, load));
+            }
+        }
+
+        loads
+    }
+
+    /// Send an encryption request, routing it to a load-weighted subset of
+    /// cloud nodes (least-loaded nodes most likely, but never guaranteed, to
+    /// be picked - see `router::select_nodes`) and returning the first
+    /// successful response. Falls back to multicasting every known node if
+    /// no load information could be gathered (e.g. a freshly started
+    /// cluster with no `LoadResponse`s yet).
     pub async fn send_encryption_request(
         &self,
         request_id: String,
@@ -90,27 +498,57 @@ impl Client {
         usernames: Vec<String>,
         quota: u32,
     ) -> Result<Message, String> {
-        let message = Message::EncryptionRequest {
-            request_id: request_id.clone(),
-            client_username,
-            image_data,
-            usernames,
-            quota,
-            forwarded: false,
+        let loads = self.query_loads().await;
+
+        let targets: Vec<String> = if loads.is_empty() {
+            debug!("[Client {}] No load info available, multicasting to all nodes", self.id);
+            self.healthy_addresses().await
+        } else {
+            let load_by_node: HashMap<NodeId, f64> = loads
+                .iter()
+                .map(|(&node_id, (_, load))| (node_id, *load))
+                .collect();
+            let selected = router::select_nodes(&load_by_node, ROUTING_FANOUT);
+            selected
+                .into_iter()
+                .filter_map(|node_id| loads.get(&node_id).map(|(address, _)| address.clone()))
+                .collect()
         };
 
-        debug!("[Client {}] Multicasting request: {}", self.id, request_id);
+        debug!("[Client {}] Routing request {} to {:?}", self.id, request_id, targets);
 
-        // Multicast to all cloud nodes
         let mut handles = vec![];
 
-        for address in &self.cloud_addresses {
-            let address = address.clone();
-            let message = message.clone();
+        for address in targets {
             let client_id = self.id;
+            let codec = self.negotiate_codec(&address, &client_username).await;
+            let compressed = match codec.compress(&image_data) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(
+                        "[Client {}] Failed to compress request {} for {}: {}",
+                        self.id, request_id, address, e
+                    );
+                    continue;
+                }
+            };
+
+            let message = Message::EncryptionRequest {
+                request_id: request_id.clone(),
+                client_username: client_username.clone(),
+                image_data: compressed,
+                usernames: usernames.clone(),
+                quota,
+                forwarded: false,
+                client_address: None,
+                codec,
+            };
+
+            let retry_policy = self.retry_policy.clone();
+            let unhealthy = Arc::clone(&self.unhealthy);
 
             let handle = tokio::spawn(async move {
-                Self::send_to_node(client_id, &address, message).await
+                Self::send_with_retry_as(client_id, &address, message, &retry_policy, &unhealthy).await
             });
 
             handles.push(handle);
@@ -126,7 +564,16 @@ impl Client {
         Err("All nodes failed to respond".to_string())
     }
 
-    /// Send message to a specific node
+    /// How long to wait for the next expected fragment before asking the
+    /// sender to retransmit whatever's missing.
+    const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(3);
+    /// Cap on retransmit rounds per transfer before giving up and surfacing
+    /// the failure to the caller (and from there, the UI).
+    const MAX_RETRANSMIT_ROUNDS: u32 = 5;
+
+    /// Send message to a specific node. Large outbound payloads (image data)
+    /// are fragmented the same way node responses already are, so arbitrarily
+    /// large images can traverse UDP instead of being capped at one datagram.
     async fn send_to_node(
         client_id: usize,
         address: &str,
@@ -141,37 +588,66 @@ impl Client {
             }
         };
 
-        // Serialize message
+        // Serialize and fragment the outbound message the same way the node
+        // fragments large responses
         let message_bytes = serde_json::to_vec(&message).map_err(|e| e.to_string())?;
-
-        // Check message size
-        if message_bytes.len() > 65507 {
-            return Err("Message exceeds UDP packet size limit".to_string());
+        let chunks = ChunkedMessage::fragment(message_bytes);
+
+        debug!("[Client {}] Sending {} chunk(s) to {}", client_id, chunks.len(), address);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let chunk_bytes = serde_json::to_vec(chunk).map_err(|e| e.to_string())?;
+            socket
+                .send_to(&chunk_bytes, address)
+                .await
+                .map_err(|e| format!("Send error: {}", e))?;
+
+            if i < chunks.len() - 1 {
+                sleep(Duration::from_millis(10)).await;
+            }
         }
 
-        // Send message
-        socket
-            .send_to(&message_bytes, address)
-            .await
-            .map_err(|e| format!("Send error: {}", e))?;
-
-        debug!("[Client {}] Sent {} bytes to {}", client_id, message_bytes.len(), address);
-
         // Create chunk reassembler for receiving response
         let mut reassembler = ChunkReassembler::new();
         let mut buffer = vec![0u8; 65535]; // Max UDP packet size
+        let mut response_chunk_id: Option<String> = None;
+        let mut retransmit_rounds = 0u32;
 
-        // Loop to receive all chunks
+        // Loop to receive all chunks, requesting retransmission of whatever's
+        // missing if we stall waiting for the rest of a transfer
         loop {
-            // Read response with timeout
-            let n = match tokio::time::timeout(Duration::from_secs(10), socket.recv_from(&mut buffer)).await
-            {
+            let n = match tokio::time::timeout(Self::FRAGMENT_TIMEOUT, socket.recv_from(&mut buffer)).await {
                 Ok(Ok((n, _))) => n,
                 Ok(Err(e)) => {
                     return Err(format!("Receive error: {}", e));
                 }
                 Err(_) => {
-                    return Err("Timeout waiting for response".to_string());
+                    // Stalled: ask the sender to resend whatever we're missing
+                    let Some(chunk_id) = &response_chunk_id else {
+                        return Err("Timeout waiting for response".to_string());
+                    };
+                    let missing = reassembler.missing_indices(chunk_id).unwrap_or_default();
+                    if missing.is_empty() || retransmit_rounds >= Self::MAX_RETRANSMIT_ROUNDS {
+                        return Err(format!(
+                            "Transfer {} failed after {} retransmit round(s)",
+                            chunk_id, retransmit_rounds
+                        ));
+                    }
+
+                    retransmit_rounds += 1;
+                    warn!(
+                        "[Client {}] Requesting retransmit of {} chunk(s) for {} (round {}/{})",
+                        client_id, missing.len(), chunk_id, retransmit_rounds, Self::MAX_RETRANSMIT_ROUNDS
+                    );
+                    let request = ChunkedMessage::RetransmitRequest {
+                        chunk_id: chunk_id.clone(),
+                        missing_indices: missing,
+                    };
+                    let request_bytes = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+                    socket
+                        .send_to(&request_bytes, address)
+                        .await
+                        .map_err(|e| format!("Retransmit request failed: {}", e))?;
+                    continue;
                 }
             };
 
@@ -184,6 +660,10 @@ impl Client {
                 Ok(chunked_message) => {
                     debug!("[Client {}] Received chunk from {}", client_id, address);
 
+                    if let Some(id) = chunked_message.chunk_id() {
+                        response_chunk_id = Some(id.to_string());
+                    }
+
                     // Process chunk through reassembler
                     if let Some(complete_data) = reassembler.process_chunk(chunked_message) {
                         debug!("[Client {}] All chunks received, reassembled {} bytes", client_id, complete_data.len());
@@ -223,8 +703,8 @@ impl Client {
         };
 
         // Try to check with any available node
-        for address in &self.cloud_addresses {
-            match Self::send_to_node(self.id, address, message.clone()).await {
+        for address in &self.healthy_addresses().await {
+            match self.send_with_retry(address, message.clone()).await {
                 Ok(Message::CheckUsernameAvailableResponse { is_available, .. }) => {
                     return Ok(is_available);
                 }
@@ -241,7 +721,10 @@ impl Client {
         Err("Failed to connect to any cloud node".to_string())
     }
 
-    /// Send an encrypted image to other users
+    /// Send an encrypted image to other users. Returns the image ID plus a
+    /// per-recipient delivery outcome (a recipient being offline is not a
+    /// failure: the share is queued server-side and delivered at their next
+    /// login, see `DeliveryState::Pending`).
     pub async fn send_image(
         &self,
         from_username: String,
@@ -249,24 +732,40 @@ impl Client {
         encrypted_image: Vec<u8>,
         max_views: u32,
         image_id: String,
-    ) -> Result<String, String> {
-        let message = Message::SendImage {
-            from_username: from_username.clone(),
-            to_usernames: to_usernames.clone(),
-            encrypted_image,
-            max_views,
-            image_id: image_id.clone(),
-        };
-
+    ) -> Result<(String, Vec<(String, crate::messages::DeliveryState)>), String> {
         info!("[Client {}] Sending image {} to {:?}", self.id, image_id, to_usernames);
+        // Computed over the uncompressed bytes so it's the same regardless of
+        // which codec ends up negotiated per-node - see `messages::checksum`.
+        let checksum = messages::checksum(&encrypted_image);
 
         // Try to send to any available node
-        for address in &self.cloud_addresses {
-            match Self::send_to_node(self.id, address, message.clone()).await {
-                Ok(Message::SendImageResponse { success, image_id, error }) => {
+        for address in &self.healthy_addresses().await {
+            let codec = self.negotiate_codec(address, &from_username).await;
+            let compressed = match codec.compress(&encrypted_image) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(
+                        "[Client {}] Failed to compress image {} for {}: {}",
+                        self.id, image_id, address, e
+                    );
+                    continue;
+                }
+            };
+            let message = Message::SendImage {
+                from_username: from_username.clone(),
+                to_usernames: to_usernames.clone(),
+                encrypted_image: compressed,
+                max_views,
+                image_id: image_id.clone(),
+                codec,
+                checksum: Some(checksum),
+            };
+
+            match self.send_with_retry(address, message).await {
+                Ok(Message::SendImageResponse { success, image_id, error, delivery }) => {
                     if success {
                         info!("[Client {}] Successfully sent image: {}", self.id, image_id);
-                        return Ok(image_id);
+                        return Ok((image_id, delivery));
                     } else {
                         return Err(error.unwrap_or_else(|| "Send failed".to_string()));
                     }
@@ -284,23 +783,33 @@ impl Client {
         Err("Failed to connect to any cloud node".to_string())
     }
 
-    /// Query received images for a username
+    /// Query one page of received images for a username, starting at
+    /// `offset` and returning at most `limit` entries plus whether more
+    /// pages remain, so the UI can page through large histories instead of
+    /// fetching everything at once.
     pub async fn query_received_images(
         &self,
         username: String,
-    ) -> Result<Vec<crate::messages::ReceivedImageInfo>, String> {
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<crate::messages::ReceivedImageInfo>, bool), String> {
         let message = Message::QueryReceivedImages {
             username: username.clone(),
+            offset,
+            limit,
         };
 
-        info!("[Client {}] Querying received images for: {}", self.id, username);
+        info!("[Client {}] Querying received images for: {} (offset: {}, limit: {})", self.id, username, offset, limit);
 
         // Try to query from any available node
-        for address in &self.cloud_addresses {
-            match Self::send_to_node(self.id, address, message.clone()).await {
-                Ok(Message::QueryReceivedImagesResponse { images }) => {
-                    info!("[Client {}] Found {} images for {}", self.id, images.len(), username);
-                    return Ok(images);
+        for address in &self.healthy_addresses().await {
+            match self.send_with_retry(address, message.clone()).await {
+                Ok(Message::QueryReceivedImagesResponse { images, has_more, error }) => {
+                    if let Some(error) = error {
+                        return Err(error);
+                    }
+                    info!("[Client {}] Found {} images for {} (has_more: {})", self.id, images.len(), username, has_more);
+                    return Ok((images, has_more));
                 }
                 Ok(_) => {
                     return Err("Unexpected response from server".to_string());
@@ -315,6 +824,132 @@ impl Client {
         Err("Failed to connect to any cloud node".to_string())
     }
 
+    /// Fetch the full contacts directory (every username ever registered,
+    /// plus whether each is currently online)
+    pub async fn query_directory(&self) -> Result<Vec<(String, bool)>, String> {
+        let message = Message::QueryDirectory;
+
+        for address in &self.healthy_addresses().await {
+            match self.send_with_retry(address, message.clone()).await {
+                Ok(Message::QueryDirectoryResponse { entries }) => {
+                    return Ok(entries);
+                }
+                Ok(_) => {
+                    return Err("Unexpected response from server".to_string());
+                }
+                Err(e) => {
+                    warn!("[Client {}] Failed to query directory from {}: {}", self.id, address, e);
+                    continue;
+                }
+            }
+        }
+
+        Err("Failed to connect to any cloud node".to_string())
+    }
+
+    /// Maintain a persistent subscription for real-time push notifications
+    /// (new shares, quota changes), reconnecting with exponential backoff if
+    /// the subscription drops. The UI drains `ClientEvent`s from the returned
+    /// receiver on its egui update loop and falls back to its normal polling
+    /// (`query_received_images`) whenever it hasn't seen a `Connected` event
+    /// recently.
+    pub fn spawn_notification_listener(
+        client_id: usize,
+        cloud_addresses: Vec<String>,
+        username: String,
+        runtime: &Arc<Runtime>,
+    ) -> std::sync::mpsc::Receiver<ClientEvent> {
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        runtime.spawn(async move {
+            const MAX_BACKOFF: Duration = Duration::from_secs(30);
+            let mut backoff = Duration::from_secs(1);
+
+            loop {
+                if let Err(e) =
+                    Self::run_notification_session(client_id, &cloud_addresses, &username, &tx).await
+                {
+                    warn!("[Client {}] Notification subscription ended: {}", client_id, e);
+                }
+
+                let _ = tx.send(ClientEvent::Disconnected);
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+
+        rx
+    }
+
+    /// Subscribe with the first reachable node, then listen until the
+    /// subscription stops producing anything (idle timeouts are fine and
+    /// don't count as a disconnect). The session ends on a socket bind
+    /// failure, when no node accepts the subscription, on a recv error, on
+    /// an empty datagram, or once the UI side has dropped its receiver.
+    async fn run_notification_session(
+        client_id: usize,
+        cloud_addresses: &[String],
+        username: &str,
+        tx: &std::sync::mpsc::Sender<ClientEvent>,
+    ) -> Result<(), String> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .map_err(|e| format!("Socket creation failed: {}", e))?;
+        let mut buffer = vec![0u8; 65535];
+
+        let mut subscribed_to = None;
+        for address in cloud_addresses {
+            let subscribe = Message::SubscribeNotifications {
+                username: username.to_string(),
+            };
+            let bytes = serde_json::to_vec(&subscribe).map_err(|e| e.to_string())?;
+            if socket.send_to(&bytes, address).await.is_err() {
+                continue;
+            }
+
+            match tokio::time::timeout(Duration::from_secs(3), socket.recv_from(&mut buffer)).await {
+                Ok(Ok((n, _))) => {
+                    if let Ok(Message::SubscribeNotificationsResponse { success: true }) =
+                        serde_json::from_slice(&buffer[..n])
+                    {
+                        subscribed_to = Some(address.clone());
+                        break;
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        let Some(address) = subscribed_to else {
+            return Err("No cloud node accepted the subscription".to_string());
+        };
+
+        info!("[Client {}] Subscribed to push notifications via {}", client_id, address);
+        let _ = tx.send(ClientEvent::Connected);
+
+        loop {
+            let n = match tokio::time::timeout(Duration::from_secs(120), socket.recv_from(&mut buffer)).await {
+                Ok(Ok((0, _))) => return Ok(()),
+                Ok(Ok((n, _))) => n,
+                Ok(Err(e)) => return Err(format!("Receive error: {}", e)),
+                Err(_) => continue, // idle timeout; subscription is still alive
+            };
+
+            if let Ok(Message::ImageNotification { from_username, image_id, remaining_views, .. }) =
+                serde_json::from_slice::<Message>(&buffer[..n])
+            {
+                debug!("[Client {}] Got push notification for image {}", client_id, image_id);
+                if tx
+                    .send(ClientEvent::NewImage { from_username, image_id, remaining_views })
+                    .is_err()
+                {
+                    // UI side dropped the receiver (app closing); stop listening
+                    return Ok(());
+                }
+            }
+        }
+    }
+
     /// View an image (decrements the view counter)
     pub async fn view_image(
         &self,
@@ -329,8 +964,8 @@ impl Client {
         info!("[Client {}] Viewing image {} for: {}", self.id, image_id, username);
 
         // Try to view from any available node
-        for address in &self.cloud_addresses {
-            match Self::send_to_node(self.id, address, message.clone()).await {
+        for address in &self.healthy_addresses().await {
+            match self.send_with_retry(address, message.clone()).await {
                 Ok(Message::ViewImageResponse {
                     success,
                     image_data,
@@ -378,6 +1013,98 @@ impl Client {
         Err("Failed to connect to any cloud node".to_string())
     }
 
+    /// Prove knowledge of `username`'s password against a fresh server
+    /// nonce, so a subsequent `query_received_images`/`view_image` against a
+    /// password-protected account succeeds (see `node::authenticated_sessions`).
+    /// A no-op that returns `Ok(())` if the account isn't password-protected.
+    pub async fn authenticate_password(&self, username: String, password: String) -> Result<(), String> {
+        for address in &self.healthy_addresses().await {
+            let response = self
+                .send_with_retry(address, Message::AuthChallenge { username: username.clone() })
+                .await;
+            let (nonce, salt) = match response {
+                Ok(Message::AuthChallengeResponse { nonce, salt }) => (nonce, salt),
+                Ok(_) => return Err("Unexpected response from server".to_string()),
+                Err(e) => {
+                    warn!("[Client {}] Failed to challenge {}: {}", self.id, address, e);
+                    continue;
+                }
+            };
+            let (nonce, salt) = match (nonce, salt) {
+                (Some(nonce), Some(salt)) => (nonce, salt),
+                _ => return Ok(()), // account isn't password-protected
+            };
+
+            let verifier = user_directory::derive_verifier(&password, &salt)?;
+            let proof = user_directory::derive_proof(&verifier, &nonce);
+            let message = Message::AuthProve { username: username.clone(), nonce, proof };
+
+            return match self.send_with_retry(address, message).await {
+                Ok(Message::AuthProveResponse { verified: true }) => Ok(()),
+                Ok(Message::AuthProveResponse { verified: false }) => Err("Incorrect password".to_string()),
+                Ok(_) => Err("Unexpected response from server".to_string()),
+                Err(e) => Err(format!("Failed to authenticate with {}: {}", address, e)),
+            };
+        }
+
+        Err("Failed to connect to any cloud node".to_string())
+    }
+
+    /// Set or change `username`'s password. When the account is already
+    /// protected, `old_password` must be the current password (proved the
+    /// same way `authenticate_password` does) or the node rejects the
+    /// change; pass an empty string for a not-yet-protected account.
+    pub async fn change_password(
+        &self,
+        username: String,
+        old_password: String,
+        new_password: String,
+    ) -> Result<(), String> {
+        for address in &self.healthy_addresses().await {
+            let (old_nonce, old_proof) = if old_password.is_empty() {
+                ([0u8; 16], [0u8; 32])
+            } else {
+                let response = self
+                    .send_with_retry(address, Message::AuthChallenge { username: username.clone() })
+                    .await;
+                match response {
+                    Ok(Message::AuthChallengeResponse { nonce: Some(nonce), salt: Some(salt) }) => {
+                        let verifier = user_directory::derive_verifier(&old_password, &salt)?;
+                        (nonce, user_directory::derive_proof(&verifier, &nonce))
+                    }
+                    Ok(Message::AuthChallengeResponse { .. }) => ([0u8; 16], [0u8; 32]),
+                    Ok(_) => return Err("Unexpected response from server".to_string()),
+                    Err(e) => {
+                        warn!("[Client {}] Failed to challenge {}: {}", self.id, address, e);
+                        continue;
+                    }
+                }
+            };
+
+            let mut new_salt = [0u8; 16];
+            rand::thread_rng().fill(&mut new_salt);
+            let new_verifier = user_directory::derive_verifier(&new_password, &new_salt)?;
+            let message = Message::ChangePassword {
+                username: username.clone(),
+                old_nonce,
+                old_proof,
+                new_salt,
+                new_verifier,
+            };
+
+            return match self.send_with_retry(address, message).await {
+                Ok(Message::ChangePasswordResponse { success: true, .. }) => Ok(()),
+                Ok(Message::ChangePasswordResponse { success: false, error }) => {
+                    Err(error.unwrap_or_else(|| "Password change rejected".to_string()))
+                }
+                Ok(_) => Err("Unexpected response from server".to_string()),
+                Err(e) => Err(format!("Failed to change password via {}: {}", address, e)),
+            };
+        }
+
+        Err("Failed to connect to any cloud node".to_string())
+    }
+
     /// Generate a random test image
     fn generate_test_image(size_kb: usize) -> Vec<u8> {
         let mut rng = rand::thread_rng();
@@ -430,6 +1157,21 @@ impl Client {
     }
 }
 
+/// Merge a finished client's metrics shard into the shared aggregate.
+/// Tries the non-blocking fast path first since, with many clients
+/// finishing close together, a plain `write().await` would queue them all
+/// up behind one another; only a genuinely contended merge falls back to
+/// waiting for the lock.
+async fn merge_shard(metrics: &MetricsCollector, shard: &StressTestMetrics) {
+    match metrics.try_write() {
+        Ok(mut aggregate) => aggregate.merge(shard),
+        Err(_) => {
+            let mut aggregate = metrics.write().await;
+            aggregate.merge(shard);
+        }
+    }
+}
+
 /// Run stress test with multiple concurrent clients
 pub async fn run_stress_test(
     num_clients: usize,
@@ -444,23 +1186,32 @@ pub async fn run_stress_test(
         num_clients * requests_per_client
     );
 
+    // Share whatever sink is already attached with every per-client shard so
+    // live streaming keeps working even though requests are no longer
+    // recorded straight onto the shared aggregate.
+    let sink = metrics.read().await.sink();
+
     let mut handles = vec![];
 
     for client_id in 0..num_clients {
         let cloud_addresses = cloud_addresses.clone();
         let metrics = metrics.clone();
+        let sink = sink.clone();
 
         let handle = tokio::spawn(async move {
             let client = Client::new(client_id, cloud_addresses);
 
+            // Each client accumulates into its own shard with no shared
+            // locking in the hot loop, so `num_clients` concurrent clients
+            // never serialize through one mutex on every request.
+            let mut shard = StressTestMetrics::new();
+            if let Some(sink) = sink {
+                shard.set_sink(sink);
+            }
+
             for req_num in 0..requests_per_client {
                 let (success, duration) = client.run_test_request(req_num).await;
-
-                // Record metrics
-                {
-                    let mut m = metrics.lock().await;
-                    m.record_request(success, duration);
-                }
+                shard.record_request(success, duration);
 
                 // Small delay between requests to simulate realistic behavior
                 if req_num < requests_per_client - 1 {
@@ -468,6 +1219,8 @@ pub async fn run_stress_test(
                 }
             }
 
+            merge_shard(&metrics, &shard).await;
+
             info!("[Client {}] Completed all {} requests", client_id, requests_per_client);
         });
 
@@ -486,7 +1239,7 @@ pub async fn run_stress_test(
 
     // Mark test as finished
     {
-        let mut m = metrics.lock().await;
+        let mut m = metrics.write().await;
         m.finish();
     }
 
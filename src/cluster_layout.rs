@@ -0,0 +1,294 @@
+//! Capacity- and zone-aware data placement, replacing ad-hoc
+//! `stored_images` storage with an explicit partition-assignment table.
+//!
+//! Each node advertises a `NodeCapability` (relative processing capacity and
+//! an availability-zone tag). `ClusterLayout::compute` hashes the id space
+//! into `NUM_PARTITIONS` partitions and assigns each one to `REPLICATION_FACTOR`
+//! distinct nodes, preferring one replica per zone so a single zone outage
+//! can't take out every copy of an image. It's deterministic in the same way
+//! `rapid_membership::ExpanderTopology` is: the same capability map always
+//! produces the same assignment, so independently recomputing it on every
+//! node after a committed membership change keeps everyone's layout in sync
+//! without having to replicate the table itself.
+
+use crate::messages::NodeId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+/// Default number of partitions the image id space is split into.
+pub const NUM_PARTITIONS: usize = 64;
+
+/// Default number of distinct nodes each partition is replicated to.
+pub const REPLICATION_FACTOR: usize = 3;
+
+/// What a node advertises about itself for placement purposes: a relative
+/// processing capacity (a node with `capacity: 2.0` is treated as being able
+/// to carry twice the load of one with `capacity: 1.0`) and the
+/// availability zone it runs in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeCapability {
+    pub capacity: f64,
+    pub zone: String,
+}
+
+impl Default for NodeCapability {
+    fn default() -> Self {
+        Self { capacity: 1.0, zone: "default".to_string() }
+    }
+}
+
+/// Load normalized by a node's advertised capacity, so a powerful node
+/// running at the same absolute load as a weaker one is correctly seen as
+/// less loaded. Used in place of raw load wherever nodes are compared for
+/// coordinator selection. `capacity <= 0.0` is treated as the default 1.0
+/// rather than producing infinity/NaN from a misconfigured node.
+pub fn normalized_load(load: f64, capacity: f64) -> f64 {
+    let capacity = if capacity > 0.0 { capacity } else { 1.0 };
+    load / capacity
+}
+
+/// What a node advertises about its slice of the simple modulo-sharded
+/// keyspace used for cross-node chunk/image fetches (`CloudNode::
+/// shard_aware_candidates`) - independent of the capacity/zone-aware
+/// `ClusterLayout` above, which governs durable replica placement rather
+/// than "who's worth asking for this image right now". Defaults to a
+/// single shard covering the whole keyspace, so a node that never calls
+/// `set_shard_config` behaves as if sharding isn't in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardConfig {
+    pub num_shards: usize,
+    pub shard_id: usize,
+}
+
+impl Default for ShardConfig {
+    fn default() -> Self {
+        Self { num_shards: 1, shard_id: 0 }
+    }
+}
+
+impl ShardConfig {
+    /// Whether this shard is responsible for `image_id` under modulo
+    /// sharding: `hash(image_id) % num_shards == shard_id`.
+    pub fn covers(&self, image_id: &str) -> bool {
+        self.num_shards > 0 && partition_for_image(image_id, self.num_shards) == self.shard_id
+    }
+}
+
+/// Which partition an image id hashes into, out of `num_partitions`.
+pub fn partition_for_image(image_id: &str, num_partitions: usize) -> usize {
+    if num_partitions == 0 {
+        return 0;
+    }
+    let mut hasher = DefaultHasher::new();
+    image_id.hash(&mut hasher);
+    (hasher.finish() % num_partitions as u64) as usize
+}
+
+/// A versioned partition -> replica-set assignment. `version` increments
+/// every time `compute` runs against a changed capability map, so a node can
+/// tell from a peer's `layout_version` alone whether its own table is stale.
+#[derive(Debug, Clone)]
+pub struct ClusterLayout {
+    version: u64,
+    partitions: Vec<Vec<NodeId>>,
+}
+
+impl ClusterLayout {
+    /// Build a fresh assignment from scratch. Zones are considered in
+    /// sorted order and, within a zone, nodes are considered highest-capacity
+    /// first, so the result is fully determined by the arguments -
+    /// two nodes computing this from the same capability map always agree.
+    pub fn compute(
+        capabilities: &HashMap<NodeId, NodeCapability>,
+        replicas: usize,
+        num_partitions: usize,
+        version: u64,
+    ) -> Self {
+        if capabilities.is_empty() || num_partitions == 0 {
+            return Self { version, partitions: vec![Vec::new(); num_partitions] };
+        }
+
+        let mut zones: BTreeMap<&str, Vec<NodeId>> = BTreeMap::new();
+        for (&id, cap) in capabilities {
+            zones.entry(cap.zone.as_str()).or_default().push(id);
+        }
+        for nodes in zones.values_mut() {
+            nodes.sort_by(|a, b| {
+                capabilities[b]
+                    .capacity
+                    .partial_cmp(&capabilities[a].capacity)
+                    .unwrap()
+                    .then_with(|| a.cmp(b))
+            });
+        }
+        let zone_names: Vec<&str> = zones.keys().copied().collect();
+        let replicas = replicas.min(capabilities.len()).max(1);
+
+        let partitions =
+            (0..num_partitions).map(|p| Self::assign_partition(p, &zone_names, &zones, replicas)).collect();
+
+        Self { version, partitions }
+    }
+
+    /// Assign one partition's replica set: round `0` takes at most one node
+    /// per zone (rotating which zone goes first by `p`, so replica-1 load
+    /// spreads evenly across zones instead of always favoring the same
+    /// one), only spilling into a second node from the same zone once every
+    /// zone has contributed a replica - i.e. "never place two replicas in
+    /// the same zone while capacity allows".
+    fn assign_partition(
+        p: usize,
+        zone_names: &[&str],
+        zones: &BTreeMap<&str, Vec<NodeId>>,
+        replicas: usize,
+    ) -> Vec<NodeId> {
+        let mut assigned = Vec::with_capacity(replicas);
+        let zone_count = zone_names.len();
+
+        for round in 0.. {
+            if assigned.len() >= replicas {
+                break;
+            }
+            let mut made_progress = false;
+            for offset in 0..zone_count {
+                if assigned.len() >= replicas {
+                    break;
+                }
+                let zone = zone_names[(p + offset) % zone_count];
+                let nodes = &zones[zone];
+                if round >= nodes.len() {
+                    continue;
+                }
+                let candidate = nodes[(p + round) % nodes.len()];
+                if !assigned.contains(&candidate) {
+                    assigned.push(candidate);
+                    made_progress = true;
+                }
+            }
+            if !made_progress {
+                break;
+            }
+        }
+
+        assigned
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Replica set for a partition index, or `&[]` if it's out of range
+    /// (e.g. the layout was computed with fewer partitions than asked for).
+    pub fn nodes_for_partition(&self, partition: usize) -> &[NodeId] {
+        self.partitions.get(partition).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Replica set for an image id, via `partition_for_image`.
+    pub fn nodes_for_image(&self, image_id: &str) -> &[NodeId] {
+        self.nodes_for_partition(partition_for_image(image_id, self.partitions.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capability_map(entries: &[(NodeId, f64, &str)]) -> HashMap<NodeId, NodeCapability> {
+        entries
+            .iter()
+            .map(|&(id, capacity, zone)| (id, NodeCapability { capacity, zone: zone.to_string() }))
+            .collect()
+    }
+
+    #[test]
+    fn normalized_load_divides_by_capacity() {
+        assert_eq!(normalized_load(10.0, 2.0), 5.0);
+        assert_eq!(normalized_load(10.0, 0.0), 10.0); // invalid capacity falls back to 1.0
+    }
+
+    #[test]
+    fn compute_is_deterministic_for_the_same_capability_map() {
+        let caps = capability_map(&[(1, 1.0, "us-east"), (2, 1.0, "us-west"), (3, 1.0, "eu")]);
+        let a = ClusterLayout::compute(&caps, 3, 8, 1);
+        let b = ClusterLayout::compute(&caps, 3, 8, 1);
+        for p in 0..8 {
+            assert_eq!(a.nodes_for_partition(p), b.nodes_for_partition(p));
+        }
+    }
+
+    #[test]
+    fn prefers_one_replica_per_zone_when_enough_zones_exist() {
+        let caps = capability_map(&[
+            (1, 1.0, "us-east"),
+            (2, 1.0, "us-west"),
+            (3, 1.0, "eu"),
+            (4, 1.0, "us-east"),
+        ]);
+        let layout = ClusterLayout::compute(&caps, 3, NUM_PARTITIONS, 1);
+        let zone_of = |id: NodeId| caps[&id].zone.clone();
+
+        for p in 0..NUM_PARTITIONS {
+            let replicas = layout.nodes_for_partition(p);
+            assert_eq!(replicas.len(), 3);
+            let mut zones: Vec<String> = replicas.iter().map(|&id| zone_of(id)).collect();
+            zones.sort();
+            zones.dedup();
+            assert_eq!(zones.len(), 3, "partition {} put two replicas in the same zone: {:?}", p, replicas);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_repeating_a_zone_when_there_arent_enough_zones() {
+        let caps = capability_map(&[(1, 2.0, "only-zone"), (2, 1.0, "only-zone"), (3, 1.0, "only-zone")]);
+        let layout = ClusterLayout::compute(&caps, 3, 4, 1);
+        for p in 0..4 {
+            assert_eq!(layout.nodes_for_partition(p).len(), 3);
+        }
+    }
+
+    #[test]
+    fn replica_count_is_capped_at_known_node_count() {
+        let caps = capability_map(&[(1, 1.0, "a"), (2, 1.0, "b")]);
+        let layout = ClusterLayout::compute(&caps, 5, 4, 1);
+        for p in 0..4 {
+            assert_eq!(layout.nodes_for_partition(p).len(), 2);
+        }
+    }
+
+    #[test]
+    fn nodes_for_image_is_stable_for_the_same_id() {
+        let caps = capability_map(&[(1, 1.0, "a"), (2, 1.0, "b"), (3, 1.0, "c")]);
+        let layout = ClusterLayout::compute(&caps, 2, NUM_PARTITIONS, 1);
+        assert_eq!(layout.nodes_for_image("photo-42"), layout.nodes_for_image("photo-42"));
+    }
+
+    #[test]
+    fn empty_capability_map_yields_empty_partitions() {
+        let layout = ClusterLayout::compute(&HashMap::new(), REPLICATION_FACTOR, NUM_PARTITIONS, 0);
+        assert!(layout.nodes_for_partition(0).is_empty());
+    }
+
+    #[test]
+    fn default_shard_config_covers_everything() {
+        let config = ShardConfig::default();
+        assert!(config.covers("photo-1"));
+        assert!(config.covers("photo-2"));
+    }
+
+    #[test]
+    fn shard_config_covers_exactly_the_images_that_hash_to_its_shard_id() {
+        let shard_id = partition_for_image("photo-42", 4);
+        let config = ShardConfig { num_shards: 4, shard_id };
+        assert!(config.covers("photo-42"));
+
+        let other_config = ShardConfig { num_shards: 4, shard_id: (shard_id + 1) % 4 };
+        assert!(!other_config.covers("photo-42"));
+    }
+
+    #[test]
+    fn zero_shards_covers_nothing() {
+        let config = ShardConfig { num_shards: 0, shard_id: 0 };
+        assert!(!config.covers("photo-1"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Compression codecs negotiated during the client/node handshake (see
+//! `Message::Handshake`) and applied to `EncryptionRequest`/`SendImage`
+//! payloads before chunking, so large `image_data`/`encrypted_image`
+//! transfers don't waste bandwidth over the UDP/chunked path.
+
+use serde::{Deserialize, Serialize};
+
+/// A compression codec both sides understand. `None` is always accepted, so
+/// negotiation can never fail outright - only fall back to no compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionCodec {
+    None,
+    Deflate,
+    Zstd,
+}
+
+impl CompressionCodec {
+    /// Pick the strongest codec the node is willing to use out of what the
+    /// client proposed, preferring `Zstd` over `Deflate` over `None`.
+    pub fn negotiate(proposed: &[CompressionCodec]) -> CompressionCodec {
+        for candidate in [CompressionCodec::Zstd, CompressionCodec::Deflate] {
+            if proposed.contains(&candidate) {
+                return candidate;
+            }
+        }
+        CompressionCodec::None
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        match self {
+            CompressionCodec::None => Ok(data.to_vec()),
+            CompressionCodec::Deflate => {
+                use flate2::write::DeflateEncoder;
+                use flate2::Compression;
+                use std::io::Write;
+
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(data)
+                    .map_err(|e| format!("Deflate compression failed: {}", e))?;
+                encoder
+                    .finish()
+                    .map_err(|e| format!("Deflate compression failed: {}", e))
+            }
+            CompressionCodec::Zstd => {
+                zstd::stream::encode_all(data, 0).map_err(|e| format!("Zstd compression failed: {}", e))
+            }
+        }
+    }
+
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        match self {
+            CompressionCodec::None => Ok(data.to_vec()),
+            CompressionCodec::Deflate => {
+                use flate2::read::DeflateDecoder;
+                use std::io::Read;
+
+                let mut decoder = DeflateDecoder::new(data);
+                let mut out = Vec::new();
+                decoder
+                    .read_to_end(&mut out)
+                    .map_err(|e| format!("Deflate decompression failed: {}", e))?;
+                Ok(out)
+            }
+            CompressionCodec::Zstd => {
+                zstd::stream::decode_all(data).map_err(|e| format!("Zstd decompression failed: {}", e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_every_codec() {
+        let data = b"the quick brown fox jumps over the lazy dog".repeat(50);
+
+        for codec in [CompressionCodec::None, CompressionCodec::Deflate, CompressionCodec::Zstd] {
+            let compressed = codec.compress(&data).unwrap();
+            let decompressed = codec.decompress(&compressed).unwrap();
+            assert_eq!(decompressed, data, "round trip failed for {:?}", codec);
+        }
+    }
+
+    #[test]
+    fn negotiate_prefers_zstd_then_deflate_then_none() {
+        assert_eq!(
+            CompressionCodec::negotiate(&[CompressionCodec::None, CompressionCodec::Deflate, CompressionCodec::Zstd]),
+            CompressionCodec::Zstd
+        );
+        assert_eq!(
+            CompressionCodec::negotiate(&[CompressionCodec::None, CompressionCodec::Deflate]),
+            CompressionCodec::Deflate
+        );
+        assert_eq!(CompressionCodec::negotiate(&[CompressionCodec::None]), CompressionCodec::None);
+        assert_eq!(CompressionCodec::negotiate(&[]), CompressionCodec::None);
+    }
+}
@@ -0,0 +1,20 @@
+use log::warn;
+
+/// Fire a native desktop notification for a newly received image, best
+/// effort: a missing notification daemon (common in headless/CI
+/// environments) just logs a warning rather than failing the caller.
+pub fn notify_new_image(from_username: &str, image_id: &str) {
+    let summary = format!("New image from {}", from_username);
+    let body = format!("Image {} is ready to view", image_id);
+
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(&summary).body(&body);
+
+    if let Some(icon_path) = crate::avatar::avatar_icon_path(from_username) {
+        notification.icon(&icon_path.to_string_lossy());
+    }
+
+    if let Err(e) = notification.show() {
+        warn!("Failed to show desktop notification: {}", e);
+    }
+}
@@ -0,0 +1,198 @@
+//! Dynamic node discovery via a gossiped "beacon": a small, periodically
+//! rewritten text file (or the stdout of a shell command) listing the
+//! cluster's currently-known live node addresses. `Client` polls this on a
+//! background task (see `Client::start_discovery`) so its address list can
+//! change at runtime instead of requiring a restart whenever a node joins
+//! or leaves.
+
+use log::{debug, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+const BEACON_BEGIN: &str = "---CLOUD-BEACON-BEGIN---";
+const BEACON_END: &str = "---CLOUD-BEACON-END---";
+
+/// Where a beacon is read from.
+#[derive(Debug, Clone)]
+pub enum BeaconSource {
+    /// Read the beacon from a file on disk, written by `write_beacon`
+    /// (typically by a node periodically publishing its membership table).
+    File(PathBuf),
+    /// Run a shell command and parse its stdout as a beacon, for beacons
+    /// published somewhere a plain file read can't reach (e.g. fetched from
+    /// an object store or a peer over SSH by a wrapper script).
+    ShellCommand(String),
+}
+
+/// How a `Client` should refresh its address list from a beacon.
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    pub source: BeaconSource,
+    /// How often to poll the beacon for changes.
+    pub refresh_interval: Duration,
+    /// Beacons older than this are treated as stale and rejected, rather
+    /// than trusting addresses that might no longer be accurate.
+    pub ttl: Duration,
+}
+
+/// Write a beacon listing `addresses`, timestamped with the current time,
+/// for discovery clients to later read and validate against their `ttl`.
+/// Intended to be called periodically by a node publishing its own live
+/// peer list (e.g. `MembershipTable::addresses()`).
+pub fn write_beacon(path: &Path, addresses: &[(u32, String)]) -> std::io::Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut text = String::new();
+    text.push_str(BEACON_BEGIN);
+    text.push('\n');
+    text.push_str(&now.to_string());
+    text.push('\n');
+    for (id, addr) in addresses {
+        text.push_str(&format!("{},{}\n", id, addr));
+    }
+    text.push_str(BEACON_END);
+    text.push('\n');
+
+    std::fs::write(path, text)
+}
+
+/// Parse a beacon's text, returning its addresses if it's well-formed and
+/// not older than `ttl`.
+fn parse_beacon(text: &str, ttl: Duration) -> Result<Vec<String>, String> {
+    let begin = text.find(BEACON_BEGIN).ok_or("Missing beacon begin delimiter")?;
+    let end = text.find(BEACON_END).ok_or("Missing beacon end delimiter")?;
+    let body = &text[begin + BEACON_BEGIN.len()..end];
+
+    let mut lines = body.lines().map(str::trim).filter(|l| !l.is_empty());
+    let timestamp: u64 = lines
+        .next()
+        .ok_or("Beacon missing timestamp line")?
+        .parse()
+        .map_err(|e| format!("Invalid beacon timestamp: {}", e))?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let age = Duration::from_secs(now.saturating_sub(timestamp));
+    if age > ttl {
+        return Err(format!(
+            "Beacon is {}s old, older than the {}s TTL",
+            age.as_secs(),
+            ttl.as_secs()
+        ));
+    }
+
+    let mut addresses = Vec::new();
+    for line in lines {
+        let (_, addr) = line
+            .split_once(',')
+            .ok_or_else(|| format!("Malformed beacon entry: {}", line))?;
+        addresses.push(addr.to_string());
+    }
+
+    Ok(addresses)
+}
+
+/// Load the current address list from `config.source`, applying `config.ttl`.
+async fn load_once(config: &DiscoveryConfig) -> Result<Vec<String>, String> {
+    let text = match &config.source {
+        BeaconSource::File(path) => tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| format!("Failed to read beacon file {}: {}", path.display(), e))?,
+        BeaconSource::ShellCommand(command) => {
+            let output = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .output()
+                .await
+                .map_err(|e| format!("Failed to run beacon command '{}': {}", command, e))?;
+            if !output.status.success() {
+                return Err(format!("Beacon command '{}' exited with {}", command, output.status));
+            }
+            String::from_utf8(output.stdout)
+                .map_err(|e| format!("Beacon command output wasn't UTF-8: {}", e))?
+        }
+    };
+
+    parse_beacon(&text, config.ttl)
+}
+
+/// Spawn a background task that refreshes `target` from `config.source`
+/// every `config.refresh_interval`, replacing its contents only on a
+/// successful, fresh read - a transient failure (the beacon file briefly
+/// missing, a command failing once) just keeps the last known-good list
+/// rather than clearing it out from under callers.
+pub fn spawn_refresh_task(
+    config: DiscoveryConfig,
+    target: Arc<RwLock<Vec<String>>>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match load_once(&config).await {
+                Ok(addresses) => {
+                    debug!("Discovery beacon refreshed: {} address(es)", addresses.len());
+                    *target.write().await = addresses;
+                }
+                Err(e) => warn!("Discovery beacon refresh failed, keeping last known list: {}", e),
+            }
+            tokio::time::sleep(config.refresh_interval).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_parse_round_trips() {
+        let dir = std::env::temp_dir().join(format!("beacon_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("beacon.txt");
+
+        let addresses = vec![(1u32, "127.0.0.1:8001".to_string()), (2u32, "127.0.0.1:8002".to_string())];
+        write_beacon(&path, &addresses).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let parsed = parse_beacon(&text, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(parsed, vec!["127.0.0.1:8001".to_string(), "127.0.0.1:8002".to_string()]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn stale_beacon_is_rejected() {
+        let old_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .saturating_sub(3600);
+        let text = format!(
+            "{}\n{}\n1,127.0.0.1:8001\n{}\n",
+            BEACON_BEGIN, old_timestamp, BEACON_END
+        );
+
+        let result = parse_beacon(&text, Duration::from_secs(60));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn missing_delimiters_are_rejected() {
+        assert!(parse_beacon("not a beacon at all", Duration::from_secs(60)).is_err());
+    }
+
+    #[test]
+    fn malformed_entry_is_rejected() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let text = format!("{}\n{}\nnot-a-valid-entry\n{}\n", BEACON_BEGIN, now, BEACON_END);
+
+        assert!(parse_beacon(&text, Duration::from_secs(60)).is_err());
+    }
+}
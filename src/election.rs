@@ -1,17 +1,113 @@
 use crate::messages::{Message, NodeId};
-use log::info;
-use std::collections::HashMap;
+use crate::phi_detector::PHI_FAILURE_THRESHOLD;
+use log::{info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
 use std::time::Duration;
-use tokio::time::sleep;
+
+/// Default budget for collecting `LoadResponse`s during an election before
+/// giving up on stragglers and deciding with whatever came back in time.
+pub const DEFAULT_ELECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How many past `(term, coordinator_id, load)` entries to keep around, for
+/// logging/debugging term transitions after a split-brain resolves.
+const TERM_HISTORY_LEN: usize = 5;
+
+/// In quorum mode, a self-reported load further than this factor away from
+/// the median of all collected loads is treated as unreliable (lying or
+/// malfunctioning) and excluded from coordinator selection.
+const OUTLIER_DEVIATION_FACTOR: f64 = 3.0;
+
+/// A candidate whose self-reported `phi` (see `Message::LoadResponse`)
+/// exceeds this is treated as poorly-connected and passed over for
+/// coordinator, even at equal or lower load - half of
+/// `PHI_FAILURE_THRESHOLD`, so a node has to look meaningfully shaky (not
+/// just momentarily slow) before it's excluded.
+const SUSPECT_PHI_THRESHOLD: f64 = PHI_FAILURE_THRESHOLD / 2.0;
+
+/// Smallest number of nodes, out of `known_nodes` total, that counts as a
+/// majority: `floor(n/2) + 1`.
+pub fn quorum_size(known_nodes: usize) -> usize {
+    known_nodes / 2 + 1
+}
+
+/// Drop entries whose load deviates from the median by more than
+/// `OUTLIER_DEVIATION_FACTOR`x, so a single node under-reporting its load
+/// can't unilaterally win an election. Never returns an empty map if `loads`
+/// wasn't empty - if filtering would discard everyone (e.g. only 1-2
+/// samples), the original set is returned unfiltered.
+fn filter_outliers(loads: &HashMap<NodeId, f64>) -> HashMap<NodeId, f64> {
+    if loads.len() < 3 {
+        return loads.clone();
+    }
+
+    let mut sorted: Vec<f64> = loads.values().copied().collect();
+    // load is peer-reported and arrives straight off the wire - a lying or
+    // buggy peer reporting NaN must not panic the comparator of the very
+    // function meant to tolerate lying peers.
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    let upper = median * OUTLIER_DEVIATION_FACTOR;
+    let lower = median / OUTLIER_DEVIATION_FACTOR;
+
+    let filtered: HashMap<NodeId, f64> = loads
+        .iter()
+        .filter(|(_, &load)| load >= lower && load <= upper)
+        .map(|(&id, &load)| (id, load))
+        .collect();
+
+    if filtered.is_empty() {
+        loads.clone()
+    } else {
+        filtered
+    }
+}
+
+/// Drop candidates whose self-reported `phi` exceeds `SUSPECT_PHI_THRESHOLD`,
+/// so a node that's currently having a rough time reaching its own peers
+/// doesn't get picked as coordinator just because its load happens to be
+/// low. Like `filter_outliers`, never excludes everyone - if every
+/// candidate looks shaky, load is still the best signal available.
+fn filter_suspect_connectivity(loads: &HashMap<NodeId, f64>, phis: &HashMap<NodeId, f64>) -> HashMap<NodeId, f64> {
+    let filtered: HashMap<NodeId, f64> = loads
+        .iter()
+        .filter(|(id, _)| phis.get(id).copied().unwrap_or(0.0) < SUSPECT_PHI_THRESHOLD)
+        .map(|(&id, &load)| (id, load))
+        .collect();
+
+    if filtered.is_empty() {
+        loads.clone()
+    } else {
+        filtered
+    }
+}
 
 /// Bully election algorithm implementation with load-based selection
 ///
 /// Modified Bully algorithm where the node with the LOWEST load wins
 /// instead of the highest ID. This provides transparent load balancing.
+///
+/// Coordinator changes carry a monotonically increasing `term`, so two
+/// overlapping elections (e.g. after a flurry of failures) can't leave the
+/// cluster split between two self-proclaimed coordinators forever: whichever
+/// term is higher wins, with load (then node id) as the tiebreak at equal
+/// terms. `current_term`/`current_coordinator`/`current_coordinator_load`
+/// always describe the same, single accepted winner.
 pub struct ElectionManager {
     pub node_id: NodeId,
     pub node_addresses: HashMap<NodeId, String>,
     pub current_coordinator: Option<NodeId>,
+    current_coordinator_load: Option<f64>,
+    current_term: u64,
+    term_history: VecDeque<(u64, NodeId, f64)>,
+    /// See `remote_layout_version()`.
+    remote_layout_version: u64,
+    /// When enabled, `start_election` requires a majority of responses
+    /// (rather than all of them) before deciding, discards self-reported
+    /// loads that look like outliers, and the winner is only committed once
+    /// `commit_with_quorum` sees a majority acknowledge the same
+    /// `(term, coordinator_id)` pair.
+    quorum_mode: bool,
 }
 
 impl ElectionManager {
@@ -20,53 +116,146 @@ impl ElectionManager {
             node_id,
             node_addresses,
             current_coordinator: None,
+            current_coordinator_load: None,
+            current_term: 0,
+            term_history: VecDeque::with_capacity(TERM_HISTORY_LEN),
+            remote_layout_version: 0,
+            quorum_mode: false,
         }
     }
 
-    /// Initiate an election based on current load
-    /// Returns the elected coordinator's ID
-    pub async fn start_election(
+    /// Enable or disable quorum-based (BFT-style) election. Off by default,
+    /// matching the classic modified-Bully behavior where every known peer
+    /// must respond and the first decision is final.
+    pub fn set_quorum_mode(&mut self, enabled: bool) {
+        self.quorum_mode = enabled;
+    }
+
+    pub fn quorum_mode(&self) -> bool {
+        self.quorum_mode
+    }
+
+    /// Refresh the candidate set from the live, gossiped membership view so
+    /// nodes that joined after startup become electable and ones that left
+    /// are no longer considered.
+    pub fn sync_addresses(&mut self, node_addresses: HashMap<NodeId, String>) {
+        self.node_addresses = node_addresses;
+    }
+
+    /// Initiate an election based on current load.
+    ///
+    /// `query_peer` performs the actual request/response round-trip for one
+    /// peer (e.g. `node.send_message_to_node(peer_id, LoadQuery { .. })`,
+    /// unpacked down to `(load, processed_count)`) and is fired concurrently
+    /// for every known peer; we wait until either all of them have replied
+    /// or `election_timeout` elapses, then pick the lowest-load node (ties
+    /// broken by lowest id) from whatever came back in time.
+    pub async fn start_election<F, Fut>(
         &mut self,
         current_load: f64,
+        current_processed: usize,
+        current_phi: f64,
+        excluded: &std::collections::HashSet<NodeId>,
         send_message: impl Fn(NodeId, Message) -> bool,
-    ) -> Option<NodeId> {
+        query_peer: F,
+        election_timeout: Duration,
+    ) -> Option<ElectionResult>
+    where
+        F: Fn(NodeId) -> Fut,
+        Fut: Future<Output = Option<(f64, usize, f64)>> + Send + 'static,
+    {
         info!(
             "[Node {}] Starting election with load: {:.2}",
             self.node_id, current_load
         );
 
-        // Query all other nodes for their load
+        // Skip failed nodes - they cannot be elected as coordinator
+        let peers: Vec<NodeId> = self
+            .node_addresses
+            .keys()
+            .copied()
+            .filter(|&id| id != self.node_id && !excluded.contains(&id))
+            .collect();
+
+        // Let peers know an election is underway (informational; the real
+        // outcome is decided from the LoadResponses collected below).
+        for &peer in &peers {
+            send_message(peer, Message::Election { from_node: self.node_id });
+        }
+
+        // Fire load queries concurrently and collect whatever comes back
+        // before the deadline, rather than blocking on peers one at a time.
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(NodeId, f64, usize, f64)>();
+        for peer in peers.iter().copied() {
+            let tx = tx.clone();
+            let fut = query_peer(peer);
+            tokio::spawn(async move {
+                if let Some((load, processed, phi)) = fut.await {
+                    let _ = tx.send((peer, load, processed, phi));
+                }
+            });
+        }
+        drop(tx);
+
         let mut node_loads = HashMap::new();
+        let mut node_processed = HashMap::new();
+        let mut node_phis = HashMap::new();
         node_loads.insert(self.node_id, current_load);
+        node_processed.insert(self.node_id, current_processed);
+        node_phis.insert(self.node_id, current_phi);
 
-        // Send ELECTION message to all other nodes
-        for (&other_node, _) in &self.node_addresses {
-            if other_node != self.node_id {
-                send_message(other_node, Message::Election {
-                    from_node: self.node_id,
-                });
+        let total_nodes = peers.len() + 1;
+        let required = if self.quorum_mode { quorum_size(total_nodes) } else { total_nodes };
+
+        let deadline = tokio::time::Instant::now() + election_timeout;
+        while node_loads.len() < required {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Some((peer_id, load, processed, phi))) => {
+                    node_loads.insert(peer_id, load);
+                    node_processed.insert(peer_id, processed);
+                    node_phis.insert(peer_id, phi);
+                }
+                _ => break,
             }
         }
 
-        // Wait a bit for responses
-        sleep(Duration::from_millis(100)).await;
+        info!(
+            "[Node {}] Election collected {}/{} peer responses",
+            self.node_id, node_loads.len() - 1, peers.len()
+        );
 
-        // Send load queries to all nodes
-        for (&other_node, _) in &self.node_addresses {
-            if other_node != self.node_id {
-                send_message(other_node, Message::LoadQuery {
-                    from_node: self.node_id,
-                });
-            }
+        if self.quorum_mode && node_loads.len() < required {
+            warn!(
+                "[Node {}] Only {}/{} nodes responded, short of the {} needed for quorum; aborting this round",
+                self.node_id, node_loads.len(), total_nodes, required
+            );
+            return None;
         }
 
-        // In a real implementation, we would collect responses here
-        // For this simulation, we assume the lowest-load node wins
+        let candidates = if self.quorum_mode { filter_outliers(&node_loads) } else { node_loads.clone() };
+        let candidates = filter_suspect_connectivity(&candidates, &node_phis);
 
-        node_loads
+        let (&coordinator_id, &coordinator_load) = candidates
             .iter()
-            .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-            .map(|(id, _)| *id)
+            .min_by(|a, b| a.1.total_cmp(b.1).then_with(|| a.0.cmp(b.0)))?;
+
+        // Every election attempt proposes the next term, regardless of who
+        // it picks - that's what lets a later, overlapping election always
+        // win the fork-choice over an earlier one.
+        let proposed_term = self.current_term + 1;
+
+        Some(ElectionResult::new(
+            coordinator_id,
+            coordinator_load,
+            proposed_term,
+            node_loads,
+            node_processed,
+            node_phis,
+        ))
     }
 
     /// Handle incoming election message
@@ -74,6 +263,9 @@ impl ElectionManager {
         &self,
         from_node: NodeId,
         my_load: f64,
+        my_capacity: f64,
+        my_zone: String,
+        my_phi: f64,
         send_message: impl Fn(NodeId, Message) -> bool,
     ) {
         info!(
@@ -92,42 +284,206 @@ impl ElectionManager {
             load: my_load,
             queue_length: 0, // Will be filled by actual node
             processed_count: 0, // Will be filled by actual node
+            capacity: my_capacity,
+            zone: my_zone,
+            phi: my_phi,
         });
     }
 
-    /// Announce this node as coordinator
+    /// Announce this node as coordinator for `term`, and broadcast that term
+    /// to every known peer. Only takes effect if `term` actually wins the
+    /// fork-choice rule (it always should, since callers derive it from
+    /// `start_election`'s `current_term + 1`, but a concurrent incoming
+    /// `Coordinator` message could have raced ahead of us in the meantime).
     pub fn announce_coordinator(
         &mut self,
         load: f64,
+        term: u64,
+        layout_version: u64,
         send_message: impl Fn(NodeId, Message) -> bool,
     ) {
+        if !self.update_coordinator(self.node_id, load, term, layout_version) {
+            warn!(
+                "[Node {}] Lost the race to announce term {} (already at term {} with coordinator {:?}); standing down",
+                self.node_id, term, self.current_term, self.current_coordinator
+            );
+            return;
+        }
+
         info!(
-            "[Node {}] Announcing as COORDINATOR with load: {:.2}",
-            self.node_id, load
+            "[Node {}] Announcing as COORDINATOR with load: {:.2} (term {})",
+            self.node_id, load, term
         );
 
-        self.current_coordinator = Some(self.node_id);
-
         // Broadcast coordinator message to all nodes
         for (&other_node, _) in &self.node_addresses {
             if other_node != self.node_id {
                 send_message(other_node, Message::Coordinator {
                     node_id: self.node_id,
                     load,
+                    term,
+                    layout_version,
                 });
             }
         }
     }
 
-    /// Update the current coordinator
-    pub fn update_coordinator(&mut self, coordinator_id: NodeId, load: f64) {
-        if self.current_coordinator != Some(coordinator_id) {
+    /// Update the current coordinator from an incoming `Coordinator`
+    /// message (or a local election decision), applying the fork-choice
+    /// rule: a `term` strictly greater than ours always wins; at an equal
+    /// term, lower load wins, with node id as the final tiebreak. Returns
+    /// `true` if this call actually changed the accepted coordinator/term.
+    pub fn update_coordinator(&mut self, coordinator_id: NodeId, load: f64, term: u64, layout_version: u64) -> bool {
+        // Tracked independently of the term fork-choice below: even a
+        // `Coordinator` message that loses the fork-choice (a stale/retried
+        // announcement) might still carry a layout version newer than ours,
+        // and the caller (`CloudNode`) treats any observed-but-unseen version
+        // as a trigger to recompute its own `ClusterLayout`.
+        if layout_version > self.remote_layout_version {
+            self.remote_layout_version = layout_version;
+        }
+
+        if !self.accept_term(term, coordinator_id, load) {
+            return false;
+        }
+
+        let was_self_coordinator = self.current_coordinator == Some(self.node_id);
+        if self.current_coordinator != Some(coordinator_id) || self.current_term != term {
             info!(
-                "[Node {}] New COORDINATOR: Node {} (load: {:.2})",
-                self.node_id, coordinator_id, load
+                "[Node {}] New COORDINATOR: Node {} (load: {:.2}, term {})",
+                self.node_id, coordinator_id, load, term
+            );
+        }
+        if was_self_coordinator && coordinator_id != self.node_id {
+            warn!(
+                "[Node {}] Stepping down as coordinator: Node {} won term {}",
+                self.node_id, coordinator_id, term
+            );
+        }
+
+        self.current_coordinator = Some(coordinator_id);
+        self.current_coordinator_load = Some(load);
+        self.current_term = term;
+        self.term_history.push_back((term, coordinator_id, load));
+        if self.term_history.len() > TERM_HISTORY_LEN {
+            self.term_history.pop_front();
+        }
+
+        true
+    }
+
+    /// Quorum-mode counterpart to `announce_coordinator`/`update_coordinator`:
+    /// broadcasts `(coordinator_id, load, term)` via `ack_peer` to every
+    /// known peer and only commits it locally once a majority of the known
+    /// cluster (including ourselves) has acknowledged that exact pair.
+    /// `ack_peer` should send the `Coordinator` message to one peer and
+    /// report whether it came back with an `Ok` for it, within whatever
+    /// per-peer timeout the caller wants to allow.
+    ///
+    /// Returns `true` if a quorum formed and the result was committed,
+    /// `false` if it timed out short of a majority - callers should retry
+    /// with a fresh (higher) term rather than accept a partially-agreed
+    /// coordinator.
+    pub async fn commit_with_quorum<F, Fut>(
+        &mut self,
+        coordinator_id: NodeId,
+        load: f64,
+        term: u64,
+        layout_version: u64,
+        ack_peer: F,
+        ack_timeout: Duration,
+    ) -> bool
+    where
+        F: Fn(NodeId) -> Fut,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        let peers: Vec<NodeId> = self
+            .node_addresses
+            .keys()
+            .copied()
+            .filter(|&id| id != self.node_id)
+            .collect();
+        let required = quorum_size(peers.len() + 1);
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<bool>();
+        for peer in peers.iter().copied() {
+            let tx = tx.clone();
+            let fut = ack_peer(peer);
+            tokio::spawn(async move {
+                let _ = tx.send(fut.await);
+            });
+        }
+        drop(tx);
+
+        let mut acked = 1; // we agree with ourselves
+        let deadline = tokio::time::Instant::now() + ack_timeout;
+        while acked < required {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Some(true)) => acked += 1,
+                Ok(Some(false)) => {}
+                _ => break,
+            }
+        }
+
+        if acked < required {
+            warn!(
+                "[Node {}] Only {}/{} nodes acknowledged Node {} for term {}, short of quorum; not committing",
+                self.node_id, acked, peers.len() + 1, coordinator_id, term
             );
-            self.current_coordinator = Some(coordinator_id);
+            return false;
         }
+
+        info!(
+            "[Node {}] Quorum reached ({}/{}): committing Node {} as coordinator for term {}",
+            self.node_id, acked, peers.len() + 1, coordinator_id, term
+        );
+        self.update_coordinator(coordinator_id, load, term, layout_version)
+    }
+
+    /// Fork-choice rule shared by `announce_coordinator` and
+    /// `update_coordinator`: a higher term always wins; an equal term is
+    /// only accepted if it agrees with (or improves on) what we already
+    /// have, so a stale resend of our own current winner doesn't fire
+    /// spurious "new coordinator" logging.
+    fn accept_term(&self, term: u64, coordinator_id: NodeId, load: f64) -> bool {
+        if term > self.current_term {
+            return true;
+        }
+        if term < self.current_term {
+            return false;
+        }
+
+        match self.current_coordinator {
+            None => true,
+            Some(current_id) if current_id == coordinator_id => true,
+            Some(current_id) => {
+                let current_load = self.current_coordinator_load.unwrap_or(f64::INFINITY);
+                load < current_load || (load == current_load && coordinator_id < current_id)
+            }
+        }
+    }
+
+    /// The term of the currently accepted coordinator.
+    pub fn current_term(&self) -> u64 {
+        self.current_term
+    }
+
+    /// Highest `layout_version` seen on any incoming `Coordinator` message,
+    /// regardless of whether that message's term won the fork-choice.
+    /// `CloudNode` recomputes its `ClusterLayout` whenever this exceeds its
+    /// own current version.
+    pub fn remote_layout_version(&self) -> u64 {
+        self.remote_layout_version
+    }
+
+    /// Recent `(term, coordinator_id, load)` transitions, oldest first, for
+    /// diagnosing how a split-brain resolved.
+    pub fn term_history(&self) -> &VecDeque<(u64, NodeId, f64)> {
+        &self.term_history
     }
 
     /// Check if this node is the coordinator
@@ -146,24 +502,43 @@ impl ElectionManager {
 pub struct ElectionResult {
     pub coordinator_id: NodeId,
     pub load: f64,
+    /// The term this election proposes for `coordinator_id`. Not yet
+    /// adopted by the `ElectionManager` until `announce_coordinator`/
+    /// `update_coordinator` is called with it.
+    pub term: u64,
     pub all_loads: HashMap<NodeId, f64>,
+    pub all_processed: HashMap<NodeId, usize>,
+    /// Each responding node's self-reported connectivity phi (see
+    /// `Message::LoadResponse`), for callers that want to factor
+    /// connectivity health into a second-stage selection of their own.
+    pub all_phi: HashMap<NodeId, f64>,
 }
 
 impl ElectionResult {
-    pub fn new(coordinator_id: NodeId, load: f64, all_loads: HashMap<NodeId, f64>) -> Self {
+    pub fn new(
+        coordinator_id: NodeId,
+        load: f64,
+        term: u64,
+        all_loads: HashMap<NodeId, f64>,
+        all_processed: HashMap<NodeId, usize>,
+        all_phi: HashMap<NodeId, f64>,
+    ) -> Self {
         Self {
             coordinator_id,
             load,
+            term,
             all_loads,
+            all_processed,
+            all_phi,
         }
     }
 
     pub fn log_result(&self) {
         info!("=== ELECTION RESULT ===");
-        info!("Coordinator: Node {} (load: {:.2})", self.coordinator_id, self.load);
+        info!("Coordinator: Node {} (load: {:.2}, term {})", self.coordinator_id, self.load, self.term);
         info!("All node loads:");
         let mut sorted_loads: Vec<_> = self.all_loads.iter().collect();
-        sorted_loads.sort_by(|a, b| a.1.partial_cmp(b.1).unwrap());
+        sorted_loads.sort_by(|a, b| a.1.total_cmp(b.1));
         for (node_id, load) in sorted_loads {
             let is_coord = if *node_id == self.coordinator_id { " [COORDINATOR]" } else { "" };
             info!("  Node {}: {:.2}{}", node_id, load, is_coord);
@@ -195,9 +570,161 @@ mod tests {
         let mut manager = ElectionManager::new(1, addresses);
 
         let send_fn = |_node: NodeId, _msg: Message| true;
-        manager.announce_coordinator(0.5, send_fn);
+        manager.announce_coordinator(0.5, 1, 0, send_fn);
 
         assert_eq!(manager.current_coordinator, Some(1));
         assert!(manager.is_coordinator());
+        assert_eq!(manager.current_term(), 1);
+    }
+
+    #[test]
+    fn higher_term_always_wins_over_lower_load() {
+        let mut manager = ElectionManager::new(1, HashMap::new());
+
+        assert!(manager.update_coordinator(2, 10.0, 5, 0));
+        assert_eq!(manager.get_coordinator(), Some(2));
+
+        // Node 3 has a much lower load, but a lower term - must lose.
+        assert!(!manager.update_coordinator(3, 0.1, 4, 0));
+        assert_eq!(manager.get_coordinator(), Some(2));
+
+        // A genuinely higher term wins even with higher load.
+        assert!(manager.update_coordinator(3, 20.0, 6, 0));
+        assert_eq!(manager.get_coordinator(), Some(3));
+        assert_eq!(manager.current_term(), 6);
+    }
+
+    #[test]
+    fn equal_term_breaks_tie_by_lower_load_then_lower_id() {
+        let mut manager = ElectionManager::new(1, HashMap::new());
+
+        assert!(manager.update_coordinator(5, 2.0, 1, 0));
+        // Same term, higher load - loses.
+        assert!(!manager.update_coordinator(2, 3.0, 1, 0));
+        // Same term, lower load - wins.
+        assert!(manager.update_coordinator(2, 1.0, 1, 0));
+        assert_eq!(manager.get_coordinator(), Some(2));
+    }
+
+    #[test]
+    fn quorum_size_is_floor_n_over_2_plus_1() {
+        assert_eq!(quorum_size(1), 1);
+        assert_eq!(quorum_size(3), 2);
+        assert_eq!(quorum_size(4), 3);
+        assert_eq!(quorum_size(5), 3);
+    }
+
+    #[test]
+    fn filter_outliers_drops_loads_far_from_the_median() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.4);
+        loads.insert(2, 0.5);
+        loads.insert(3, 0.45);
+        loads.insert(4, 0.01); // under-reporting to try to win
+
+        let filtered = filter_outliers(&loads);
+        assert!(!filtered.contains_key(&4));
+        assert_eq!(filtered.len(), 3);
+    }
+
+    #[test]
+    fn filter_outliers_keeps_everything_with_too_few_samples() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.9);
+        loads.insert(2, 0.01);
+
+        assert_eq!(filter_outliers(&loads), loads);
+    }
+
+    #[test]
+    fn filter_suspect_connectivity_drops_high_phi_candidates() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.5);
+        loads.insert(2, 0.1); // lowest load, but looks unreliable
+
+        let mut phis = HashMap::new();
+        phis.insert(1, 1.0);
+        phis.insert(2, SUSPECT_PHI_THRESHOLD + 1.0);
+
+        let filtered = filter_suspect_connectivity(&loads, &phis);
+        assert!(!filtered.contains_key(&2));
+        assert_eq!(filtered.len(), 1);
+    }
+
+    #[test]
+    fn filter_suspect_connectivity_keeps_everything_if_all_look_suspect() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.5);
+        loads.insert(2, 0.1);
+
+        let mut phis = HashMap::new();
+        phis.insert(1, SUSPECT_PHI_THRESHOLD + 1.0);
+        phis.insert(2, SUSPECT_PHI_THRESHOLD + 2.0);
+
+        assert_eq!(filter_suspect_connectivity(&loads, &phis), loads);
+    }
+
+    #[tokio::test]
+    async fn quorum_mode_aborts_the_round_without_a_majority() {
+        let mut addresses = HashMap::new();
+        addresses.insert(1, "127.0.0.1:8001".to_string());
+        addresses.insert(2, "127.0.0.1:8002".to_string());
+        addresses.insert(3, "127.0.0.1:8003".to_string());
+        addresses.insert(4, "127.0.0.1:8004".to_string());
+
+        let mut manager = ElectionManager::new(1, addresses);
+        manager.set_quorum_mode(true);
+
+        // Out of 4 nodes, only node 2 answers - short of the quorum of 3.
+        let query_peer = |peer_id: NodeId| async move {
+            if peer_id == 2 {
+                Some((0.2, 0, 0.0))
+            } else {
+                None
+            }
+        };
+
+        let result = manager
+            .start_election(0.5, 0, 0.0, &std::collections::HashSet::new(), |_, _| true, query_peer, Duration::from_millis(200))
+            .await;
+
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn commit_with_quorum_only_commits_on_majority_ack() {
+        let mut addresses = HashMap::new();
+        addresses.insert(1, "127.0.0.1:8001".to_string());
+        addresses.insert(2, "127.0.0.1:8002".to_string());
+        addresses.insert(3, "127.0.0.1:8003".to_string());
+
+        let mut manager = ElectionManager::new(1, addresses);
+        manager.set_quorum_mode(true);
+
+        // Only one of two peers acks - together with self that's a 2/3 majority.
+        let ack_peer = |peer_id: NodeId| async move { peer_id == 2 };
+        let committed = manager.commit_with_quorum(1, 0.3, 1, 0, ack_peer, Duration::from_millis(200)).await;
+        assert!(committed);
+        assert_eq!(manager.get_coordinator(), Some(1));
+        assert_eq!(manager.current_term(), 1);
+    }
+
+    #[tokio::test]
+    async fn commit_with_quorum_rejects_without_majority_ack() {
+        let mut addresses = HashMap::new();
+        addresses.insert(1, "127.0.0.1:8001".to_string());
+        addresses.insert(2, "127.0.0.1:8002".to_string());
+        addresses.insert(3, "127.0.0.1:8003".to_string());
+        addresses.insert(4, "127.0.0.1:8004".to_string());
+        addresses.insert(5, "127.0.0.1:8005".to_string());
+
+        let mut manager = ElectionManager::new(1, addresses);
+        manager.set_quorum_mode(true);
+
+        // Nobody acks - self alone is 1/5, short of the quorum of 3.
+        let ack_peer = |_peer_id: NodeId| async move { false };
+        let committed = manager.commit_with_quorum(1, 0.3, 1, 0, ack_peer, Duration::from_millis(200)).await;
+        assert!(!committed);
+        assert_eq!(manager.get_coordinator(), None);
     }
 }
@@ -1,4 +1,6 @@
+use crate::identity::{self, NodeIdentity};
 use log::info;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 // use std::time::Duration;  // Commented out - no longer using artificial delays
 // use tokio::time::sleep;   // Commented out - no longer using artificial delays
@@ -11,18 +13,37 @@ pub struct ImageMetadata {
     pub quota: u32,
 }
 
-/// Encrypt image by hiding it inside a cover image using LSB steganography
-/// The cover image becomes the "encryption key" - the encrypted result looks like the cover
-/// Embeds: [metadata_len][metadata][original_image_len][original_image_data] all in LSBs
+/// Number of low bits of each cover-pixel byte used to store payload bits.
+/// Higher values pack more payload into the same cover image (at the cost of
+/// a larger, more visible LSB footprint); `1` reproduces the original
+/// single-bit-plane behavior. Must be one of `1`, `2`, `4`, `8`.
+pub const DEFAULT_BIT_PLANES: u8 = 1;
+
+/// Encrypt image by hiding it inside a cover image using LSB steganography.
+/// The cover image becomes the "encryption key" - the encrypted result looks like the cover.
+/// Embeds, all in the low `bit_planes` bits of each pixel byte (after a fixed single-bit
+/// header recording `bit_planes` itself, so a decoder can recover it before it knows it):
+/// [signature][signer_public_key][metadata_len][metadata][original_image_len]
+/// [original_image_data]. The signature covers the metadata's canonical JSON bytes with
+/// `identity`'s key, so any node holding the signer's public key can tell whether the
+/// embedded usernames/quota were edited after the fact (e.g. by poking the stego image's
+/// LSBs directly) instead of coming from a genuine `encrypt_image` call.
 pub async fn encrypt_image(
     image_data: Vec<u8>,
     usernames: Vec<String>,
     quota: u32,
+    identity: &NodeIdentity,
+    bit_planes: u8,
 ) -> Result<Vec<u8>, String> {
+    if !matches!(bit_planes, 1 | 2 | 4 | 8) {
+        return Err(format!("bit_planes must be 1, 2, 4, or 8 (got {})", bit_planes));
+    }
+
     info!(
-        "Starting encryption for {} usernames with quota {}",
+        "Starting encryption for {} usernames with quota {} ({} bit plane(s))",
         usernames.len(),
-        quota
+        quota,
+        bit_planes
     );
 
     // Decode the original image to get its dimensions
@@ -39,45 +60,62 @@ pub async fn encrypt_image(
 
     // Get mutable pixel data from cover image
     let pixels = cover_img.as_mut();
-    let available_bits = pixels.len(); // Each byte can hold 1 bit in LSB
+    let available_pixels = pixels.len();
 
-    // Prepare metadata
+    // Prepare metadata and sign its canonical JSON encoding, so tampering with
+    // the embedded bytes later (without the private key) is detectable.
     let metadata = ImageMetadata { usernames, quota };
     let metadata_json = serde_json::to_string(&metadata).map_err(|e| e.to_string())?;
     let metadata_bytes = metadata_json.as_bytes();
+    let signature = identity.sign(metadata_bytes);
+    let signer_public_key = identity.public_key_bytes();
+
+    // Calculate total pixels needed: 8 fixed single-bit pixels for the header
+    // byte, then everything else packed `bit_planes` bits per pixel.
+    let pixels_per_byte = 8 / bit_planes as usize;
+    let header_pixels = 8; // the bit_planes byte itself, always one bit per pixel
+    let payload_bytes = signature.len() + signer_public_key.len()
+        + 4 /* metadata_len */ + metadata_bytes.len()
+        + 4 /* image_len */ + image_data.len();
+    let total_pixels = header_pixels + payload_bytes * pixels_per_byte;
 
-    // Calculate total bits needed
-    let metadata_header_bits = 32; // 4 bytes for metadata length
-    let metadata_bits = metadata_bytes.len() * 8;
-    let image_header_bits = 32; // 4 bytes for original image length
-    let image_bits = image_data.len() * 8;
-    let total_bits = metadata_header_bits + metadata_bits + image_header_bits + image_bits;
-
-    info!("Capacity check: need {} bits, have {} bits", total_bits, available_bits);
+    info!(
+        "Capacity check: need {} pixels ({} bit plane(s)), have {} pixels",
+        total_pixels, bit_planes, available_pixels
+    );
 
-    if total_bits > available_bits {
+    if total_pixels > available_pixels {
         return Err(format!(
-            "Cover image too small: need {} bits, have {} bits",
-            total_bits, available_bits
+            "Cover image too small: need {} pixels, have {} pixels",
+            total_pixels, available_pixels
         ));
     }
 
+    // STEP 0: Embed the bit-plane count itself, always as a single bit per
+    // pixel, so a decoder can recover it before it knows how wide the rest
+    // of the payload is packed.
     let mut bit_index = 0;
+    embed_bytes(pixels, &mut bit_index, &[bit_planes], 1);
 
-    // STEP 1: Embed metadata length (4 bytes)
+    // STEP 1: Embed the metadata signature and the signer's public key
+    embed_bytes(pixels, &mut bit_index, &signature, bit_planes);
+    embed_bytes(pixels, &mut bit_index, &signer_public_key, bit_planes);
+
+    // STEP 2: Embed metadata length (4 bytes)
     let metadata_len = metadata_bytes.len() as u32;
-    embed_u32(pixels, &mut bit_index, metadata_len);
+    embed_u32(pixels, &mut bit_index, metadata_len, bit_planes);
 
-    // STEP 2: Embed metadata
-    embed_bytes(pixels, &mut bit_index, metadata_bytes);
+    // STEP 3: Embed metadata
+    embed_bytes(pixels, &mut bit_index, metadata_bytes, bit_planes);
     info!("Metadata embedded: {} bytes", metadata_bytes.len());
 
-    // STEP 3: Embed original image length (4 bytes)
+    // STEP 4: Embed original image length (4 bytes)
     let image_len = image_data.len() as u32;
-    embed_u32(pixels, &mut bit_index, image_len);
+    embed_u32(pixels, &mut bit_index, image_len, bit_planes);
 
-    // STEP 4: Embed original image data
-    embed_bytes(pixels, &mut bit_index, &image_data);
+    // STEP 5: Embed original image data - this is the bulk of the payload
+    // for any real photo, so it's the one region worth parallelizing.
+    embed_bytes_parallel(pixels, bit_index, &image_data, bit_planes);
     info!("Original image embedded: {} bytes", image_data.len());
 
     // Convert to DynamicImage and encode as PNG (lossless)
@@ -113,40 +151,48 @@ fn load_cover_image() -> Result<image::RgbImage, String> {
     Ok(key_img.to_rgb8())
 }
 
-/// Embed a u32 value into LSBs
-fn embed_u32(pixels: &mut [u8], bit_index: &mut usize, value: u32) {
+/// Embed a u32 value, `bit_planes` bits per pixel
+fn embed_u32(pixels: &mut [u8], bit_index: &mut usize, value: u32, bit_planes: u8) {
     let bytes = value.to_be_bytes();
-    embed_bytes(pixels, bit_index, &bytes);
+    embed_bytes(pixels, bit_index, &bytes, bit_planes);
 }
 
-/// Embed bytes into LSBs
-fn embed_bytes(pixels: &mut [u8], bit_index: &mut usize, data: &[u8]) {
+/// Embed bytes into the low `bit_planes` bits of each pixel byte. Each
+/// payload byte consumes `8 / bit_planes` pixels; `bit_planes` must be 1, 2,
+/// 4, or 8. `bit_planes = 1` is the original single-bit-per-pixel scheme.
+fn embed_bytes(pixels: &mut [u8], bit_index: &mut usize, data: &[u8], bit_planes: u8) {
+    let mask = (1u8 << bit_planes) - 1;
+    let pixels_per_byte = 8 / bit_planes as usize;
     for &byte in data {
-        for bit_pos in (0..8).rev() {
-            let bit_value = (byte >> bit_pos) & 1;
-            pixels[*bit_index] = (pixels[*bit_index] & 0xFE) | bit_value;
+        for chunk in 0..pixels_per_byte {
+            let shift = 8 - bit_planes as usize * (chunk + 1);
+            let bits = (byte >> shift) & mask;
+            pixels[*bit_index] = (pixels[*bit_index] & !mask) | bits;
             *bit_index += 1;
         }
     }
 }
 
-/// Extract a u32 value from LSBs
-fn extract_u32(pixels: &[u8], bit_index: &mut usize) -> Result<u32, String> {
+/// Extract a u32 value, `bit_planes` bits per pixel
+fn extract_u32(pixels: &[u8], bit_index: &mut usize, bit_planes: u8) -> Result<u32, String> {
     let mut bytes = [0u8; 4];
-    extract_bytes(pixels, bit_index, &mut bytes)?;
+    extract_bytes(pixels, bit_index, &mut bytes, bit_planes)?;
     Ok(u32::from_be_bytes(bytes))
 }
 
-/// Extract bytes from LSBs
-fn extract_bytes(pixels: &[u8], bit_index: &mut usize, output: &mut [u8]) -> Result<(), String> {
+/// Extract bytes from the low `bit_planes` bits of each pixel byte - the
+/// inverse of `embed_bytes`.
+fn extract_bytes(pixels: &[u8], bit_index: &mut usize, output: &mut [u8], bit_planes: u8) -> Result<(), String> {
+    let mask = (1u8 << bit_planes) - 1;
+    let pixels_per_byte = 8 / bit_planes as usize;
     for byte_out in output.iter_mut() {
         let mut byte = 0u8;
-        for _ in 0..8 {
+        for _ in 0..pixels_per_byte {
             if *bit_index >= pixels.len() {
                 return Err("Unexpected end of pixel data".to_string());
             }
-            let bit_value = pixels[*bit_index] & 1;
-            byte = (byte << 1) | bit_value;
+            let bits = pixels[*bit_index] & mask;
+            byte = (byte << bit_planes) | bits;
             *bit_index += 1;
         }
         *byte_out = byte;
@@ -154,10 +200,80 @@ fn extract_bytes(pixels: &[u8], bit_index: &mut usize, output: &mut [u8]) -> Res
     Ok(())
 }
 
+/// Minimum payload size before `embed_bytes_parallel`/`extract_bytes_parallel`
+/// bother splitting work across threads - below this, the cost of spinning up
+/// rayon's thread pool outweighs the per-core savings.
+const PARALLEL_THRESHOLD_BYTES: usize = 1_000_000;
+
+/// Parallel counterpart to `embed_bytes` for large payloads (e.g. the hidden
+/// image itself): splits `data` into disjoint byte ranges, one per available
+/// core, and embeds each range into its own slice of `pixels`. A range's
+/// starting pixel offset is computed arithmetically from its starting byte
+/// index (`byte_index * 8 / bit_planes` pixels into the region), so each
+/// worker can write its slice independently with no synchronization. Falls
+/// back to the sequential path below `PARALLEL_THRESHOLD_BYTES`.
+fn embed_bytes_parallel(pixels: &mut [u8], start_index: usize, data: &[u8], bit_planes: u8) {
+    if data.len() < PARALLEL_THRESHOLD_BYTES {
+        let mut idx = start_index;
+        embed_bytes(pixels, &mut idx, data, bit_planes);
+        return;
+    }
+
+    let pixels_per_byte = 8 / bit_planes as usize;
+    let num_workers = rayon::current_num_threads().max(1);
+    let chunk_bytes = data.len().div_ceil(num_workers);
+    let region = &mut pixels[start_index..start_index + data.len() * pixels_per_byte];
+
+    data.par_chunks(chunk_bytes)
+        .zip(region.par_chunks_mut(chunk_bytes * pixels_per_byte))
+        .for_each(|(data_chunk, pixel_chunk)| {
+            let mut idx = 0;
+            embed_bytes(pixel_chunk, &mut idx, data_chunk, bit_planes);
+        });
+}
+
+/// Parallel counterpart to `extract_bytes`, mirroring
+/// `embed_bytes_parallel`'s range partitioning. Falls back to the sequential
+/// path below `PARALLEL_THRESHOLD_BYTES`.
+fn extract_bytes_parallel(
+    pixels: &[u8],
+    start_index: usize,
+    output: &mut [u8],
+    bit_planes: u8,
+) -> Result<(), String> {
+    if output.len() < PARALLEL_THRESHOLD_BYTES {
+        let mut idx = start_index;
+        return extract_bytes(pixels, &mut idx, output, bit_planes);
+    }
+
+    let pixels_per_byte = 8 / bit_planes as usize;
+    let num_workers = rayon::current_num_threads().max(1);
+    let chunk_bytes = output.len().div_ceil(num_workers);
+    let region_len = output.len() * pixels_per_byte;
+
+    if start_index + region_len > pixels.len() {
+        return Err("Unexpected end of pixel data".to_string());
+    }
+    let region = &pixels[start_index..start_index + region_len];
+
+    output
+        .par_chunks_mut(chunk_bytes)
+        .zip(region.par_chunks(chunk_bytes * pixels_per_byte))
+        .try_for_each(|(out_chunk, pixel_chunk)| {
+            let mut idx = 0;
+            extract_bytes(pixel_chunk, &mut idx, out_chunk, bit_planes)
+        })
+}
 
-/// Decrypt image: extracts hidden image from cover image using LSB steganography
-/// Extracts: [metadata_len][metadata][original_image_len][original_image_data] from LSBs
-/// Returns the original hidden image and the metadata
+
+/// Decrypt image: extracts hidden image from cover image using LSB steganography.
+/// Extracts: [signature][signer_public_key][metadata_len][metadata][original_image_len]
+/// [original_image_data] from LSBs. The signature is checked against the signer's own
+/// embedded public key before the metadata is trusted - this proves the usernames/quota
+/// weren't edited since `encrypt_image` signed them, though (as with any self-contained
+/// signature) it doesn't by itself prove the embedded public key belongs to a node the
+/// cluster actually trusts; binding it to `verified_peers` is a separate step.
+/// Returns the original hidden image and the metadata.
 pub async fn decrypt_image(encrypted_image: Vec<u8>) -> Result<(Vec<u8>, ImageMetadata), String> {
     info!("Starting decryption - extracting hidden image from cover");
 
@@ -175,8 +291,24 @@ pub async fn decrypt_image(encrypted_image: Vec<u8>) -> Result<(Vec<u8>, ImageMe
 
     let mut bit_index = 0;
 
-    // STEP 1: Extract metadata length (4 bytes)
-    let metadata_len = extract_u32(pixels, &mut bit_index)? as usize;
+    // STEP 0: Extract the bit-plane count - always a single bit per pixel,
+    // mirroring how `encrypt_image` embeds it, since everything after this
+    // point is packed at that width and can't be read without knowing it.
+    let mut bit_planes_buf = [0u8; 1];
+    extract_bytes(pixels, &mut bit_index, &mut bit_planes_buf, 1)?;
+    let bit_planes = bit_planes_buf[0];
+    if !matches!(bit_planes, 1 | 2 | 4 | 8) {
+        return Err(format!("Invalid bit plane count: {}", bit_planes));
+    }
+
+    // STEP 1: Extract the metadata signature and the signer's public key
+    let mut signature = [0u8; 64];
+    extract_bytes(pixels, &mut bit_index, &mut signature, bit_planes)?;
+    let mut signer_public_key = [0u8; 32];
+    extract_bytes(pixels, &mut bit_index, &mut signer_public_key, bit_planes)?;
+
+    // STEP 2: Extract metadata length (4 bytes)
+    let metadata_len = extract_u32(pixels, &mut bit_index, bit_planes)? as usize;
 
     if metadata_len == 0 || metadata_len > 10000 {
         return Err(format!("Invalid metadata length: {}", metadata_len));
@@ -184,9 +316,15 @@ pub async fn decrypt_image(encrypted_image: Vec<u8>) -> Result<(Vec<u8>, ImageMe
 
     info!("Metadata length: {} bytes", metadata_len);
 
-    // STEP 2: Extract metadata
+    // STEP 3: Extract metadata
     let mut metadata_bytes = vec![0u8; metadata_len];
-    extract_bytes(pixels, &mut bit_index, &mut metadata_bytes)?;
+    extract_bytes(pixels, &mut bit_index, &mut metadata_bytes, bit_planes)?;
+
+    if !identity::verify_signature(&signer_public_key, &metadata_bytes, &signature) {
+        return Err(
+            "BadSignature: embedded metadata failed signature verification, quota/ACL may have been tampered with".to_string()
+        );
+    }
 
     let metadata_json = String::from_utf8(metadata_bytes)
         .map_err(|e| format!("Invalid metadata UTF-8: {}", e))?;
@@ -195,8 +333,8 @@ pub async fn decrypt_image(encrypted_image: Vec<u8>) -> Result<(Vec<u8>, ImageMe
 
     info!("Metadata extracted: {} usernames, quota: {}", metadata.usernames.len(), metadata.quota);
 
-    // STEP 3: Extract original image length (4 bytes)
-    let image_len = extract_u32(pixels, &mut bit_index)? as usize;
+    // STEP 4: Extract original image length (4 bytes)
+    let image_len = extract_u32(pixels, &mut bit_index, bit_planes)? as usize;
 
     if image_len == 0 || image_len > 10_000_000 {
         return Err(format!("Invalid image length: {}", image_len));
@@ -204,9 +342,10 @@ pub async fn decrypt_image(encrypted_image: Vec<u8>) -> Result<(Vec<u8>, ImageMe
 
     info!("Original image length: {} bytes", image_len);
 
-    // STEP 4: Extract original image data
+    // STEP 5: Extract original image data - this is the bulk of the payload
+    // for any real photo, so it's the one region worth parallelizing.
     let mut original_image_data = vec![0u8; image_len];
-    extract_bytes(pixels, &mut bit_index, &mut original_image_data)?;
+    extract_bytes_parallel(pixels, bit_index, &mut original_image_data, bit_planes)?;
 
     info!("Decryption completed: extracted {} bytes (original image)", original_image_data.len());
 
@@ -233,15 +372,22 @@ pub fn decrement_quota(metadata: &mut ImageMetadata) -> bool {
 mod tests {
     use super::*;
 
+    fn test_identity() -> NodeIdentity {
+        NodeIdentity {
+            signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng),
+        }
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt() {
         // Create a simple test image (1KB of random data)
         let image_data: Vec<u8> = (0..1024).map(|i| (i % 256) as u8).collect();
         let usernames = vec!["alice".to_string(), "bob".to_string()];
         let quota = 5;
+        let identity = test_identity();
 
         // Encrypt
-        let encrypted = encrypt_image(image_data.clone(), usernames.clone(), quota)
+        let encrypted = encrypt_image(image_data.clone(), usernames.clone(), quota, &identity, DEFAULT_BIT_PLANES)
             .await
             .unwrap();
 
@@ -252,6 +398,68 @@ mod tests {
         assert_eq!(metadata.quota, quota);
     }
 
+    /// A small, genuinely decodable PNG to hide - `encrypt_image` decodes its
+    /// input as an image first, so arbitrary bytes are rejected.
+    fn test_png() -> Vec<u8> {
+        let img = image::RgbImage::from_fn(16, 16, |x, y| image::Rgb([(x * 16) as u8, (y * 16) as u8, 128]));
+        let mut bytes = Vec::new();
+        let mut cursor = std::io::Cursor::new(&mut bytes);
+        let encoder = image::codecs::png::PngEncoder::new(&mut cursor);
+        DynamicImage::ImageRgb8(img).write_with_encoder(encoder).unwrap();
+        bytes
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_multi_bit_plane() {
+        let image_data = test_png();
+        let usernames = vec!["alice".to_string()];
+        let quota = 7;
+        let identity = test_identity();
+
+        for &bit_planes in &[1u8, 2, 4, 8] {
+            let encrypted = encrypt_image(image_data.clone(), usernames.clone(), quota, &identity, bit_planes)
+                .await
+                .unwrap();
+            let (decrypted, metadata) = decrypt_image(encrypted).await.unwrap();
+
+            assert_eq!(decrypted, image_data, "mismatch at bit_planes={}", bit_planes);
+            assert_eq!(metadata.usernames, usernames);
+            assert_eq!(metadata.quota, quota);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tampered_metadata_fails_signature_check() {
+        let image_data = test_png();
+        let usernames = vec!["alice".to_string()];
+        let quota = 5;
+        let identity = test_identity();
+
+        let encrypted = encrypt_image(image_data, usernames, quota, &identity, DEFAULT_BIT_PLANES)
+            .await
+            .unwrap();
+
+        // Flip one LSB inside the metadata region (it starts right after the
+        // 1-bit bit-plane header + 64-byte signature + 32-byte public key +
+        // 4-byte length header, i.e. bit offset 8 + (64 + 32 + 4) * 8 = 808)
+        // - simulating someone editing the quota/usernames directly in the
+        // stego image without the private key.
+        let mut tampered_img = image::load_from_memory(&encrypted).unwrap().to_rgb8();
+        let metadata_region_start_bit = 8 + (64 + 32 + 4) * 8;
+        let pixels = tampered_img.as_mut();
+        pixels[metadata_region_start_bit] ^= 1;
+
+        let mut bytes = Vec::new();
+        let mut cursor = std::io::Cursor::new(&mut bytes);
+        let encoder = image::codecs::png::PngEncoder::new(&mut cursor);
+        DynamicImage::ImageRgb8(tampered_img)
+            .write_with_encoder(encoder)
+            .unwrap();
+
+        let err = decrypt_image(bytes).await.unwrap_err();
+        assert!(err.starts_with("BadSignature"), "unexpected error: {}", err);
+    }
+
     #[tokio::test]
     async fn test_authorization() {
         let metadata = ImageMetadata {
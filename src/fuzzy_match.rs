@@ -0,0 +1,79 @@
+/// Subsequence fuzzy-match score for incremental "type to filter" UIs (e.g.
+/// the contacts directory search). Returns `None` if `query` isn't a
+/// subsequence of `candidate` at all; otherwise a higher score means a
+/// better match.
+///
+/// Scoring rewards contiguous runs and an early/prefix match, which is what
+/// makes fuzzy filters feel responsive (typing a prefix of the name ranks it
+/// above an unrelated candidate that merely contains the same letters).
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_lower = candidate.to_lowercase();
+    let query_lower = query.to_lowercase();
+
+    let candidate_chars: Vec<char> = candidate_lower.chars().collect();
+    let query_chars: Vec<char> = query_lower.chars().collect();
+
+    let mut score = 0i32;
+    let mut candidate_idx = 0;
+    let mut run_length = 0i32;
+
+    for &qc in &query_chars {
+        let mut found = false;
+        while candidate_idx < candidate_chars.len() {
+            let cc = candidate_chars[candidate_idx];
+            candidate_idx += 1;
+
+            if cc == qc {
+                found = true;
+
+                // Prefix-of-candidate bonus: matching right at the start is
+                // the strongest signal the user typed what they meant.
+                if candidate_idx == 1 {
+                    score += 15;
+                }
+
+                // Contiguous-match bonus: consecutive hits build on each other.
+                run_length += 1;
+                score += 5 + run_length;
+                break;
+            } else {
+                run_length = 0;
+            }
+        }
+
+        if !found {
+            return None;
+        }
+    }
+
+    // Prefer tighter overall matches (less of the candidate skipped over).
+    score -= (candidate_chars.len() as i32 - query_chars.len() as i32).max(0);
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_prefix_outscores_scattered_match() {
+        let prefix_score = fuzzy_score("alice", "ali").unwrap();
+        let scattered_score = fuzzy_score("balicie", "ali").unwrap();
+        assert!(prefix_score > scattered_score);
+    }
+
+    #[test]
+    fn non_subsequence_returns_none() {
+        assert_eq!(fuzzy_score("alice", "xyz"), None);
+    }
+
+    #[test]
+    fn empty_query_matches_everything_with_zero_score() {
+        assert_eq!(fuzzy_score("anything", ""), Some(0));
+    }
+}
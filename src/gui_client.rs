@@ -5,6 +5,7 @@ use egui::{Color32, RichText, Ui};
 use poll_promise::Promise;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
 #[derive(Default)]
@@ -16,9 +17,22 @@ pub struct ClientApp {
     // Session state
     username: String,
     is_logged_in: bool,
-    login_in_progress: Option<Promise<Result<(), String>>>,
+    login_in_progress: Option<Promise<Result<(String, Vec<crate::messages::ReceivedImageInfo>), String>>>,
     login_error: Option<String>,
     username_input: String,
+    /// Session token issued at login, carried forward so the per-request
+    /// ephemeral `Client`s constructed after login can still handshake
+    /// without re-registering. See `Client::new_with_token`.
+    session_token: Option<String>,
+
+    // Local encrypted session cache (request_history, received_images, thumbnails)
+    passphrase_input: String,
+    session_passphrase: Option<String>,
+    current_password_input: String,
+    new_password_input: String,
+    confirm_password_input: String,
+    password_change_status: Option<Result<(), String>>,
+    thumbnail_cache: std::collections::HashMap<String, Vec<u8>>,
 
     // Image upload state
     selected_image_path: Option<PathBuf>,
@@ -32,17 +46,27 @@ pub struct ClientApp {
     username_check_in_progress: Option<Promise<Result<bool, String>>>,
     username_check_error: Option<String>,
 
+    // Contacts directory: every username ever registered, with live online status
+    contacts: Vec<(String, bool)>,
+    contacts_loading: Option<Promise<Result<Vec<(String, bool)>, String>>>,
+    contact_search_input: String,
+
     // Request state
     current_request: Option<Promise<Result<EncryptionResult, String>>>,
+    current_request_started: Option<std::time::Instant>,
+    current_request_estimated_secs: f32,
     request_history: Vec<RequestHistoryItem>,
 
     // Send image state (after encryption)
     last_encrypted_result: Option<EncryptionResult>,
-    send_image_in_progress: Option<Promise<Result<String, String>>>,
+    send_image_in_progress: Option<Promise<Result<(String, Vec<(String, crate::messages::DeliveryState)>), String>>>,
 
-    // Received images state
+    // Received images state (paginated: `received_images` only ever holds
+    // pages already fetched, so scrolling back up never refetches them)
     received_images: Vec<crate::messages::ReceivedImageInfo>,
-    received_images_loading: Option<Promise<Result<Vec<crate::messages::ReceivedImageInfo>, String>>>,
+    received_images_loading: Option<Promise<Result<(Vec<crate::messages::ReceivedImageInfo>, bool), String>>>,
+    received_images_has_more: bool,
+    received_images_appending: bool,
     view_image_in_progress: Option<Promise<Result<(Vec<u8>, u32), String>>>,
     viewing_image: Option<(Vec<u8>, String, u32)>, // (image_data, image_id, remaining_views)
     viewing_image_texture: Option<egui::TextureHandle>,
@@ -50,6 +74,21 @@ pub struct ClientApp {
     // Tokio runtime
     runtime: Option<Arc<Runtime>>,
 
+    // mDNS node discovery: addresses found on the LAN, and whether each is
+    // currently included in `cloud_addresses`
+    mdns_browser: Option<crate::mdns_discovery::NodeBrowser>,
+    discovered_nodes: Vec<(String, bool)>,
+    manual_address_input: String,
+
+    // Real-time push notifications for new shares
+    notification_rx: Option<std::sync::mpsc::Receiver<crate::client::ClientEvent>>,
+    notification_connected: bool,
+    // image_ids the user hasn't opened the Received tab (or viewed) since
+    // arriving; drives both the tab's unread badge and per-item "new" marks
+    unseen_image_ids: std::collections::HashSet<String>,
+    received_images_loaded_once: bool,
+    last_fallback_poll: Option<std::time::Instant>,
+
     // UI state
     selected_tab: Tab,
     show_help: bool,
@@ -58,6 +97,7 @@ pub struct ClientApp {
 #[derive(PartialEq)]
 enum Tab {
     Upload,
+    Contacts,
     ReceivedImages,
     History,
     Settings,
@@ -78,14 +118,17 @@ struct EncryptionResult {
     duration_ms: u64,
 }
 
-#[derive(Clone)]
-struct RequestHistoryItem {
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct RequestHistoryItem {
     request_id: String,
     timestamp: String,
     success: bool,
     duration_ms: u64,
     image_path: String,
     users_count: usize,
+    /// Per-recipient outcome of the send, filled in once `send_image`
+    /// completes; empty until then (encryption alone doesn't send anything).
+    delivery: Vec<(String, crate::messages::DeliveryState)>,
 }
 
 impl ClientApp {
@@ -95,13 +138,26 @@ impl ClientApp {
             tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime"),
         );
 
+        let mdns_browser = match crate::mdns_discovery::NodeBrowser::start() {
+            Ok(browser) => Some(browser),
+            Err(e) => {
+                log::warn!("mDNS discovery unavailable, falling back to manual addresses only: {}", e);
+                None
+            }
+        };
+
         Self {
             client_id,
+            // Manual fallback addresses for nodes outside the local network;
+            // LAN nodes are populated automatically via mDNS discovery below.
             cloud_addresses: vec![
                 "10.40.98.68:8001".to_string(),
                 "10.40.98.127:8002".to_string(),
                 "10.40.98.225:8003".to_string(),
             ],
+            mdns_browser,
+            discovered_nodes: Vec::new(),
+            manual_address_input: String::new(),
             runtime: Some(runtime),
             viewing_quota: 5,
             available_usernames: vec![],
@@ -116,6 +172,34 @@ impl ClientApp {
         }
     }
 
+    /// Manual `cloud_addresses` plus every mDNS-discovered node the user
+    /// hasn't unchecked, deduplicated.
+    fn active_cloud_addresses(&self) -> Vec<String> {
+        let mut addresses = self.cloud_addresses.clone();
+        for (addr, included) in &self.discovered_nodes {
+            if *included && !addresses.contains(addr) {
+                addresses.push(addr.clone());
+            }
+        }
+        addresses
+    }
+
+    /// Persist request_history/received_images/thumbnails under the
+    /// passphrase set at login, if any. A no-op for clients that opted out
+    /// of an encrypted cache.
+    fn save_session_cache(&self) {
+        if let Some(passphrase) = &self.session_passphrase {
+            let data = crate::session_cache::SessionCacheData {
+                request_history: self.request_history.clone(),
+                received_images: self.received_images.clone(),
+                thumbnails: self.thumbnail_cache.clone(),
+            };
+            if let Err(e) = crate::session_cache::save(&self.client_id, passphrase, &data) {
+                log::warn!("Failed to save encrypted session cache: {}", e);
+            }
+        }
+    }
+
     fn attempt_login(&mut self) {
         let username = self.username_input.trim().to_string();
 
@@ -124,8 +208,33 @@ impl ClientApp {
             return;
         }
 
+        // If an encrypted cache already exists for this client, a passphrase
+        // is required and a bad one fails the login closed (GCM tag mismatch)
+        // rather than silently starting with an empty history.
+        if crate::session_cache::cache_exists(&self.client_id) {
+            if self.passphrase_input.is_empty() {
+                self.login_error = Some("This client has a saved session. Enter its passphrase to continue.".to_string());
+                return;
+            }
+
+            match crate::session_cache::load(&self.client_id, &self.passphrase_input) {
+                Ok(cache) => {
+                    self.request_history = cache.request_history;
+                    self.received_images = cache.received_images;
+                    self.thumbnail_cache = cache.thumbnails;
+                    self.session_passphrase = Some(self.passphrase_input.clone());
+                }
+                Err(e) => {
+                    self.login_error = Some(e);
+                    return;
+                }
+            }
+        } else if !self.passphrase_input.is_empty() {
+            self.session_passphrase = Some(self.passphrase_input.clone());
+        }
+
         let client_id = self.client_id.clone();
-        let cloud_addresses = self.cloud_addresses.clone();
+        let cloud_addresses = self.active_cloud_addresses();
         let runtime = self.runtime.as_ref().unwrap().clone();
 
         let promise = Promise::spawn_thread("login", move || {
@@ -169,6 +278,19 @@ impl ClientApp {
                 }
             });
 
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(200.0);
+                ui.label(RichText::new("Passphrase:").size(16.0));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.passphrase_input)
+                        .password(true)
+                        .desired_width(250.0)
+                        .hint_text("Optional: encrypts local history/cache"),
+                );
+            });
+
             ui.add_space(20.0);
 
             // Show error message if any
@@ -190,11 +312,32 @@ impl ClientApp {
                     }
                     Some(result) => {
                         match result {
-                            Ok(()) => {
+                            Ok((token, pending_images)) => {
                                 // Success! Set logged in state
                                 self.is_logged_in = true;
                                 self.username = self.username_input.clone();
+                                self.session_token = Some(token.clone());
                                 should_clear_progress = true;
+
+                                let client_id = self.client_id.parse().unwrap_or(1);
+                                let cloud_addresses = self.active_cloud_addresses();
+                                let runtime = self.runtime.as_ref().unwrap();
+                                self.notification_rx = Some(Client::spawn_notification_listener(
+                                    client_id,
+                                    cloud_addresses,
+                                    self.username.clone(),
+                                    runtime,
+                                ));
+
+                                // Anything the coordinator had queued for us
+                                // while we were offline (store-and-forward
+                                // flush) is unseen, same as a live push.
+                                for image in &pending_images {
+                                    self.unseen_image_ids.insert(image.image_id.clone());
+                                }
+
+                                // Drain any shares sent while we were offline.
+                                self.load_received_images();
                             }
                             Err(e) => {
                                 self.login_error = Some(e.clone());
@@ -261,17 +404,21 @@ impl ClientApp {
                 if let Some(path) = &self.selected_image_path {
                     ui.label(format!("Selected: {}", path.display()));
 
-                    // Show file size
+                    // Show file size, plus how many UDP fragments it'll take
                     if let Ok(metadata) = std::fs::metadata(path) {
                         let size_kb = metadata.len() / 1024;
-                        let color = if size_kb > 10 {
-                            Color32::from_rgb(255, 165, 0) // Orange warning
-                        } else {
-                            Color32::from_rgb(0, 200, 0) // Green OK
-                        };
-                        ui.label(RichText::new(format!("Size: {} KB", size_kb)).color(color));
-                        if size_kb > 10 {
-                            ui.label(RichText::new("⚠️ Large image - will be auto-compressed to ~10KB for UDP").color(Color32::from_rgb(255, 165, 0)).size(11.0));
+                        const FRAGMENT_SIZE_BYTES: u64 = 45_000; // keep in sync with chunking::CHUNK_SIZE
+                        const FRAGMENTS_PER_SEC: u64 = 50; // ~10ms pacing delay between outbound fragments
+                        let fragments = (metadata.len() / FRAGMENT_SIZE_BYTES).max(1);
+                        let est_seconds = fragments / FRAGMENTS_PER_SEC;
+
+                        ui.label(RichText::new(format!("Size: {} KB", size_kb)).color(Color32::from_rgb(0, 200, 0)));
+                        if fragments > 1 {
+                            ui.label(RichText::new(format!(
+                                "ℹ️ Will transfer as {} fragments (~{}s)",
+                                fragments,
+                                est_seconds.max(1)
+                            )).color(Color32::GRAY).size(11.0));
                         }
                     }
                 } else {
@@ -279,8 +426,6 @@ impl ClientApp {
                 }
             });
 
-            ui.label(RichText::new("⚠️ UDP requires tiny images! Max 10KB (request + response must both fit in 65KB)").color(Color32::from_rgb(255, 100, 100)).size(11.0));
-
             // Show image preview
             if let Some(texture) = &self.image_preview {
                 ui.add_space(10.0);
@@ -434,6 +579,13 @@ impl ClientApp {
                             ui.spinner();
                             ui.label("Processing encryption request...");
                         });
+
+                        if let Some(started) = self.current_request_started {
+                            let elapsed = started.elapsed().as_secs_f32();
+                            let progress = (elapsed / self.current_request_estimated_secs).min(0.95);
+                            ui.add(egui::ProgressBar::new(progress).show_percentage());
+                            ctx.request_repaint();
+                        }
                     }
                     Some(result) => {
                         match result {
@@ -502,11 +654,66 @@ impl ClientApp {
 
         if should_clear {
             self.current_request = None;
+            self.current_request_started = None;
         }
 
         if should_send_image {
             self.send_image_to_users();
         }
+
+        self.render_send_status(ui);
+    }
+
+    /// Show progress/outcome for `send_image_in_progress` and, once it
+    /// resolves, record the per-recipient delivery state on the matching
+    /// history entry (an offline recipient is `Pending`, not a failure).
+    fn render_send_status(&mut self, ui: &mut Ui) {
+        let mut should_clear = false;
+        let mut completed: Option<(String, Vec<(String, crate::messages::DeliveryState)>)> = None;
+
+        if let Some(promise) = &self.send_image_in_progress {
+            ui.group(|ui| {
+                ui.label(RichText::new("Send Status").size(16.0).strong());
+                ui.add_space(5.0);
+
+                match promise.ready() {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Sending to recipients...");
+                        });
+                    }
+                    Some(Ok((image_id, delivery))) => {
+                        for (username, state) in delivery {
+                            let (icon, color) = match state {
+                                crate::messages::DeliveryState::Delivered => ("✅ delivered", Color32::from_rgb(0, 200, 0)),
+                                crate::messages::DeliveryState::Pending => ("🕓 pending (offline)", Color32::from_rgb(255, 165, 0)),
+                                crate::messages::DeliveryState::Failed(_) => ("❌ failed", Color32::from_rgb(255, 0, 0)),
+                            };
+                            ui.label(RichText::new(format!("{}: {}", username, icon)).color(color));
+                        }
+                        completed = Some((image_id.clone(), delivery.clone()));
+                        should_clear = true;
+                    }
+                    Some(Err(e)) => {
+                        ui.label(RichText::new(format!("❌ Send failed: {}", e)).color(Color32::from_rgb(255, 0, 0)));
+                        should_clear = true;
+                    }
+                }
+            });
+        }
+
+        if should_clear {
+            self.send_image_in_progress = None;
+        }
+
+        if let Some((image_id, delivery)) = completed {
+            if let Some(item) = self.request_history.iter_mut().find(|i| i.request_id == image_id) {
+                item.success = delivery.iter().any(|(_, s)| !matches!(s, crate::messages::DeliveryState::Failed(_)));
+                item.delivery = delivery;
+            }
+            self.save_session_cache();
+        }
     }
 
     fn render_history_tab(&mut self, ui: &mut Ui) {
@@ -538,6 +745,20 @@ impl ClientApp {
                         ui.label(format!("👥 {} users", item.users_count));
                         ui.label(format!("⏱ {}ms", item.duration_ms));
                     });
+
+                    if !item.delivery.is_empty() {
+                        ui.add_space(3.0);
+                        ui.horizontal_wrapped(|ui| {
+                            for (username, state) in &item.delivery {
+                                let (icon, color) = match state {
+                                    crate::messages::DeliveryState::Delivered => ("✅", Color32::from_rgb(0, 200, 0)),
+                                    crate::messages::DeliveryState::Pending => ("🕓", Color32::from_rgb(255, 165, 0)),
+                                    crate::messages::DeliveryState::Failed(_) => ("❌", Color32::from_rgb(255, 0, 0)),
+                                };
+                                ui.label(RichText::new(format!("{} {}", icon, username)).color(color).size(12.0));
+                            }
+                        });
+                    }
                 });
 
                 ui.add_space(5.0);
@@ -567,7 +788,87 @@ impl ClientApp {
         ui.add_space(10.0);
 
         ui.group(|ui| {
-            ui.label(RichText::new("Cloud Nodes").size(16.0).strong());
+            ui.label(RichText::new("Local Session Cache").size(16.0).strong());
+            ui.label(RichText::new("Encrypted at rest with a passphrase (AES-GCM-SIV, scrypt key derivation)").size(11.0).color(Color32::GRAY));
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Status:");
+                if self.session_passphrase.is_some() {
+                    ui.label(RichText::new("Encrypted cache active").color(Color32::from_rgb(0, 200, 0)));
+                } else {
+                    ui.label(RichText::new("Not encrypted (history kept in memory only)").color(Color32::GRAY));
+                }
+            });
+
+            ui.add_space(5.0);
+            ui.label("Change Password:");
+            ui.horizontal(|ui| {
+                ui.label("Current:");
+                ui.add(egui::TextEdit::singleline(&mut self.current_password_input).password(true).desired_width(150.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("New:");
+                ui.add(egui::TextEdit::singleline(&mut self.new_password_input).password(true).desired_width(150.0));
+                ui.label("Confirm:");
+                ui.add(egui::TextEdit::singleline(&mut self.confirm_password_input).password(true).desired_width(150.0));
+            });
+
+            if ui.button("🔑 Re-encrypt Cache").clicked() {
+                let vault_exists = crate::session_cache::cache_exists(&self.client_id);
+
+                if vault_exists && self.current_password_input.is_empty() {
+                    self.password_change_status = Some(Err("Enter your current passphrase first".to_string()));
+                } else if vault_exists && Some(&self.current_password_input) != self.session_passphrase.as_ref() {
+                    self.password_change_status = Some(Err("Current passphrase is incorrect".to_string()));
+                } else if self.new_password_input.is_empty() {
+                    self.password_change_status = Some(Err("Enter a new passphrase first".to_string()));
+                } else if self.new_password_input != self.confirm_password_input {
+                    self.password_change_status = Some(Err("Passphrases do not match".to_string()));
+                } else {
+                    self.session_passphrase = Some(self.new_password_input.clone());
+                    self.save_session_cache();
+                    self.password_change_status = Some(Ok(()));
+                    self.current_password_input.clear();
+                    self.new_password_input.clear();
+                    self.confirm_password_input.clear();
+                }
+            }
+
+            if let Some(status) = &self.password_change_status {
+                match status {
+                    Ok(()) => ui.label(RichText::new("Cache re-encrypted under new passphrase").color(Color32::from_rgb(0, 200, 0))),
+                    Err(e) => ui.label(RichText::new(e).color(Color32::from_rgb(255, 100, 100))),
+                };
+            }
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Discovered Nodes").size(16.0).strong());
+            ui.label(RichText::new("Found automatically via mDNS on the local network").size(11.0).color(Color32::GRAY));
+            ui.add_space(5.0);
+
+            if self.mdns_browser.is_none() {
+                ui.label(RichText::new("mDNS discovery unavailable on this machine").color(Color32::from_rgb(255, 180, 50)));
+            } else if self.discovered_nodes.is_empty() {
+                ui.label("No nodes discovered yet...");
+            } else {
+                for (addr, included) in &mut self.discovered_nodes {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(included, "");
+                        ui.label(addr.as_str());
+                    });
+                }
+            }
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.label(RichText::new("Manual Cloud Nodes").size(16.0).strong());
+            ui.label(RichText::new("Fallback for nodes outside the local network").size(11.0).color(Color32::GRAY));
             ui.add_space(5.0);
 
             let mut to_remove = None;
@@ -586,9 +887,13 @@ impl ClientApp {
             }
 
             ui.add_space(5.0);
-            if ui.button("➕ Add Node").clicked() {
-                self.cloud_addresses.push(format!("127.0.0.1:{}", 8000 + self.cloud_addresses.len() + 1));
-            }
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.manual_address_input).on_hover_text("ip:port");
+                if ui.button("➕ Add Node").clicked() && !self.manual_address_input.trim().is_empty() {
+                    self.cloud_addresses.push(self.manual_address_input.trim().to_string());
+                    self.manual_address_input.clear();
+                }
+            });
         });
     }
 
@@ -610,9 +915,19 @@ impl ClientApp {
         let quota: u32 = self.viewing_quota;
         let client_id: usize = self.client_id.parse().unwrap_or(1);
         let client_username = self.username.clone();
-        let cloud_addresses = self.cloud_addresses.clone();
+        let cloud_addresses = self.active_cloud_addresses();
+        let session_token = self.session_token.clone();
         let runtime = self.runtime.as_ref().unwrap().clone();
 
+        // Same fragment-size/pacing constants as the upload-tab estimate, used
+        // here to drive a live progress bar while the transfer is in flight.
+        const FRAGMENT_SIZE_BYTES: u64 = 45_000;
+        const FRAGMENTS_PER_SEC: u64 = 50;
+        let image_len = std::fs::metadata(&image_path).map(|m| m.len()).unwrap_or(0);
+        let fragments = (image_len / FRAGMENT_SIZE_BYTES).max(1);
+        self.current_request_started = Some(std::time::Instant::now());
+        self.current_request_estimated_secs = (fragments / FRAGMENTS_PER_SEC).max(1) as f32;
+
         // Add to history
         let request_id = format!("client_{}_req_{}", client_id, chrono::Utc::now().timestamp());
         self.request_history.push(RequestHistoryItem {
@@ -622,7 +937,9 @@ impl ClientApp {
             duration_ms: 0,
             image_path: image_path.display().to_string(),
             users_count: usernames.len(),
+            delivery: vec![],
         });
+        self.save_session_cache();
 
         // Create promise for async request
         let promise = Promise::spawn_thread("encryption_request", move || {
@@ -634,12 +951,11 @@ impl ClientApp {
                 Err(e) => return Err(format!("Failed to read image: {}", e)),
             };
 
-            // UDP packet size limit is ~65KB, but we need room for:
-            // - JSON serialization overhead (~30% increase)
-            // - Request metadata (usernames, quota, request_id)
-            // - Response needs to fit the encrypted image back
-            // Limit to 10KB to ensure both request AND response fit
-            const MAX_IMAGE_SIZE: usize = 10 * 1024; // 10 KB
+            // Requests and responses are fragmented across multiple UDP
+            // datagrams (see ChunkedMessage), so we're no longer bound by a
+            // single 65KB packet. Still cap the size to keep transfers (and
+            // the number of chunks that can go missing) reasonable.
+            const MAX_IMAGE_SIZE: usize = 20 * 1024 * 1024; // 20 MB
 
             // Check if image is too large
             if image_data.len() > MAX_IMAGE_SIZE {
@@ -662,7 +978,7 @@ impl ClientApp {
                                 image_data = compressed;
                             } else {
                                 return Err(format!(
-                                    "Image too large for UDP! Original: {} KB, After compression: {} KB. Max allowed: {} KB.\n\nTip: Use a smaller image file, or resize it before uploading.",
+                                    "Image too large! Original: {} KB, After compression: {} KB. Max allowed: {} KB.\n\nTip: Use a smaller image file, or resize it before uploading.",
                                     image_data.len() / 1024,
                                     compressed.len() / 1024,
                                     MAX_IMAGE_SIZE / 1024
@@ -672,7 +988,7 @@ impl ClientApp {
                     }
                     Err(_) => {
                         return Err(format!(
-                            "Image too large for UDP! Size: {} KB, Max allowed: {} KB.\n\nThe image cannot be automatically compressed. Please:\n1. Use a smaller image file\n2. Resize the image before uploading\n3. Use a JPEG format for better compression",
+                            "Image too large! Size: {} KB, Max allowed: {} KB.\n\nThe image cannot be automatically compressed. Please:\n1. Use a smaller image file\n2. Resize the image before uploading\n3. Use a JPEG format for better compression",
                             image_data.len() / 1024,
                             MAX_IMAGE_SIZE / 1024
                         ));
@@ -684,7 +1000,10 @@ impl ClientApp {
             eprintln!("[DEBUG] Image size after processing: {} bytes ({} KB)", image_data.len(), image_data.len() / 1024);
 
             // Create client and send request
-            let client = Client::new(client_id, cloud_addresses);
+            let client = match session_token {
+                Some(token) => Client::new_with_token(client_id, cloud_addresses, client_username.clone(), token),
+                None => Client::new(client_id, cloud_addresses),
+            };
 
             let result = runtime.block_on(async move {
                 client
@@ -717,7 +1036,7 @@ impl ClientApp {
 
     fn check_username_availability(&mut self, username: String) {
         let client_id = self.client_id.parse().unwrap_or(1);
-        let cloud_addresses = self.cloud_addresses.clone();
+        let cloud_addresses = self.active_cloud_addresses();
         let runtime = self.runtime.as_ref().unwrap().clone();
 
         let promise = Promise::spawn_thread("check_username", move || {
@@ -730,6 +1049,114 @@ impl ClientApp {
         self.username_check_in_progress = Some(promise);
     }
 
+    fn load_contacts(&mut self) {
+        let client_id = self.client_id.parse().unwrap_or(1);
+        let cloud_addresses = self.active_cloud_addresses();
+        let runtime = self.runtime.as_ref().unwrap().clone();
+
+        let promise = Promise::spawn_thread("load_contacts", move || {
+            let client = Client::new(client_id, cloud_addresses);
+            runtime.block_on(async move { client.query_directory().await })
+        });
+
+        self.contacts_loading = Some(promise);
+    }
+
+    /// Whether `username` is currently checked in the shared
+    /// `available_usernames`/`selected_usernames` pair used by the Upload tab.
+    fn is_contact_selected(&self, username: &str) -> bool {
+        self.available_usernames
+            .iter()
+            .position(|u| u == username)
+            .map(|i| self.selected_usernames[i])
+            .unwrap_or(false)
+    }
+
+    /// Toggle `username` in/out of the authorized-user list, keeping
+    /// `available_usernames` and `selected_usernames` in sync the same way
+    /// manual entry in the Upload tab does.
+    fn set_contact_selected(&mut self, username: &str, selected: bool) {
+        if let Some(i) = self.available_usernames.iter().position(|u| u == username) {
+            self.selected_usernames[i] = selected;
+        } else if selected {
+            self.available_usernames.push(username.to_string());
+            self.selected_usernames.push(true);
+        }
+    }
+
+    fn render_contacts_tab(&mut self, ui: &mut Ui) {
+        ui.heading("👥 Contacts");
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.text_edit_singleline(&mut self.contact_search_input);
+            if ui.button("🔄 Refresh").clicked() && self.contacts_loading.is_none() {
+                self.load_contacts();
+            }
+        });
+
+        if let Some(promise) = &self.contacts_loading {
+            match promise.ready() {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Loading directory...");
+                    });
+                }
+                Some(result) => {
+                    match result {
+                        Ok(entries) => self.contacts = entries.clone(),
+                        Err(e) => {
+                            ui.label(RichText::new(format!("Failed to load contacts: {}", e)).color(Color32::from_rgb(255, 100, 100)));
+                        }
+                    }
+                    self.contacts_loading = None;
+                }
+            }
+        }
+
+        ui.add_space(10.0);
+
+        if self.contacts.is_empty() {
+            ui.label(RichText::new("No contacts loaded yet. Click Refresh.").color(Color32::GRAY));
+            return;
+        }
+
+        let query = self.contact_search_input.clone();
+        let mut matches: Vec<(&String, bool, i32)> = self
+            .contacts
+            .iter()
+            .filter(|(username, _)| username != &self.username)
+            .filter_map(|(username, online)| {
+                crate::fuzzy_match::fuzzy_score(username, &query).map(|score| (username, *online, score))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.2.cmp(&a.2));
+
+        let selections: Vec<(String, bool, bool)> = matches
+            .into_iter()
+            .map(|(username, online, _)| (username.clone(), online, self.is_contact_selected(username)))
+            .collect();
+
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            for (username, online, mut checked) in selections {
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut checked, "").changed() {
+                        self.set_contact_selected(&username, checked);
+                    }
+                    let dot_color = if online { Color32::from_rgb(0, 200, 0) } else { Color32::GRAY };
+                    ui.label(RichText::new("●").color(dot_color));
+                    ui.label(&username);
+                    ui.label(RichText::new(if online { "online" } else { "offline" }).color(Color32::GRAY).size(11.0));
+                });
+            }
+        });
+
+        ui.add_space(10.0);
+        ui.label(RichText::new("Selected contacts are added to the Upload tab's authorized users.").color(Color32::GRAY).size(11.0));
+    }
+
     fn send_image_to_users(&mut self) {
         let result = match &self.last_encrypted_result {
             Some(r) => r.clone(),
@@ -750,11 +1177,15 @@ impl ClientApp {
         let max_views = self.viewing_quota;
         let image_id = result.request_id.clone();
         let client_id = self.client_id.parse().unwrap_or(1);
-        let cloud_addresses = self.cloud_addresses.clone();
+        let cloud_addresses = self.active_cloud_addresses();
+        let session_token = self.session_token.clone();
         let runtime = self.runtime.as_ref().unwrap().clone();
 
         let promise = Promise::spawn_thread("send_image", move || {
-            let client = Client::new(client_id, cloud_addresses);
+            let client = match session_token {
+                Some(token) => Client::new_with_token(client_id, cloud_addresses, from_username.clone(), token),
+                None => Client::new(client_id, cloud_addresses),
+            };
             runtime.block_on(async move {
                 client.send_image(from_username, to_usernames, encrypted_image, max_views, image_id).await
             })
@@ -763,16 +1194,35 @@ impl ClientApp {
         self.send_image_in_progress = Some(promise);
     }
 
+    const RECEIVED_IMAGES_PAGE_SIZE: usize = 20;
+
+    /// Reload the first page, replacing whatever is currently shown.
     fn load_received_images(&mut self) {
+        self.received_images_appending = false;
+        self.fetch_received_images_page(0);
+    }
+
+    /// Fetch the next page and append it once loaded, for "load more on
+    /// scroll" rather than refetching everything already shown.
+    fn load_more_received_images(&mut self) {
+        if !self.received_images_has_more || self.received_images_loading.is_some() {
+            return;
+        }
+        self.received_images_appending = true;
+        self.fetch_received_images_page(self.received_images.len());
+    }
+
+    fn fetch_received_images_page(&mut self, offset: usize) {
         let username = self.username.clone();
         let client_id = self.client_id.parse().unwrap_or(1);
-        let cloud_addresses = self.cloud_addresses.clone();
+        let cloud_addresses = self.active_cloud_addresses();
         let runtime = self.runtime.as_ref().unwrap().clone();
+        let limit = Self::RECEIVED_IMAGES_PAGE_SIZE;
 
         let promise = Promise::spawn_thread("load_received_images", move || {
             let client = Client::new(client_id, cloud_addresses);
             runtime.block_on(async move {
-                client.query_received_images(username).await
+                client.query_received_images(username, offset, limit).await
             })
         });
 
@@ -780,9 +1230,11 @@ impl ClientApp {
     }
 
     fn view_received_image(&mut self, image_id: String) {
+        self.unseen_image_ids.remove(&image_id);
+
         let username = self.username.clone();
         let client_id = self.client_id.parse().unwrap_or(1);
-        let cloud_addresses = self.cloud_addresses.clone();
+        let cloud_addresses = self.active_cloud_addresses();
         let runtime = self.runtime.as_ref().unwrap().clone();
 
         let promise = Promise::spawn_thread("view_image", move || {
@@ -795,8 +1247,53 @@ impl ClientApp {
         self.view_image_in_progress = Some(promise);
     }
 
+    /// Drain whatever push-notification events have arrived since the last
+    /// frame: track connection state for the "reconnecting" indicator, and
+    /// refresh `received_images` (bumping the unread badge) on new shares.
+    /// When the subscription has been down for a while, fall back to the
+    /// same polling `render_received_images_tab`'s Refresh button uses.
+    fn drain_notification_events(&mut self) {
+        let Some(rx) = &self.notification_rx else { return };
+
+        let mut got_new_image = false;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                crate::client::ClientEvent::Connected => self.notification_connected = true,
+                crate::client::ClientEvent::Disconnected => self.notification_connected = false,
+                crate::client::ClientEvent::NewImage { from_username, image_id, .. } => {
+                    // Mark unseen before notifying, so a click on the
+                    // notification always finds consistent badge/list state.
+                    self.unseen_image_ids.insert(image_id.clone());
+                    crate::desktop_notify::notify_new_image(&from_username, &image_id);
+                    got_new_image = true;
+                }
+            }
+        }
+
+        if got_new_image && self.received_images_loading.is_none() {
+            self.load_received_images();
+        }
+
+        if !self.notification_connected && self.received_images_loading.is_none() {
+            let should_poll = match self.last_fallback_poll {
+                Some(t) => t.elapsed() >= Duration::from_secs(15),
+                None => true,
+            };
+            if should_poll {
+                self.last_fallback_poll = Some(std::time::Instant::now());
+                self.load_received_images();
+            }
+        }
+    }
+
     fn render_received_images_tab(&mut self, ui: &mut Ui, ctx: &egui::Context) {
         ui.heading("📬 Received Images");
+        self.unseen_image_ids.clear();
+
+        if !self.notification_connected {
+            ui.label(RichText::new("🔄 Reconnecting to live updates... (falling back to polling)").color(Color32::from_rgb(255, 165, 0)).size(11.0));
+        }
+
         ui.add_space(10.0);
 
         if ui.button("🔄 Refresh").clicked() {
@@ -817,8 +1314,29 @@ impl ClientApp {
                 }
                 Some(result) => {
                     match result {
-                        Ok(images) => {
-                            self.received_images = images.clone();
+                        Ok((images, has_more)) => {
+                            if self.received_images_appending {
+                                self.received_images.extend(images.iter().cloned());
+                            } else {
+                                // Fallback-polling path: the push listener
+                                // already marks unseen items live, but when
+                                // it's been down we only learn about new
+                                // shares here, so diff against what we knew.
+                                if self.received_images_loaded_once {
+                                    let previously_known: std::collections::HashSet<&str> =
+                                        self.received_images.iter().map(|i| i.image_id.as_str()).collect();
+                                    for img in images {
+                                        if !previously_known.contains(img.image_id.as_str()) {
+                                            self.unseen_image_ids.insert(img.image_id.clone());
+                                        }
+                                    }
+                                } else {
+                                    self.received_images_loaded_once = true;
+                                }
+                                self.received_images = images.clone();
+                            }
+                            self.received_images_has_more = *has_more;
+                            self.save_session_cache();
                         }
                         Err(e) => {
                             ui.label(RichText::new(format!("Error loading images: {}", e))
@@ -907,12 +1425,19 @@ impl ClientApp {
         if self.received_images.is_empty() {
             ui.label(RichText::new("No images received").color(Color32::GRAY));
         } else {
-            egui::ScrollArea::vertical().show(ui, |ui| {
+            let scroll_output = egui::ScrollArea::vertical().show(ui, |ui| {
                 for img_info in &self.received_images.clone() {
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
+                            crate::avatar::avatar_ui(ui, &img_info.from_username, 32.0);
+
                             ui.vertical(|ui| {
-                                ui.label(RichText::new(format!("From: {}", img_info.from_username)).strong());
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(format!("From: {}", img_info.from_username)).strong());
+                                    if self.unseen_image_ids.contains(&img_info.image_id) {
+                                        ui.label(RichText::new("NEW").color(Color32::from_rgb(255, 80, 80)).small().strong());
+                                    }
+                                });
                                 ui.label(format!("ID: {}", img_info.image_id));
                                 ui.label(format!("Remaining views: {}", img_info.remaining_views));
                             });
@@ -926,7 +1451,22 @@ impl ClientApp {
                     });
                     ui.add_space(5.0);
                 }
+
+                if self.received_images_has_more {
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 10.0);
+                        ui.spinner();
+                    });
+                }
             });
+
+            // Fire the next page once the user scrolls near the bottom,
+            // instead of requiring an explicit "load more" click.
+            let near_bottom = scroll_output.state.offset.y + scroll_output.inner_rect.height()
+                >= scroll_output.content_size.y - 100.0;
+            if near_bottom {
+                self.load_more_received_images();
+            }
         }
 
         // Show viewing image
@@ -958,6 +1498,18 @@ impl eframe::App for ClientApp {
         // Repaint continuously to update async operations
         ctx.request_repaint();
 
+        if let Some(browser) = &mut self.mdns_browser {
+            let update = browser.poll();
+            for found in update.discovered {
+                if !self.discovered_nodes.iter().any(|(addr, _)| *addr == found.address) {
+                    self.discovered_nodes.push((found.address, true));
+                }
+            }
+            self.discovered_nodes.retain(|(addr, _)| !update.removed.contains(addr));
+        }
+
+        self.drain_notification_events();
+
         // Show login screen if not logged in
         if !self.is_logged_in {
             egui::CentralPanel::default().show(ctx, |ui| {
@@ -978,9 +1530,10 @@ impl eframe::App for ClientApp {
 
                     ui.separator();
 
-                    ui.label(RichText::new(format!("👤 {}", self.username))
+                    ui.label(RichText::new(&self.username)
                         .color(Color32::from_rgb(0, 200, 255))
                         .strong());
+                    crate::avatar::avatar_ui(ui, &self.username, 24.0);
                 });
             });
         });
@@ -1001,7 +1554,13 @@ impl eframe::App for ClientApp {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.selectable_value(&mut self.selected_tab, Tab::Upload, "📤 Upload");
-                ui.selectable_value(&mut self.selected_tab, Tab::ReceivedImages, "📬 Received");
+                ui.selectable_value(&mut self.selected_tab, Tab::Contacts, "👥 Contacts");
+                let received_label = if !self.unseen_image_ids.is_empty() {
+                    format!("📬 Received ({})", self.unseen_image_ids.len())
+                } else {
+                    "📬 Received".to_string()
+                };
+                ui.selectable_value(&mut self.selected_tab, Tab::ReceivedImages, received_label);
                 ui.selectable_value(&mut self.selected_tab, Tab::History, "📜 History");
                 ui.selectable_value(&mut self.selected_tab, Tab::Settings, "⚙️ Settings");
             });
@@ -1011,6 +1570,7 @@ impl eframe::App for ClientApp {
 
             match self.selected_tab {
                 Tab::Upload => self.render_upload_tab(ui, ctx),
+                Tab::Contacts => self.render_contacts_tab(ui),
                 Tab::ReceivedImages => self.render_received_images_tab(ui, ctx),
                 Tab::History => self.render_history_tab(ui),
                 Tab::Settings => self.render_settings_tab(ui),
@@ -0,0 +1,176 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Software version advertised during the handshake, so peers can log/react
+/// to version skew without guessing from behavior.
+pub const SOFTWARE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A node's long-term cryptographic identity. The `NodeId` is derived from
+/// the public key so it can't be spoofed by simply picking a CLI integer.
+pub struct NodeIdentity {
+    pub signing_key: ed25519_dalek::SigningKey,
+}
+
+impl NodeIdentity {
+    /// Load a persisted identity from `path`, or generate and persist a new
+    /// one if none exists yet. The key is stored as raw bytes; this is a
+    /// local node secret and is never sent over the wire.
+    pub fn load_or_generate(path: &Path) -> std::io::Result<Self> {
+        if let Ok(bytes) = fs::read(path) {
+            if bytes.len() == 32 {
+                let mut key_bytes = [0u8; 32];
+                key_bytes.copy_from_slice(&bytes);
+                info!("Loaded node identity from {}", path.display());
+                return Ok(Self {
+                    signing_key: ed25519_dalek::SigningKey::from_bytes(&key_bytes),
+                });
+            }
+            warn!("Identity file at {} is malformed, regenerating", path.display());
+        }
+
+        let signing_key = ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, signing_key.to_bytes())?;
+        info!("Generated new node identity, persisted to {}", path.display());
+
+        Ok(Self { signing_key })
+    }
+
+    /// Derive the stable `NodeId` from the public key: the first 4 key bytes
+    /// read big-endian, which is stable across restarts as long as the key file
+    /// survives, and collision-resistant enough for a hand-sized cluster.
+    pub fn node_id(&self) -> crate::messages::NodeId {
+        node_id_from_public_key(&self.signing_key.verifying_key().to_bytes())
+    }
+
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        use ed25519_dalek::Signer;
+        self.signing_key.sign(message).to_bytes()
+    }
+}
+
+/// Metadata a node announces about itself right after connecting, so peers
+/// learn addresses/capabilities/version instead of inferring them
+/// positionally from CLI argument order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInformation {
+    pub public_key: [u8; 32],
+    pub advertised_addresses: Vec<String>,
+    pub software_version: String,
+    pub capabilities: Vec<String>,
+}
+
+/// A handshake message proving ownership of the node's private key: the
+/// signature covers the nonce the peer sent, so it can't be replayed against
+/// a different challenge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingProof {
+    pub node_information: NodeInformation,
+    pub nonce: [u8; 16],
+    pub signature: [u8; 64],
+}
+
+impl PairingProof {
+    pub fn new(identity: &NodeIdentity, advertised_addresses: Vec<String>, nonce: [u8; 16]) -> Self {
+        let node_information = NodeInformation {
+            public_key: identity.public_key_bytes(),
+            advertised_addresses,
+            software_version: SOFTWARE_VERSION.to_string(),
+            capabilities: vec!["encryption".to_string(), "election".to_string()],
+        };
+
+        let signature = identity.sign(&nonce);
+
+        Self {
+            node_information,
+            nonce,
+            signature,
+        }
+    }
+
+    /// Verify the proof actually came from the holder of `public_key`.
+    pub fn verify(&self) -> bool {
+        verify_signature(&self.node_information.public_key, &self.nonce, &self.signature)
+    }
+}
+
+/// Verify that `signature` is a valid ed25519 signature over `message` made
+/// by the holder of `public_key_bytes`. Shared by `PairingProof::verify` and
+/// anything else (e.g. `encryption`'s metadata signing) that needs to check a
+/// signature against an arbitrary, already-known public key.
+pub fn verify_signature(public_key_bytes: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let Ok(verifying_key) = VerifyingKey::from_bytes(public_key_bytes) else {
+        return false;
+    };
+    let signature = Signature::from_bytes(signature);
+
+    verifying_key.verify(message, &signature).is_ok()
+}
+
+/// Derive a stable `NodeId` from a public key: the first 4 key bytes, read
+/// big-endian. Shared between `NodeIdentity::node_id` and pairing verification so
+/// both sides agree on what ID a given public key claims.
+pub fn node_id_from_public_key(public_bytes: &[u8; 32]) -> crate::messages::NodeId {
+    u32::from_be_bytes([public_bytes[0], public_bytes[1], public_bytes[2], public_bytes[3]])
+}
+
+/// Default location for the persisted node key, namespaced by bind address so
+/// multiple local demo nodes on one machine don't clobber each other's keys.
+pub fn default_identity_path(node_address: &str) -> PathBuf {
+    let safe_name = node_address.replace([':', '.'], "_");
+    PathBuf::from(format!(".node_identity_{}.key", safe_name))
+}
+
+/// Default location for a client's persisted static key (see
+/// `Client::ensure_secure_session` in `client.rs`), namespaced by client id
+/// so multiple local `Client` instances on one machine don't clobber each
+/// other's keys - same convention as `default_identity_path` for nodes.
+pub fn default_client_identity_path(client_id: usize) -> PathBuf {
+    PathBuf::from(format!(".client_identity_{}.key", client_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairing_proof_round_trips() {
+        let identity = NodeIdentity {
+            signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng),
+        };
+
+        let proof = PairingProof::new(&identity, vec!["127.0.0.1:8001".to_string()], [7u8; 16]);
+        assert!(proof.verify());
+    }
+
+    #[test]
+    fn tampered_proof_fails_verification() {
+        let identity = NodeIdentity {
+            signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng),
+        };
+
+        let mut proof = PairingProof::new(&identity, vec!["127.0.0.1:8001".to_string()], [7u8; 16]);
+        proof.nonce[0] ^= 0xFF;
+
+        assert!(!proof.verify());
+    }
+
+    #[test]
+    fn node_id_is_stable_for_same_key() {
+        let identity = NodeIdentity {
+            signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng),
+        };
+
+        assert_eq!(identity.node_id(), identity.node_id());
+    }
+}
@@ -0,0 +1,242 @@
+//! Spill-to-disk storage for large stored images, borrowed from Garage's
+//! block storage model: `CloudNode::stored_images` used to hold every
+//! image's bytes fully in memory and lost them on restart. Images at or
+//! above `INLINE_THRESHOLD` are now zstd-compressed with a streaming
+//! encoder and written to their own file (named after
+//! `image_id`) under the store directory, with `node::StoredImage` holding
+//! only the file name plus the compressed/uncompressed sizes instead of
+//! the bytes themselves; anything smaller stays inline, same as before.
+//! A periodic JSON index (same convention as `peer_store`/`user_directory`)
+//! lets the whole `stored_images` map be reloaded on restart - inline
+//! images round-trip through the index directly, spilled ones are read
+//! back from their blob file on demand via `ImageStore::get`.
+
+use crate::upload_session::is_valid_path_segment;
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Images smaller than this stay inline in a `StoredImage`'s blob; anything
+/// at or above it is compressed and spilled to its own file.
+pub const INLINE_THRESHOLD: usize = 3 * 1024;
+
+/// zstd level used for spilled blobs. Deliberately modest rather than
+/// zstd's max - these bytes are already encrypted, so ciphertext barely
+/// compresses and a high level would just burn CPU for little size win.
+pub const ZSTD_LEVEL: i32 = 3;
+
+/// Where a stored image's bytes actually live.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ImageBlob {
+    Inline(Vec<u8>),
+    Spilled {
+        file_name: String,
+        compressed_len: usize,
+        uncompressed_len: usize,
+    },
+}
+
+/// Reads/writes spilled blob files under `dir` plus the periodic JSON
+/// metadata index at `index_path`, both namespaced to one node (see
+/// `default_image_store_dir`/`default_image_index_path`).
+pub struct ImageStore {
+    dir: PathBuf,
+    index_path: PathBuf,
+}
+
+impl ImageStore {
+    pub fn new(dir: PathBuf, index_path: PathBuf) -> Self {
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            warn!("Failed to create image store directory {}: {}", dir.display(), e);
+        }
+        Self { dir, index_path }
+    }
+
+    /// Store `data`, inline if it's under `INLINE_THRESHOLD`, otherwise
+    /// zstd-compressed to its own file named after `image_id`. Falls back
+    /// to inline storage if `image_id` isn't a safe path segment, or if
+    /// compression or the write fails, rather than losing the image
+    /// outright - callers that can reject the request outright (see
+    /// `Node`'s `SendImage`/`ReplicationPush` handlers) should do so before
+    /// ever reaching this far, this is a last-resort guard against
+    /// `PathBuf::join` ever seeing an unsafe `image_id`.
+    pub fn put(&self, image_id: &str, data: Vec<u8>) -> ImageBlob {
+        if data.len() < INLINE_THRESHOLD {
+            return ImageBlob::Inline(data);
+        }
+
+        if !is_valid_path_segment(image_id) {
+            warn!("Refusing to spill image with unsafe id '{}' to disk - keeping it inline instead", image_id);
+            return ImageBlob::Inline(data);
+        }
+
+        match zstd::stream::encode_all(data.as_slice(), ZSTD_LEVEL) {
+            Ok(compressed) => {
+                let file_name = format!("{}.zst", image_id);
+                let path = self.dir.join(&file_name);
+                match std::fs::File::create(&path).and_then(|mut f| f.write_all(&compressed)) {
+                    Ok(()) => ImageBlob::Spilled {
+                        file_name,
+                        compressed_len: compressed.len(),
+                        uncompressed_len: data.len(),
+                    },
+                    Err(e) => {
+                        warn!(
+                            "Failed to write spilled image {} to {}: {} - keeping it inline instead",
+                            image_id, path.display(), e
+                        );
+                        ImageBlob::Inline(data)
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("Failed to compress image {} for spilling ({}) - keeping it inline instead", image_id, e);
+                ImageBlob::Inline(data)
+            }
+        }
+    }
+
+    /// Resolve a blob back to its bytes, decompressing a spilled file if needed.
+    pub fn get(&self, blob: &ImageBlob) -> Result<Vec<u8>, String> {
+        match blob {
+            ImageBlob::Inline(data) => Ok(data.clone()),
+            ImageBlob::Spilled { file_name, .. } => {
+                let path = self.dir.join(file_name);
+                let compressed = std::fs::read(&path)
+                    .map_err(|e| format!("Failed to read spilled image {}: {}", path.display(), e))?;
+                zstd::stream::decode_all(compressed.as_slice())
+                    .map_err(|e| format!("Failed to decompress spilled image {}: {}", path.display(), e))
+            }
+        }
+    }
+
+    /// Delete a spilled blob's file (a no-op for inline blobs), e.g. once a
+    /// replicated record replaces it. Best-effort: an orphaned file on
+    /// error just wastes a little disk, which matters less than failing
+    /// whatever update triggered the removal.
+    pub fn remove(&self, blob: &ImageBlob) {
+        if let ImageBlob::Spilled { file_name, .. } = blob {
+            let path = self.dir.join(file_name);
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove spilled image file {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Bytes spilled-blob files currently occupy under `dir`, for the
+    /// admin-facing free-capacity report. Inline blobs aren't counted - they
+    /// live in the JSON index/in memory rather than as their own file.
+    pub fn spilled_bytes_on_disk(&self) -> u64 {
+        std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries.filter_map(Result::ok).filter_map(|entry| entry.metadata().ok()).map(|meta| meta.len()).sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Load the metadata index (everything needed to repopulate
+    /// `CloudNode::stored_images`, inline bytes and all) from disk, or an
+    /// empty map if it doesn't exist yet or is corrupt.
+    pub fn load_index<T: DeserializeOwned>(&self) -> HashMap<String, Vec<T>> {
+        match std::fs::read_to_string(&self.index_path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Image store index at {} is corrupt ({}), starting empty", self.index_path.display(), e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    /// Persist the metadata index to disk. Errors are logged but non-fatal:
+    /// losing the index just means stored images aren't recovered on the
+    /// next restart (their spilled blob files on disk are unaffected either way).
+    pub fn save_index<T: Serialize>(&self, images: &HashMap<String, Vec<T>>) {
+        match serde_json::to_string_pretty(images) {
+            Ok(json) => {
+                if let Some(parent) = self.index_path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Err(e) = std::fs::write(&self.index_path, json) {
+                    warn!("Failed to write image store index to {}: {}", self.index_path.display(), e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize image store index: {}", e),
+        }
+    }
+}
+
+/// Default on-disk directory for a node's spilled image blobs, namespaced
+/// by its own bind address - same convention as `peer_store::default_peer_store_path`.
+pub fn default_image_store_dir(node_address: &str) -> PathBuf {
+    let safe_name = node_address.replace([':', '.'], "_");
+    PathBuf::from(format!(".image_store_{}", safe_name))
+}
+
+/// Default on-disk location for a node's image metadata index.
+pub fn default_image_index_path(node_address: &str) -> PathBuf {
+    let safe_name = node_address.replace([':', '.'], "_");
+    PathBuf::from(format!(".image_index_{}.json", safe_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_payloads_stay_inline() {
+        let dir = std::env::temp_dir().join(format!("image_store_test1_{}", std::process::id()));
+        let store = ImageStore::new(dir.join("blobs"), dir.join("index.json"));
+
+        let blob = store.put("img-1", vec![1, 2, 3]);
+        assert!(matches!(blob, ImageBlob::Inline(_)));
+        assert_eq!(store.get(&blob).unwrap(), vec![1, 2, 3]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn large_payloads_spill_and_round_trip() {
+        let dir = std::env::temp_dir().join(format!("image_store_test2_{}", std::process::id()));
+        let store = ImageStore::new(dir.join("blobs"), dir.join("index.json"));
+
+        let data = vec![42u8; INLINE_THRESHOLD + 1];
+        let blob = store.put("img-2", data.clone());
+        assert!(matches!(blob, ImageBlob::Spilled { .. }));
+        assert_eq!(store.get(&blob).unwrap(), data);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unsafe_image_id_stays_inline_instead_of_spilling() {
+        let dir = std::env::temp_dir().join(format!("image_store_test4_{}", std::process::id()));
+        let store = ImageStore::new(dir.join("blobs"), dir.join("index.json"));
+
+        let data = vec![42u8; INLINE_THRESHOLD + 1];
+        let blob = store.put("../../../../etc/cron.d/evil", data.clone());
+        assert!(matches!(blob, ImageBlob::Inline(_)));
+        assert_eq!(store.get(&blob).unwrap(), data);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn index_round_trips_through_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("image_store_test3_{}", std::process::id()));
+        let store = ImageStore::new(dir.join("blobs"), dir.join("index.json"));
+
+        let mut images: HashMap<String, Vec<ImageBlob>> = HashMap::new();
+        images.insert("alice".to_string(), vec![ImageBlob::Inline(vec![9, 9])]);
+        store.save_index(&images);
+
+        let reloaded: HashMap<String, Vec<ImageBlob>> = store.load_index();
+        assert_eq!(reloaded.get("alice").unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
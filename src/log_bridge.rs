@@ -0,0 +1,64 @@
+use log::{Level, Log, Metadata, Record};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+
+/// How many captured log lines the GUI can be behind on before new ones are
+/// dropped rather than blocking the thread that logged them.
+const CAPTURE_QUEUE_CAPACITY: usize = 1024;
+
+/// One crate-wide log event, captured off the global `log` facade for the
+/// Logs tab to render - this is what makes that tab a real observability
+/// surface instead of a mockup.
+pub struct CapturedLog {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Wraps a plain `env_logger` logger (so stdout output is unaffected) and
+/// mirrors every record that passes its filter onto a channel the GUI
+/// drains each frame.
+struct GuiLogBridge {
+    inner: env_logger::Logger,
+    tx: SyncSender<CapturedLog>,
+}
+
+impl Log for GuiLogBridge {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+            let captured = CapturedLog {
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            };
+            let _ = self.tx.try_send(captured);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the GUI's global logger in place of a plain `env_logger::init()`,
+/// returning the receiving end of the channel every record across the
+/// crate is mirrored onto. Must be called at most once per process - the
+/// same restriction `log::set_boxed_logger` itself imposes.
+pub fn install() -> Receiver<CapturedLog> {
+    let inner =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).build();
+    let max_level = inner.filter();
+
+    let (tx, rx) = mpsc::sync_channel(CAPTURE_QUEUE_CAPACITY);
+    let bridge = GuiLogBridge { inner, tx };
+
+    if log::set_boxed_logger(Box::new(bridge)).is_ok() {
+        log::set_max_level(max_level);
+    }
+
+    rx
+}
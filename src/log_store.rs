@@ -0,0 +1,310 @@
+use crate::gui_server::{LogEntry, LogLevel};
+use crate::messages::NodeId;
+use crate::worker_registry::{WorkerControl, WorkerRegistry, WorkerStatus};
+use log::warn;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// How many pending writes the channel holds before `record_log`/
+/// `record_election` start dropping entries rather than blocking the
+/// caller. Generous enough to absorb a burst without the UI ever waiting
+/// on disk I/O.
+const WRITE_QUEUE_CAPACITY: usize = 512;
+
+/// A coordinator election as observed by the monitor: the winner, its
+/// winning load, and every node that was part of the cluster at the time.
+#[derive(Debug, Clone)]
+pub struct ElectionRecord {
+    pub timestamp: String,
+    pub winner_node_id: NodeId,
+    pub winning_load: f64,
+    pub participants: Vec<NodeId>,
+}
+
+enum StoreWrite {
+    Log(LogEntry),
+    Election(ElectionRecord),
+}
+
+/// Handle held by `ServerMonitorApp` to enqueue writes. Cheap to clone;
+/// the actual `rusqlite::Connection` lives only inside the writer task
+/// spawned by `spawn`.
+#[derive(Clone)]
+pub struct LogStoreHandle {
+    tx: mpsc::Sender<StoreWrite>,
+}
+
+impl LogStoreHandle {
+    /// Queue a log entry for persistence. Non-blocking: if the writer is
+    /// backed up past `WRITE_QUEUE_CAPACITY`, the entry is dropped and a
+    /// warning is logged rather than stalling the caller.
+    pub fn record_log(&self, entry: LogEntry) {
+        if self.tx.try_send(StoreWrite::Log(entry)).is_err() {
+            warn!("Log store queue full or closed; dropping a log entry");
+        }
+    }
+
+    /// Queue an election record for persistence. Same non-blocking
+    /// semantics as `record_log`.
+    pub fn record_election(&self, record: ElectionRecord) {
+        if self.tx.try_send(StoreWrite::Election(record)).is_err() {
+            warn!("Log store queue full or closed; dropping an election record");
+        }
+    }
+}
+
+/// Schema migrations, applied in order past whatever `PRAGMA user_version`
+/// the database is already at. Each entry is one version; add new ones to
+/// the end rather than editing earlier ones once shipped.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE log_entries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        level TEXT NOT NULL,
+        message TEXT NOT NULL
+    );
+    CREATE INDEX idx_log_entries_timestamp ON log_entries(timestamp);
+
+    CREATE TABLE elections (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        winner_node_id INTEGER NOT NULL,
+        winning_load REAL NOT NULL,
+        participants TEXT NOT NULL
+    );
+    CREATE INDEX idx_elections_timestamp ON elections(timestamp);
+    CREATE INDEX idx_elections_winner ON elections(winner_node_id);",
+];
+
+fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate() {
+        let version = (i + 1) as i64;
+        if version > current {
+            conn.execute_batch(migration)?;
+            conn.pragma_update(None, "user_version", version)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn level_to_str(level: &LogLevel) -> &'static str {
+    match level {
+        LogLevel::Info => "info",
+        LogLevel::Warning => "warning",
+        LogLevel::Error => "error",
+        LogLevel::Debug => "debug",
+    }
+}
+
+fn parse_level(s: &str) -> LogLevel {
+    match s {
+        "warning" => LogLevel::Warning,
+        "error" => LogLevel::Error,
+        "debug" => LogLevel::Debug,
+        _ => LogLevel::Info,
+    }
+}
+
+fn insert_log_entry(conn: &Connection, entry: &LogEntry) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO log_entries (timestamp, level, message) VALUES (?1, ?2, ?3)",
+        (&entry.timestamp, level_to_str(&entry.level), &entry.message),
+    )?;
+    Ok(())
+}
+
+fn insert_election(conn: &Connection, record: &ElectionRecord) -> rusqlite::Result<()> {
+    let participants = serde_json::to_string(&record.participants).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT INTO elections (timestamp, winner_node_id, winning_load, participants) VALUES (?1, ?2, ?3, ?4)",
+        (&record.timestamp, record.winner_node_id, record.winning_load, participants),
+    )?;
+    Ok(())
+}
+
+/// Open (creating if needed) the SQLite store at `db_path`, run any
+/// pending migrations, and spawn the dedicated writer task that drains the
+/// bounded channel onto disk. Returns a `LogStoreHandle` for callers to
+/// enqueue writes with; intended to run on the caller's existing Tokio
+/// runtime, same as the stats poller. Registers itself with `registry` as
+/// "Log Store Writer" so the Workers tab can see and pause/cancel it.
+pub fn spawn(db_path: PathBuf, registry: WorkerRegistry, runtime: &tokio::runtime::Runtime) -> LogStoreHandle {
+    let (tx, mut rx) = mpsc::channel::<StoreWrite>(WRITE_QUEUE_CAPACITY);
+
+    runtime.spawn(async move {
+        let (reporter, mut control_rx) = registry.register("Log Store Writer").await;
+
+        let conn = match Connection::open(&db_path) {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Failed to open log store at {}: {}", db_path.display(), e);
+                reporter.report_error(e).await;
+                reporter.set_status(WorkerStatus::Dead).await;
+                return;
+            }
+        };
+
+        if let Err(e) = run_migrations(&conn) {
+            warn!("Failed to migrate log store schema at {}: {}", db_path.display(), e);
+            reporter.report_error(e).await;
+            reporter.set_status(WorkerStatus::Dead).await;
+            return;
+        }
+
+        reporter.set_status(WorkerStatus::Idle).await;
+        let mut paused = false;
+
+        loop {
+            tokio::select! {
+                write = rx.recv(), if !paused => {
+                    match write {
+                        Some(write) => {
+                            reporter.set_status(WorkerStatus::Active).await;
+                            let result = match &write {
+                                StoreWrite::Log(entry) => insert_log_entry(&conn, entry),
+                                StoreWrite::Election(record) => insert_election(&conn, record),
+                            };
+                            if let Err(e) = result {
+                                warn!("Log store write failed: {}", e);
+                                reporter.report_error(e).await;
+                            }
+                            reporter.tick().await;
+                            reporter.set_status(WorkerStatus::Idle).await;
+                        }
+                        None => break,
+                    }
+                }
+                cmd = control_rx.recv() => {
+                    match cmd {
+                        Some(WorkerControl::Pause) => paused = true,
+                        Some(WorkerControl::Resume) => paused = false,
+                        Some(WorkerControl::Cancel) | None => break,
+                    }
+                }
+            }
+        }
+
+        reporter.set_status(WorkerStatus::Dead).await;
+    });
+
+    LogStoreHandle { tx }
+}
+
+/// Load the most recent `limit` log entries from disk, oldest first (same
+/// ordering as the live in-memory deque). Used by the Logs tab's "load
+/// history" control to see past the live `MAX_LOG_ENTRIES` window.
+pub fn load_recent_logs(db_path: &Path, limit: usize) -> Vec<LogEntry> {
+    match query_recent_logs(db_path, limit) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to load historical logs from {}: {}", db_path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+fn query_recent_logs(db_path: &Path, limit: usize) -> rusqlite::Result<Vec<LogEntry>> {
+    let conn = Connection::open(db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, level, message FROM log_entries ORDER BY id DESC LIMIT ?1",
+    )?;
+    let rows = stmt.query_map([limit as i64], |row| {
+        let timestamp: String = row.get(0)?;
+        let level: String = row.get(1)?;
+        let message: String = row.get(2)?;
+        Ok(LogEntry { timestamp, level: parse_level(&level), message })
+    })?;
+
+    let mut entries: Vec<LogEntry> = rows.filter_map(Result::ok).collect();
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Load the most recent `limit` election records from disk, newest first,
+/// for the Metrics tab's "Recent Elections" table.
+pub fn load_recent_elections(db_path: &Path, limit: usize) -> Vec<ElectionRecord> {
+    match query_recent_elections(db_path, limit) {
+        Ok(records) => records,
+        Err(e) => {
+            warn!("Failed to load election history from {}: {}", db_path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+fn query_recent_elections(db_path: &Path, limit: usize) -> rusqlite::Result<Vec<ElectionRecord>> {
+    let conn = Connection::open(db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, winner_node_id, winning_load, participants FROM elections ORDER BY id DESC LIMIT ?1",
+    )?;
+    let rows = stmt.query_map([limit as i64], |row| {
+        let timestamp: String = row.get(0)?;
+        let winner_node_id: NodeId = row.get(1)?;
+        let winning_load: f64 = row.get(2)?;
+        let participants_json: String = row.get(3)?;
+        let participants: Vec<NodeId> = serde_json::from_str(&participants_json).unwrap_or_default();
+        Ok(ElectionRecord { timestamp, winner_node_id, winning_load, participants })
+    })?;
+
+    Ok(rows.filter_map(Result::ok).collect())
+}
+
+/// Default on-disk location for the monitor's log/election store.
+pub fn default_log_store_path() -> PathBuf {
+    PathBuf::from("server_monitor.db")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        run_migrations(&conn).unwrap(); // must not error on a second pass
+
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn insert_then_query_log_entries_round_trips() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        insert_log_entry(&conn, &LogEntry {
+            timestamp: "12:00:00".to_string(),
+            level: LogLevel::Warning,
+            message: "node 2 failed".to_string(),
+        }).unwrap();
+
+        let level: String = conn
+            .query_row("SELECT level FROM log_entries WHERE message = 'node 2 failed'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(level, "warning");
+    }
+
+    #[test]
+    fn insert_then_query_elections_round_trips() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        insert_election(&conn, &ElectionRecord {
+            timestamp: "12:00:05".to_string(),
+            winner_node_id: 2,
+            winning_load: 0.35,
+            participants: vec![1, 2, 3],
+        }).unwrap();
+
+        let participants_json: String = conn
+            .query_row("SELECT participants FROM elections WHERE winner_node_id = 2", [], |row| row.get(0))
+            .unwrap();
+        let participants: Vec<NodeId> = serde_json::from_str(&participants_json).unwrap();
+        assert_eq!(participants, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,121 @@
+use log::{debug, info, warn};
+use std::collections::HashMap;
+
+/// mDNS service type cloud nodes advertise themselves under, so clients on
+/// the same LAN can find them without hand-typed IPs.
+pub const SERVICE_TYPE: &str = "_imgcloud._udp.local.";
+
+/// Advertise this node under `SERVICE_TYPE` so LAN clients can discover it.
+/// Returns the running daemon; dropping it withdraws the advertisement.
+pub fn advertise_node(node_id: crate::messages::NodeId, bind_address: &str) -> Result<mdns_sd::ServiceDaemon, String> {
+    let daemon = mdns_sd::ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS daemon: {}", e))?;
+
+    let (host, port) = bind_address
+        .rsplit_once(':')
+        .ok_or_else(|| format!("Invalid bind address: {}", bind_address))?;
+    let port: u16 = port.parse().map_err(|e| format!("Invalid port in {}: {}", bind_address, e))?;
+
+    let instance_name = format!("node-{}", node_id);
+    let host_name = format!("{}.local.", instance_name);
+
+    let service = mdns_sd::ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance_name,
+        &host_name,
+        host,
+        port,
+        HashMap::<String, String>::new(),
+    )
+    .map_err(|e| format!("Failed to build mDNS service info: {}", e))?;
+
+    daemon
+        .register(service)
+        .map_err(|e| format!("Failed to register mDNS service: {}", e))?;
+
+    info!("Advertising Node {} via mDNS as {}", node_id, instance_name);
+    Ok(daemon)
+}
+
+/// A cloud node address learned via mDNS browsing.
+#[derive(Debug, Clone)]
+pub struct DiscoveredNode {
+    pub instance_name: String,
+    pub address: String,
+}
+
+/// Result of draining mDNS browse events since the last `poll`.
+#[derive(Debug, Default)]
+pub struct DiscoveryUpdate {
+    pub discovered: Vec<DiscoveredNode>,
+    /// Addresses of nodes whose mDNS advertisement just went away (e.g. the
+    /// node process exited), so callers can drop them from their live list.
+    pub removed: Vec<String>,
+}
+
+/// Browses for `SERVICE_TYPE` instances. Non-blocking: call `poll` from the
+/// UI loop to drain whatever has arrived since the last call.
+pub struct NodeBrowser {
+    receiver: mdns_sd::Receiver<mdns_sd::ServiceEvent>,
+    _daemon: mdns_sd::ServiceDaemon,
+    // Tracks resolved addresses per instance fullname, so a later
+    // ServiceRemoved (which only carries the fullname) can be translated
+    // back into the addresses a caller actually cares about.
+    resolved: HashMap<String, Vec<String>>,
+}
+
+impl NodeBrowser {
+    pub fn start() -> Result<Self, String> {
+        let daemon = mdns_sd::ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS daemon: {}", e))?;
+        let receiver = daemon
+            .browse(SERVICE_TYPE)
+            .map_err(|e| format!("Failed to browse for {}: {}", SERVICE_TYPE, e))?;
+
+        Ok(Self { receiver, _daemon: daemon, resolved: HashMap::new() })
+    }
+
+    /// Drain any discovery events received since the last poll, returning
+    /// newly-resolved node addresses and any that just went offline. Safe to
+    /// call every UI frame.
+    pub fn poll(&mut self) -> DiscoveryUpdate {
+        let mut update = DiscoveryUpdate::default();
+
+        while let Ok(event) = self.receiver.try_recv() {
+            match event {
+                mdns_sd::ServiceEvent::ServiceResolved(info) => {
+                    let fullname = info.get_fullname().to_string();
+                    let mut addresses = Vec::new();
+                    for addr in info.get_addresses() {
+                        let address = format!("{}:{}", addr, info.get_port());
+                        addresses.push(address.clone());
+                        update.discovered.push(DiscoveredNode {
+                            instance_name: fullname.clone(),
+                            address,
+                        });
+                    }
+                    self.resolved.insert(fullname, addresses);
+                }
+                mdns_sd::ServiceEvent::ServiceRemoved(_, fullname) => {
+                    if let Some(addresses) = self.resolved.remove(&fullname) {
+                        debug!("mDNS service removed: {} ({:?})", fullname, addresses);
+                        update.removed.extend(addresses);
+                    } else {
+                        debug!("mDNS service removed: {}", fullname);
+                    }
+                }
+                other => {
+                    debug!("mDNS browse event: {:?}", other);
+                }
+            }
+        }
+
+        update
+    }
+}
+
+impl Drop for NodeBrowser {
+    fn drop(&mut self) {
+        if let Err(e) = self._daemon.shutdown() {
+            warn!("Failed to shut down mDNS browser cleanly: {}", e);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::messages::NodeId;
+use log::{debug, info};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// What we know about a peer we've exchanged gossip with
+#[derive(Debug, Clone)]
+pub struct PeerInfo {
+    pub address: String,
+    /// Other addresses this peer has been reachable at (multi-homed nodes,
+    /// or a node that changed address without a new node ID). Learned from
+    /// gossip exchanges and consulted by `send_message_to_node_once` as a
+    /// fallback when `address` stops responding.
+    pub alt_addrs: HashSet<String>,
+    pub last_seen: Instant,
+}
+
+/// Dynamic membership table that starts from the CLI-provided bootstrap peers
+/// but is kept live via gossiped digest comparison, so nodes can learn about
+/// peers they weren't started with and drop ones that go away. Modeled on
+/// vpncloud's `PeerList`: every peer carries a last-seen timestamp and a set
+/// of alternate addresses alongside its primary one.
+pub struct MembershipTable {
+    peers: HashMap<NodeId, PeerInfo>,
+}
+
+impl MembershipTable {
+    /// Seed the table from the static addresses passed on the command line
+    pub fn bootstrap(initial: &HashMap<NodeId, String>) -> Self {
+        let now = Instant::now();
+        let peers = initial
+            .iter()
+            .map(|(&id, addr)| {
+                (
+                    id,
+                    PeerInfo {
+                        address: addr.clone(),
+                        alt_addrs: HashSet::new(),
+                        last_seen: now,
+                    },
+                )
+            })
+            .collect();
+
+        Self { peers }
+    }
+
+    /// Digest over only the set of node IDs currently considered up (sorted,
+    /// hashed). Addresses are deliberately excluded so a node reachable at two
+    /// addresses doesn't cause the digest to churn. The empty-set digest is
+    /// well-defined (hash of an empty, sorted vector) so two freshly started
+    /// nodes with no known peers already agree.
+    pub fn digest(&self) -> u64 {
+        let mut ids: Vec<NodeId> = self.peers.keys().copied().collect();
+        ids.sort_unstable();
+
+        let mut hasher = DefaultHasher::new();
+        ids.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Snapshot of the active-connection list, for sending to a peer whose
+    /// digest disagrees with ours. Includes each peer's known alt addresses
+    /// so a full exchange also spreads alternate-reachability info, not just
+    /// the primary address.
+    pub fn active_list(&self) -> Vec<(NodeId, String, Vec<String>)> {
+        self.peers
+            .iter()
+            .map(|(&id, info)| (id, info.address.clone(), info.alt_addrs.iter().cloned().collect()))
+            .collect()
+    }
+
+    /// Merge an active-connection list received from a peer whose digest
+    /// didn't match ours. Returns true if membership actually changed.
+    pub fn merge(&mut self, from: &[(NodeId, String, Vec<String>)]) -> bool {
+        let mut changed = false;
+        let now = Instant::now();
+
+        for (id, addr, alts) in from {
+            match self.peers.get_mut(id) {
+                Some(existing) => {
+                    existing.last_seen = now;
+                    if existing.address != *addr {
+                        // The old primary is still worth trying, so keep it
+                        // around as an alternate instead of dropping it.
+                        existing.alt_addrs.insert(existing.address.clone());
+                        existing.address = addr.clone();
+                        changed = true;
+                    }
+                    for alt in alts {
+                        if alt != &existing.address && existing.alt_addrs.insert(alt.clone()) {
+                            changed = true;
+                        }
+                    }
+                }
+                None => {
+                    debug!("Learned new peer {} at {} via gossip merge", id, addr);
+                    self.peers.insert(
+                        *id,
+                        PeerInfo {
+                            address: addr.clone(),
+                            alt_addrs: alts.iter().cloned().collect(),
+                            last_seen: now,
+                        },
+                    );
+                    changed = true;
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// A freshly started (or rejoining) node announcing itself via
+    /// `Message::Join`. `addrs` is the node's primary address followed by any
+    /// alternates it wants advertised; unlike `merge`, this always refreshes
+    /// `last_seen` since the node is, by definition, live right now.
+    pub fn join(&mut self, id: NodeId, addrs: &[String]) -> bool {
+        let Some((primary, alts)) = addrs.split_first() else {
+            return false;
+        };
+        let now = Instant::now();
+
+        match self.peers.get_mut(&id) {
+            Some(existing) => {
+                let changed = existing.address != *primary;
+                if changed {
+                    existing.alt_addrs.insert(existing.address.clone());
+                    existing.address = primary.clone();
+                }
+                for alt in alts {
+                    existing.alt_addrs.insert(alt.clone());
+                }
+                existing.last_seen = now;
+                changed
+            }
+            None => {
+                info!("Node {} joined at {}", id, primary);
+                self.peers.insert(
+                    id,
+                    PeerInfo {
+                        address: primary.clone(),
+                        alt_addrs: alts.iter().cloned().collect(),
+                        last_seen: now,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Refresh a peer's `last_seen` without changing its address, e.g. after
+    /// any successful request/response round trip with it - a finer-grained
+    /// liveness signal than the periodic gossip rounds alone.
+    pub fn touch(&mut self, id: NodeId) {
+        if let Some(peer) = self.peers.get_mut(&id) {
+            peer.last_seen = Instant::now();
+        }
+    }
+
+    /// Evict every peer not heard from within `ttl`. Distinct from the
+    /// failure detector (which reacts to gossiped load staleness): this is a
+    /// pure membership-table timeout, the same sweep vpncloud's `PeerList`
+    /// runs to forget addresses nobody has confirmed in a while. Returns the
+    /// evicted node IDs so the caller can fold them into `failed_nodes` too.
+    pub fn timeout(&mut self, ttl: Duration) -> Vec<NodeId> {
+        let now = Instant::now();
+        let stale: Vec<NodeId> = self
+            .peers
+            .iter()
+            .filter(|(_, info)| now.duration_since(info.last_seen) > ttl)
+            .map(|(&id, _)| id)
+            .collect();
+
+        for &id in &stale {
+            self.peers.remove(&id);
+            info!("Evicted peer {} from membership table (unseen for over {:?})", id, ttl);
+        }
+
+        stale
+    }
+
+    /// Drop a peer that's been confirmed dead.
+    pub fn remove(&mut self, id: NodeId) {
+        if self.peers.remove(&id).is_some() {
+            info!("Dropped peer {} from membership table", id);
+        }
+    }
+
+    pub fn addresses(&self) -> HashMap<NodeId, String> {
+        self.peers
+            .iter()
+            .map(|(&id, info)| (id, info.address.clone()))
+            .collect()
+    }
+
+    /// Look up a single peer's current address, for callers that just need
+    /// to route a message rather than enumerate the whole table.
+    pub fn address(&self, id: NodeId) -> Option<String> {
+        self.peers.get(&id).map(|info| info.address.clone())
+    }
+
+    /// Known alternate addresses for a peer, for `send_message_to_node_once`
+    /// to fall back to once the primary address stops responding.
+    pub fn alt_addresses(&self, id: NodeId) -> Vec<String> {
+        self.peers
+            .get(&id)
+            .map(|info| info.alt_addrs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.peers.contains_key(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_digest_is_well_defined_and_stable() {
+        let empty: HashMap<NodeId, String> = HashMap::new();
+        let a = MembershipTable::bootstrap(&empty);
+        let b = MembershipTable::bootstrap(&empty);
+        assert_eq!(a.digest(), b.digest());
+    }
+
+    #[test]
+    fn digest_ignores_address_only_changes() {
+        let mut initial = HashMap::new();
+        initial.insert(1, "127.0.0.1:8001".to_string());
+
+        let a = MembershipTable::bootstrap(&initial);
+
+        let mut other = HashMap::new();
+        other.insert(1, "127.0.0.1:9999".to_string());
+        let b = MembershipTable::bootstrap(&other);
+
+        assert_eq!(a.digest(), b.digest());
+    }
+
+    #[test]
+    fn merge_learns_new_peers_and_changes_digest() {
+        let initial = HashMap::new();
+        let mut table = MembershipTable::bootstrap(&initial);
+        let before = table.digest();
+
+        let changed = table.merge(&[(2, "127.0.0.1:8002".to_string(), vec![])]);
+
+        assert!(changed);
+        assert_ne!(table.digest(), before);
+        assert!(table.contains(2));
+    }
+
+    #[test]
+    fn address_change_keeps_old_address_as_alternate() {
+        let mut initial = HashMap::new();
+        initial.insert(1, "127.0.0.1:8001".to_string());
+        let mut table = MembershipTable::bootstrap(&initial);
+
+        table.merge(&[(1, "127.0.0.1:9001".to_string(), vec![])]);
+
+        assert_eq!(table.address(1), Some("127.0.0.1:9001".to_string()));
+        assert_eq!(table.alt_addresses(1), vec!["127.0.0.1:8001".to_string()]);
+    }
+
+    #[test]
+    fn join_adds_a_brand_new_node_with_alternates() {
+        let initial = HashMap::new();
+        let mut table = MembershipTable::bootstrap(&initial);
+
+        let changed = table.join(3, &["127.0.0.1:8003".to_string(), "10.0.0.3:8003".to_string()]);
+
+        assert!(changed);
+        assert_eq!(table.address(3), Some("127.0.0.1:8003".to_string()));
+        assert_eq!(table.alt_addresses(3), vec!["10.0.0.3:8003".to_string()]);
+    }
+
+    #[test]
+    fn timeout_evicts_only_stale_peers() {
+        let initial = HashMap::new();
+        let mut table = MembershipTable::bootstrap(&initial);
+
+        table.join(1, &["127.0.0.1:8001".to_string()]);
+        std::thread::sleep(Duration::from_millis(20));
+        table.join(2, &["127.0.0.1:8002".to_string()]);
+        std::thread::sleep(Duration::from_millis(20));
+        // Refresh node 1 right before the sweep; node 2 has been idle since
+        // it joined, so only it should be past the TTL below.
+        table.touch(1);
+
+        let evicted = table.timeout(Duration::from_millis(15));
+
+        assert_eq!(evicted, vec![2]);
+        assert!(table.contains(1));
+        assert!(!table.contains(2));
+    }
+}
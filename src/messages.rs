@@ -1,9 +1,46 @@
+use crate::compression::CompressionCodec;
+use crate::identity::PairingProof;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt;
 
 /// Node ID for cloud nodes
 pub type NodeId = u32;
 
+/// SHA-256 digest of an image's decrypted-on-the-wire bytes (the plaintext
+/// `SendImage.encrypted_image`/`StoredImage.encrypted_data`/
+/// `ReplicatedImage.encrypted_data`, after any `CompressionCodec` has
+/// already been undone), so it's stable regardless of which codec carried
+/// it over the wire. Computed once at ingest (`SendImage`) and recomputed
+/// on every later read or replica transfer to catch corruption - see
+/// `node::StoredImage`.
+pub fn checksum(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Why a checksum recheck (see `checksum`) rejected a payload before it
+/// reached `decrypt_image` or `stored_images`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// The recomputed digest didn't match the one the sender claimed.
+    ChecksumMismatch,
+    /// `image_id` isn't safe to use as a path segment (see
+    /// `upload_session::is_valid_path_segment`) - rejected before it ever
+    /// reaches `ImageStore::put`/`PathBuf::join`.
+    InvalidImageId,
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            IntegrityError::InvalidImageId => write!(f, "invalid image_id"),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
 /// Information about a received image
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReceivedImageInfo {
@@ -13,13 +50,48 @@ pub struct ReceivedImageInfo {
     pub timestamp: i64,
 }
 
+/// Outcome of sharing an image with a single recipient.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DeliveryState {
+    /// Recipient was online and got a push notification immediately.
+    Delivered,
+    /// Recipient was offline; the image is stored and will be picked up
+    /// the next time they register a session.
+    Pending,
+    /// Recipient isn't a known registered user, so the share can never
+    /// be collected.
+    Failed(String),
+}
+
 /// Message types exchanged between nodes and clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Message {
     // Election messages (Bully Algorithm)
     Election { from_node: NodeId },
     Ok { from_node: NodeId },
-    Coordinator { node_id: NodeId, load: f64 },
+    Coordinator {
+        node_id: NodeId,
+        load: f64,
+        term: u64,
+        /// The sender's `ClusterLayout::version()` at the time of this
+        /// announcement, so a recipient whose own layout is older knows to
+        /// recompute it (see `cluster_layout.rs`) instead of serving
+        /// placement decisions from a stale partition table.
+        layout_version: u64,
+    },
+
+    // Sent once, right before a node shuts down cleanly (see
+    // `CloudNode::graceful_leave_task`), so peers can drop it from
+    // `failed_nodes`/membership immediately instead of waiting out the
+    // gossip staleness timeout and logging a false "FAILURE DETECTED".
+    Leave {
+        from_node: NodeId,
+        /// Best-effort suggestion for who should pick up coordination next,
+        /// if the leaving node was the coordinator - purely informational,
+        /// peers still run the normal `trigger_election` to actually decide.
+        successor_hint: Option<NodeId>,
+    },
+    LeaveAck { from_node: NodeId },
 
     // Session management messages
     SessionRegister {
@@ -29,12 +101,88 @@ pub enum Message {
     SessionRegisterResponse {
         success: bool,
         error: Option<String>,
+        /// Issued on a successful registration, keyed server-side to
+        /// `username`; present to `Handshake` on later requests to prove
+        /// the client actually owns this username.
+        session_token: Option<String>,
+        /// Store-and-forward flush: every image that arrived for this
+        /// username while it had no active session (`DeliveryState::Pending`
+        /// at `SendImage` time), not yet delivered to it. Empty on a failed
+        /// registration. The client should treat every entry here as
+        /// freshly delivered and unseen, the same as a live
+        /// `ImageNotification` - see `Client::register_session`.
+        pending_images: Vec<ReceivedImageInfo>,
     },
     SessionUnregister {
         client_id: String,
         username: String,
     },
 
+    // Optional password protection for a username (see
+    // `user_directory::UserDirectory`). The password itself is never sent
+    // over the wire, not even once: the client derives a scrypt verifier
+    // locally, and proves knowledge of it against a fresh server nonce
+    // (`AuthChallenge`/`AuthProve`), the same nonce-bound proof shape used
+    // to change it (`ChangePassword`). Deliberately independent of
+    // `SessionRegister` rather than folded into it, so an account that
+    // enables protection after a client already registered doesn't
+    // retroactively invalidate that session - protection is enforced at the
+    // point of proof (`node::authenticated_sessions`, checked by
+    // `QueryReceivedImages`/`ViewImage`), not at registration time.
+    AuthChallenge {
+        username: String,
+    },
+    AuthChallengeResponse {
+        /// `None` if `username` isn't password-protected - nothing to prove.
+        nonce: Option<[u8; 16]>,
+        /// The salt `username`'s verifier was derived with, so the client
+        /// can recompute `user_directory::derive_verifier` locally. Always
+        /// `Some` exactly when `nonce` is.
+        salt: Option<[u8; 16]>,
+    },
+    AuthProve {
+        username: String,
+        nonce: [u8; 16],
+        /// Proof of knowledge of the account's scrypt verifier for `nonce`,
+        /// without ever transmitting the verifier itself (see
+        /// `user_directory::derive_proof`).
+        proof: [u8; 32],
+    },
+    AuthProveResponse {
+        verified: bool,
+    },
+    /// Change (or, for a not-yet-protected account, set for the first time)
+    /// `username`'s password verifier. `old_nonce`/`old_proof` must prove
+    /// the *current* password when the account is already protected; an
+    /// all-zero proof is only accepted while the account is unprotected, so
+    /// an unauthenticated peer can't strip or replace existing protection.
+    ChangePassword {
+        username: String,
+        old_nonce: [u8; 16],
+        old_proof: [u8; 32],
+        new_salt: [u8; 16],
+        new_verifier: [u8; 32],
+    },
+    ChangePasswordResponse {
+        success: bool,
+        error: Option<String>,
+    },
+
+    // Authentication and compression negotiation, performed once per node
+    // a client talks to (the result is cached client-side - see
+    // `Client::negotiate_codec`) before `EncryptionRequest`/`SendImage`
+    // payloads are compressed and sent.
+    Handshake {
+        client_username: String,
+        session_token: Option<String>,
+        supported_codecs: Vec<CompressionCodec>,
+    },
+    HandshakeResponse {
+        accepted: bool,
+        codec: CompressionCodec,
+        error: Option<String>,
+    },
+
     // Client request messages
     EncryptionRequest {
         request_id: String,
@@ -43,6 +191,8 @@ pub enum Message {
         usernames: Vec<String>,
         quota: u32,
         forwarded: bool, // Prevent infinite forwarding loops
+        client_address: Option<String>, // Set by the first node that sees this request, so the node that ends up processing it can reply directly
+        codec: CompressionCodec, // Codec `image_data` was compressed with, negotiated via `Handshake`
     },
 
     // Response messages
@@ -55,7 +205,36 @@ pub enum Message {
 
     // Load query for election
     LoadQuery { from_node: NodeId },
-    LoadResponse { node_id: NodeId, load: f64, queue_length: usize },
+    LoadResponse {
+        node_id: NodeId,
+        load: f64,
+        queue_length: usize,
+        processed_count: usize,
+        /// This node's advertised placement capability (see
+        /// `cluster_layout::NodeCapability`), piggybacked so a peer doing an
+        /// election or recomputing its `ClusterLayout` doesn't need a
+        /// separate round trip to learn it.
+        capacity: f64,
+        zone: String,
+        /// This node's own self-assessed connectivity health: the highest
+        /// phi-accrual suspicion level (see `phi_detector`) it currently
+        /// observes across its own gossip peers. A node whose own gossip
+        /// view looks unreliable would make a poor coordinator even if its
+        /// reported `load` is low, so election excludes candidates above a
+        /// suspicion cutoff (see `election::start_election`).
+        phi: f64,
+    },
+
+    /// Announces this node's `cluster_layout::ShardConfig` - which slice of
+    /// the modulo-sharded keyspace it's responsible for - so peers can tell
+    /// whether it's worth asking for a given image without guessing. Sent
+    /// whenever a node's shard assignment changes and cached in
+    /// `peer_shard_configs` alongside `peer_load_cache`.
+    AnnounceShardConfig {
+        node_id: NodeId,
+        num_shards: usize,
+        shard_id: usize,
+    },
 
     // State synchronization
     StateSync { from_node: NodeId },
@@ -71,9 +250,200 @@ pub enum Message {
         coordinator_address: String,
     },
 
-    // Heartbeat
-    Heartbeat { from_node: NodeId },
-    HeartbeatAck { from_node: NodeId },
+    // Gossip-based peer discovery (replaces the static node_addresses map)
+    MembershipDigest {
+        from_node: NodeId,
+        digest: u64,
+    },
+    // `peers` is `(node_id, primary_addr, alt_addrs)` - alt_addrs rides
+    // along on every full exchange so multi-homed/address-changed peers get
+    // their alternates spread through the cluster, not just their primary.
+    MembershipExchange {
+        from_node: NodeId,
+        peers: Vec<(NodeId, String, Vec<String>)>,
+    },
+    // A freshly started (or rejoined) node announcing itself, so it's
+    // reachable before the next digest-mismatch exchange would otherwise
+    // have picked it up. `addrs` is the node's primary address followed by
+    // any alternates it wants advertised.
+    Join {
+        node_id: NodeId,
+        addrs: Vec<String>,
+    },
+
+    // Rapid-style consistent membership (see `rapid_membership.rs`),
+    // agreed before any coordinator election runs so two nodes can't
+    // diverge on `get_coordinator()`. `RapidAlert` is broadcast by an
+    // observer the moment it sees one of its monitored subjects change
+    // reachability; once enough distinct observers agree (the `H`
+    // threshold), the proposing node broadcasts the resulting
+    // `MultiNodeCut` as a `RapidCutProposal` and collects `Ok` acks
+    // (fast-path 3/4 quorum, falling back to a classic majority).
+    RapidAlert {
+        observer: NodeId,
+        subject: NodeId,
+        up: bool,
+    },
+    RapidCutProposal {
+        proposer: NodeId,
+        to_add: Vec<NodeId>,
+        to_remove: Vec<NodeId>,
+    },
+
+    // Anti-entropy gossip for peer load/liveness (replaces all-to-all
+    // heartbeats - see `gossip::GossipTable`). A node periodically picks
+    // one random live peer and pulls from it; the peer replies with only
+    // the records it holds that are newer than what the puller already
+    // knows.
+    GossipPull {
+        from_node: NodeId,
+        known: std::collections::HashMap<NodeId, u64>,
+    },
+    GossipPush {
+        from_node: NodeId,
+        records: std::collections::HashMap<NodeId, GossipRecord>,
+    },
+
+    // Replication of stored images across replica nodes (see
+    // `replication.rs`). `ReplicationPush` is a fire-and-forget proactive
+    // copy sent right after an image is first stored; `ReplicationSync`/
+    // `ReplicationSyncResponse` are the periodic Merkle-bucket anti-entropy
+    // exchange that catches anything a push missed.
+    ReplicationPush {
+        image: ReplicatedImage,
+    },
+    // `accepted` is false when the receiving replica's own checksum recheck
+    // (see `messages::checksum`/`IntegrityError`) rejected `image` - without
+    // this, `SendImage`'s write-quorum count would believe a silently
+    // dropped, corrupted push durably landed.
+    ReplicationPushResponse {
+        from_node: NodeId,
+        accepted: bool,
+        error: Option<String>,
+    },
+    ReplicationSync {
+        from_node: NodeId,
+        bucket_hashes: std::collections::HashMap<u32, u64>,
+    },
+    ReplicationSyncResponse {
+        from_node: NodeId,
+        records: Vec<ReplicatedImage>,
+    },
+
+    // Reference-counted GC (see `node::image_gc_task`): before a replica
+    // physically deletes an exhausted or TTL-expired image it asks every
+    // other replica in the set whether they still consider it live, so a
+    // view that hasn't synced over yet can't have its record pulled out
+    // from under it mid-flight.
+    NeedImageQuery {
+        username: String,
+        image_id: String,
+    },
+    NeedImageQueryResponse {
+        still_needed: bool,
+    },
+
+    // Multipart upload protocol for `EncryptionRequest`, so a large image
+    // isn't fully materialized in memory at once (see `upload_session.rs`).
+    // `BeginUpload` pins the node every later part/CompleteUpload for this
+    // `request_id` must land on, the same load-balanced dispatch
+    // `EncryptionRequest` already does, just decided up front since no
+    // image bytes have arrived yet to justify per-hop forwarding. The node
+    // that accepts it stages `UploadPart`s into a temp file and, once
+    // `CompleteUpload` arrives, replays the reassembled bytes through the
+    // same path a normal `EncryptionRequest` takes - its reply is an
+    // ordinary `EncryptionResponse`, not a new message type.
+    BeginUpload {
+        request_id: String,
+        client_username: String,
+        usernames: Vec<String>,
+        quota: u32,
+        codec: CompressionCodec,
+        client_address: Option<String>,
+        forwarded: bool, // Prevent infinite forwarding loops, same as `EncryptionRequest::forwarded`
+    },
+    BeginUploadResponse {
+        request_id: String,
+        accepted: bool,
+        error: Option<String>,
+    },
+    UploadPart {
+        request_id: String,
+        part_number: u32,
+        data: Vec<u8>,
+    },
+    UploadPartResponse {
+        request_id: String,
+        part_number: u32,
+        success: bool,
+        error: Option<String>,
+    },
+    CompleteUpload {
+        request_id: String,
+    },
+
+    // Authenticated pairing handshake (cryptographic node identity)
+    PairingRequest {
+        nonce: [u8; 16],
+    },
+    PairingResponse {
+        proof: PairingProof,
+    },
+
+    // Per-pair secure channel (see `secure_session.rs`): a static-key
+    // handshake run once two nodes have paired (so each side already holds
+    // the other's long-term public key from `PairingResponse`), after which
+    // `SecureEnvelope` carries an authenticated-encrypted copy of another
+    // `Message` between that pair. Closes the spoofing gap a forged
+    // plaintext `Coordinator`/`GossipPush`/`ReplicationSync` would otherwise
+    // open for election, gossip, and replication traffic.
+    SecureHandshakeInit {
+        from_node: NodeId,
+        ephemeral_public: [u8; 32],
+        signature: [u8; 64],
+    },
+    SecureHandshakeAck {
+        from_node: NodeId,
+        ephemeral_public: [u8; 32],
+        signature: [u8; 64],
+    },
+    SecureEnvelope {
+        from_node: NodeId,
+        sealed: Vec<u8>,
+    },
+
+    // Client-to-node counterpart of `SecureHandshakeInit`/`Ack`/
+    // `SecureEnvelope` above, for traffic that carries client credentials
+    // (currently `SessionRegister` - see `node::claimed_client_sender_matches`).
+    // Keyed by the client's claimed username rather than a `NodeId`, since a
+    // client has no out-of-band pairing step to learn a peer's static key
+    // from first: `static_public` is presented directly in the handshake and
+    // pinned to that username on first use (see `node::client_trusted_keys`),
+    // the same spoofing protection `known_static_keys` gives paired nodes,
+    // just trust-on-first-use instead of requiring prior pairing.
+    ClientSecureHandshakeInit {
+        client_username: String,
+        static_public: [u8; 32],
+        ephemeral_public: [u8; 32],
+        signature: [u8; 64],
+    },
+    ClientSecureHandshakeAck {
+        ephemeral_public: [u8; 32],
+        signature: [u8; 64],
+    },
+    ClientSecureEnvelope {
+        client_username: String,
+        sealed: Vec<u8>,
+    },
+
+    // Sent sealed inside a `SecureEnvelope` to ratchet a secure channel's
+    // key forward (see `secure_session::SessionWriter::rotate` /
+    // `node::key_rotation_task`). A fire-and-forget notification, same as
+    // `ReplicationPush` - there's nothing to ack, since each side just
+    // rotates its own matching key once the marker is sent/opened.
+    KeyRotation {
+        from_node: NodeId,
+    },
 
     // Image sending/receiving messages
     SendImage {
@@ -82,17 +452,29 @@ pub enum Message {
         encrypted_image: Vec<u8>,
         max_views: u32,
         image_id: String,
+        codec: CompressionCodec, // Codec `encrypted_image` was compressed with, negotiated via `Handshake`
+        // Client-computed `checksum` of `encrypted_image` before compression, so the
+        // receiving node can reject a corrupted upload at ingest instead of storing
+        // and later serving bad bytes. Optional so older clients can still send images.
+        checksum: Option<[u8; 32]>,
     },
     SendImageResponse {
         success: bool,
         image_id: String,
         error: Option<String>,
+        delivery: Vec<(String, DeliveryState)>, // per-recipient outcome
     },
     QueryReceivedImages {
         username: String,
+        offset: usize,
+        limit: usize,
     },
     QueryReceivedImagesResponse {
         images: Vec<ReceivedImageInfo>,
+        has_more: bool,
+        /// Set (with `images` empty) when `username` is password-protected
+        /// and the requester hasn't completed `AuthChallenge`/`AuthProve`.
+        error: Option<String>,
     },
     ViewImage {
         username: String,
@@ -111,6 +493,28 @@ pub enum Message {
         username: String,
         is_available: bool,
     },
+
+    // Contacts directory (every username ever registered, with live online status)
+    QueryDirectory,
+    QueryDirectoryResponse {
+        entries: Vec<(String, bool)>, // (username, online)
+    },
+
+    // Real-time push notifications: a client opens a long-lived UDP socket
+    // and subscribes so the node can proactively notify it of new shares
+    // instead of making it poll QueryReceivedImages.
+    SubscribeNotifications {
+        username: String,
+    },
+    SubscribeNotificationsResponse {
+        success: bool,
+    },
+    ImageNotification {
+        to_username: String,
+        from_username: String,
+        image_id: String,
+        remaining_views: u32,
+    },
 }
 
 impl fmt::Display for Message {
@@ -118,9 +522,17 @@ impl fmt::Display for Message {
         match self {
             Message::Election { from_node } => write!(f, "ELECTION from Node {}", from_node),
             Message::Ok { from_node } => write!(f, "OK from Node {}", from_node),
-            Message::Coordinator { node_id, load } => {
-                write!(f, "COORDINATOR Node {} (load: {:.2})", node_id, load)
+            Message::Coordinator { node_id, load, term, layout_version } => {
+                write!(
+                    f,
+                    "COORDINATOR Node {} (load: {:.2}, term {}, layout v{})",
+                    node_id, load, term, layout_version
+                )
+            }
+            Message::Leave { from_node, successor_hint } => {
+                write!(f, "LEAVE from Node {} (successor hint: {:?})", from_node, successor_hint)
             }
+            Message::LeaveAck { from_node } => write!(f, "LEAVE_ACK from Node {}", from_node),
             Message::SessionRegister { username, .. } => {
                 write!(f, "SESSION_REGISTER username: {}", username)
             }
@@ -130,14 +542,35 @@ impl fmt::Display for Message {
             Message::SessionUnregister { username, .. } => {
                 write!(f, "SESSION_UNREGISTER username: {}", username)
             }
+            Message::AuthChallenge { username } => write!(f, "AUTH_CHALLENGE for '{}'", username),
+            Message::AuthChallengeResponse { nonce, .. } => {
+                write!(f, "AUTH_CHALLENGE_RESPONSE (protected: {})", nonce.is_some())
+            }
+            Message::AuthProve { username, .. } => write!(f, "AUTH_PROVE for '{}'", username),
+            Message::AuthProveResponse { verified } => {
+                write!(f, "AUTH_PROVE_RESPONSE (verified: {})", verified)
+            }
+            Message::ChangePassword { username, .. } => write!(f, "CHANGE_PASSWORD for '{}'", username),
+            Message::ChangePasswordResponse { success, .. } => {
+                write!(f, "CHANGE_PASSWORD_RESPONSE (success: {})", success)
+            }
+            Message::Handshake { client_username, .. } => {
+                write!(f, "HANDSHAKE from {}", client_username)
+            }
+            Message::HandshakeResponse { accepted, codec, .. } => {
+                write!(f, "HANDSHAKE_RESPONSE (accepted: {}, codec: {:?})", accepted, codec)
+            }
             Message::EncryptionRequest { request_id, .. } => {
                 write!(f, "ENCRYPTION_REQUEST {}", request_id)
             }
             Message::EncryptionResponse { request_id, success, .. } => {
                 write!(f, "ENCRYPTION_RESPONSE {} (success: {})", request_id, success)
             }
+            Message::AnnounceShardConfig { node_id, num_shards, shard_id } => {
+                write!(f, "ANNOUNCE_SHARD_CONFIG from Node {} (shard {}/{})", node_id, shard_id, num_shards)
+            }
             Message::LoadQuery { from_node } => write!(f, "LOAD_QUERY from Node {}", from_node),
-            Message::LoadResponse { node_id, load, queue_length } => {
+            Message::LoadResponse { node_id, load, queue_length, .. } => {
                 write!(f, "LOAD_RESPONSE Node {} (load: {:.2}, queue: {})", node_id, load, queue_length)
             }
             Message::StateSync { from_node } => write!(f, "STATE_SYNC from Node {}", from_node),
@@ -148,19 +581,90 @@ impl fmt::Display for Message {
             Message::CoordinatorQueryResponse { coordinator_address } => {
                 write!(f, "COORDINATOR_QUERY_RESPONSE (address: {})", coordinator_address)
             }
-            Message::Heartbeat { from_node } => write!(f, "HEARTBEAT from Node {}", from_node),
-            Message::HeartbeatAck { from_node } => write!(f, "HEARTBEAT_ACK from Node {}", from_node),
+            Message::MembershipDigest { from_node, digest } => {
+                write!(f, "MEMBERSHIP_DIGEST from Node {} ({:x})", from_node, digest)
+            }
+            Message::MembershipExchange { from_node, peers } => {
+                write!(f, "MEMBERSHIP_EXCHANGE from Node {} ({} peers)", from_node, peers.len())
+            }
+            Message::Join { node_id, addrs } => {
+                write!(f, "JOIN from Node {} ({} addr(s))", node_id, addrs.len())
+            }
+            Message::RapidAlert { observer, subject, up } => {
+                write!(f, "RAPID_ALERT Node {} reports Node {} as {}", observer, subject, if *up { "UP" } else { "DOWN" })
+            }
+            Message::RapidCutProposal { proposer, to_add, to_remove } => {
+                write!(
+                    f,
+                    "RAPID_CUT_PROPOSAL from Node {} (+{} -{})",
+                    proposer,
+                    to_add.len(),
+                    to_remove.len()
+                )
+            }
+            Message::GossipPull { from_node, known } => {
+                write!(f, "GOSSIP_PULL from Node {} (knows {} records)", from_node, known.len())
+            }
+            Message::GossipPush { from_node, records } => {
+                write!(f, "GOSSIP_PUSH from Node {} ({} records)", from_node, records.len())
+            }
+            Message::ReplicationPush { image } => {
+                write!(f, "REPLICATION_PUSH {} for {}", image.image_id, image.username)
+            }
+            Message::ReplicationPushResponse { from_node, accepted, .. } => {
+                write!(f, "REPLICATION_PUSH_RESPONSE from Node {} (accepted={})", from_node, accepted)
+            }
+            Message::ReplicationSync { from_node, bucket_hashes } => {
+                write!(f, "REPLICATION_SYNC from Node {} ({} buckets)", from_node, bucket_hashes.len())
+            }
+            Message::ReplicationSyncResponse { from_node, records } => {
+                write!(f, "REPLICATION_SYNC_RESPONSE from Node {} ({} records)", from_node, records.len())
+            }
+            Message::NeedImageQuery { username, image_id } => {
+                write!(f, "NEED_IMAGE_QUERY {} for {}", image_id, username)
+            }
+            Message::NeedImageQueryResponse { still_needed } => {
+                write!(f, "NEED_IMAGE_QUERY_RESPONSE (still_needed: {})", still_needed)
+            }
+            Message::BeginUpload { request_id, .. } => write!(f, "BEGIN_UPLOAD {}", request_id),
+            Message::BeginUploadResponse { request_id, accepted, .. } => {
+                write!(f, "BEGIN_UPLOAD_RESPONSE {} (accepted: {})", request_id, accepted)
+            }
+            Message::UploadPart { request_id, part_number, .. } => {
+                write!(f, "UPLOAD_PART {} part {}", request_id, part_number)
+            }
+            Message::UploadPartResponse { request_id, part_number, success, .. } => {
+                write!(f, "UPLOAD_PART_RESPONSE {} part {} (success: {})", request_id, part_number, success)
+            }
+            Message::CompleteUpload { request_id } => write!(f, "COMPLETE_UPLOAD {}", request_id),
+            Message::PairingRequest { .. } => write!(f, "PAIRING_REQUEST"),
+            Message::PairingResponse { proof } => {
+                write!(f, "PAIRING_RESPONSE (pubkey: {})", hex_prefix(&proof.node_information.public_key))
+            }
+            Message::SecureHandshakeInit { from_node, .. } => write!(f, "SECURE_HANDSHAKE_INIT from Node {}", from_node),
+            Message::SecureHandshakeAck { from_node, .. } => write!(f, "SECURE_HANDSHAKE_ACK from Node {}", from_node),
+            Message::SecureEnvelope { from_node, sealed } => {
+                write!(f, "SECURE_ENVELOPE from Node {} ({} bytes sealed)", from_node, sealed.len())
+            }
+            Message::ClientSecureHandshakeInit { client_username, .. } => {
+                write!(f, "CLIENT_SECURE_HANDSHAKE_INIT from '{}'", client_username)
+            }
+            Message::ClientSecureHandshakeAck { .. } => write!(f, "CLIENT_SECURE_HANDSHAKE_ACK"),
+            Message::ClientSecureEnvelope { client_username, sealed } => {
+                write!(f, "CLIENT_SECURE_ENVELOPE from '{}' ({} bytes sealed)", client_username, sealed.len())
+            }
+            Message::KeyRotation { from_node } => write!(f, "KEY_ROTATION from Node {}", from_node),
             Message::SendImage { from_username, to_usernames, image_id, .. } => {
                 write!(f, "SEND_IMAGE {} from {} to {:?}", image_id, from_username, to_usernames)
             }
             Message::SendImageResponse { success, image_id, .. } => {
                 write!(f, "SEND_IMAGE_RESPONSE {} (success: {})", image_id, success)
             }
-            Message::QueryReceivedImages { username } => {
-                write!(f, "QUERY_RECEIVED_IMAGES for {}", username)
+            Message::QueryReceivedImages { username, offset, limit } => {
+                write!(f, "QUERY_RECEIVED_IMAGES for {} (offset: {}, limit: {})", username, offset, limit)
             }
-            Message::QueryReceivedImagesResponse { images } => {
-                write!(f, "QUERY_RECEIVED_IMAGES_RESPONSE ({} images)", images.len())
+            Message::QueryReceivedImagesResponse { images, has_more, .. } => {
+                write!(f, "QUERY_RECEIVED_IMAGES_RESPONSE ({} images, has_more: {})", images.len(), has_more)
             }
             Message::ViewImage { username, image_id } => {
                 write!(f, "VIEW_IMAGE {} by {}", image_id, username)
@@ -174,16 +678,71 @@ impl fmt::Display for Message {
             Message::CheckUsernameAvailableResponse { username, is_available } => {
                 write!(f, "CHECK_USERNAME_AVAILABLE_RESPONSE {} (available: {})", username, is_available)
             }
+            Message::QueryDirectory => write!(f, "QUERY_DIRECTORY"),
+            Message::QueryDirectoryResponse { entries } => {
+                write!(f, "QUERY_DIRECTORY_RESPONSE ({} entries)", entries.len())
+            }
+            Message::SubscribeNotifications { username } => {
+                write!(f, "SUBSCRIBE_NOTIFICATIONS {}", username)
+            }
+            Message::SubscribeNotificationsResponse { success } => {
+                write!(f, "SUBSCRIBE_NOTIFICATIONS_RESPONSE (success: {})", success)
+            }
+            Message::ImageNotification { to_username, image_id, .. } => {
+                write!(f, "IMAGE_NOTIFICATION {} for {}", image_id, to_username)
+            }
         }
     }
 }
 
+/// Short hex preview of a public key, for log lines
+fn hex_prefix(bytes: &[u8]) -> String {
+    bytes.iter().take(4).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A `node::StoredImage` plus the username it's stored under, for carrying
+/// replica copies over the wire (`node::StoredImage` itself isn't
+/// (de)serialized - it never otherwise leaves the node that holds it).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicatedImage {
+    pub username: String,
+    pub image_id: String,
+    pub from_username: String,
+    pub encrypted_data: Vec<u8>,
+    pub remaining_views: u32,
+    pub max_views: u32,
+    pub timestamp: i64,
+    pub checksum: [u8; 32],
+    /// Mirrors `node::StoredImage::notified` so a replica that gets elected
+    /// coordinator (or answers a `SessionRegister` directly) agrees on
+    /// whether the recipient has already been told about this image.
+    pub notified: bool,
+}
+
+/// The wire form of a `gossip::PeerRecord`: everything except `last_seen`,
+/// which is deliberately local-only (a remote monotonic clock reading
+/// doesn't mean anything here) - the receiver stamps its own `Instant::now()`
+/// when a record is merged in, same as `membership::MembershipTable` already
+/// does for the peers it learns about via `MembershipExchange`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GossipRecord {
+    pub update_index: u64,
+    pub state: NodeState,
+    pub load: f64,
+    pub processed_count: usize,
+}
+
 /// Node state enum
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NodeState {
     Active,
     Failed,
     Recovering,
+    /// Shutting down deliberately via the admin API: still up and gossiping
+    /// (so peers learn about it quickly, unlike `Failed`), but ineligible
+    /// for coordinator election and refusing new sessions while it migrates
+    /// `stored_images` off to other replicas before finally leaving.
+    Draining,
 }
 
 impl fmt::Display for NodeState {
@@ -192,6 +751,7 @@ impl fmt::Display for NodeState {
             NodeState::Active => write!(f, "ACTIVE"),
             NodeState::Failed => write!(f, "FAILED"),
             NodeState::Recovering => write!(f, "RECOVERING"),
+            NodeState::Draining => write!(f, "DRAINING"),
         }
     }
 }
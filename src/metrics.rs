@@ -1,18 +1,85 @@
+use crate::metrics_sink::MetricsSink;
+use crate::quantile::P2Estimator;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 
-/// Metrics collected during stress testing
+/// Streaming p50/p90/p95/p99 latency estimates, kept in O(1) memory via
+/// `P2Estimator` rather than a `Vec<u64>` of every observed duration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyQuantiles {
+    p50: P2Estimator,
+    p90: P2Estimator,
+    p95: P2Estimator,
+    p99: P2Estimator,
+}
+
+impl LatencyQuantiles {
+    fn new() -> Self {
+        Self {
+            p50: P2Estimator::new(0.50),
+            p90: P2Estimator::new(0.90),
+            p95: P2Estimator::new(0.95),
+            p99: P2Estimator::new(0.99),
+        }
+    }
+
+    fn observe(&mut self, duration_ms: u64) {
+        let x = duration_ms as f64;
+        self.p50.observe(x);
+        self.p90.observe(x);
+        self.p95.observe(x);
+        self.p99.observe(x);
+    }
+
+    /// Fold another shard's quantile estimates into this one. See
+    /// `P2Estimator::merge` for how each marker is combined.
+    fn merge(&mut self, other: &LatencyQuantiles) {
+        self.p50.merge(&other.p50);
+        self.p90.merge(&other.p90);
+        self.p95.merge(&other.p95);
+        self.p99.merge(&other.p99);
+    }
+}
+
+/// Metrics collected during stress testing
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StressTestMetrics {
     pub total_requests: usize,
     pub successful_requests: usize,
     pub failed_requests: usize,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
-    pub request_durations_ms: Vec<u64>,
+    /// Running total of every `record_request` duration, so `avg_latency_ms`
+    /// stays exact without keeping each sample around.
+    latency_sum_ms: u64,
+    latency_count: u64,
+    latency_quantiles: LatencyQuantiles,
     pub load_balancing_decisions: Vec<LoadBalancingDecision>,
+    /// Optional live-metrics sink (e.g. `KafkaMetricsSink`) that every
+    /// `record_request`/`record_load_balancing` call also pushes into, so a
+    /// run can be watched off-box instead of only via `print_summary` at the
+    /// end. Not serialized - it's a live handle, not recorded state.
+    #[serde(skip)]
+    sink: Option<Arc<dyn MetricsSink>>,
+}
+
+impl std::fmt::Debug for StressTestMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StressTestMetrics")
+            .field("total_requests", &self.total_requests)
+            .field("successful_requests", &self.successful_requests)
+            .field("failed_requests", &self.failed_requests)
+            .field("start_time", &self.start_time)
+            .field("end_time", &self.end_time)
+            .field("latency_sum_ms", &self.latency_sum_ms)
+            .field("latency_count", &self.latency_count)
+            .field("latency_quantiles", &self.latency_quantiles)
+            .field("load_balancing_decisions", &self.load_balancing_decisions)
+            .field("sink_attached", &self.sink.is_some())
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,11 +97,27 @@ impl StressTestMetrics {
             failed_requests: 0,
             start_time: Utc::now(),
             end_time: None,
-            request_durations_ms: Vec::new(),
+            latency_sum_ms: 0,
+            latency_count: 0,
+            latency_quantiles: LatencyQuantiles::new(),
             load_balancing_decisions: Vec::new(),
+            sink: None,
         }
     }
 
+    /// Attach a live-metrics sink. Every `record_request`/
+    /// `record_load_balancing` call from this point on also pushes into it,
+    /// fire-and-forget, on top of the existing in-memory aggregates.
+    pub fn set_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.sink = Some(sink);
+    }
+
+    /// Clone out the attached sink, if any, so a per-task shard can stream
+    /// into the same destination as the aggregate it will later merge into.
+    pub fn sink(&self) -> Option<Arc<dyn MetricsSink>> {
+        self.sink.clone()
+    }
+
     pub fn record_request(&mut self, success: bool, duration_ms: u64) {
         self.total_requests += 1;
         if success {
@@ -42,21 +125,62 @@ impl StressTestMetrics {
         } else {
             self.failed_requests += 1;
         }
-        self.request_durations_ms.push(duration_ms);
+        self.latency_sum_ms += duration_ms;
+        self.latency_count += 1;
+        self.latency_quantiles.observe(duration_ms);
+
+        if let Some(sink) = self.sink.clone() {
+            tokio::spawn(async move {
+                sink.record_request(success, duration_ms).await;
+            });
+        }
     }
 
     pub fn record_load_balancing(&mut self, selected_node: u32, node_loads: Vec<(u32, f64)>) {
-        self.load_balancing_decisions.push(LoadBalancingDecision {
+        let decision = LoadBalancingDecision {
             timestamp: Utc::now(),
             selected_node,
             node_loads,
-        });
+        };
+        self.load_balancing_decisions.push(decision.clone());
+
+        if let Some(sink) = self.sink.clone() {
+            tokio::spawn(async move {
+                sink.record_load_balancing(&decision).await;
+            });
+        }
     }
 
     pub fn finish(&mut self) {
         self.end_time = Some(Utc::now());
     }
 
+    /// Fold a per-client shard into this aggregate: counters add up,
+    /// latency quantiles are P²-merged (see `P2Estimator::merge`), and
+    /// load-balancing decisions are unioned. `start_time` keeps the
+    /// earliest of the two and `end_time` the latest of the two that are
+    /// set. Does not touch `self.sink` - the aggregate's sink handle is
+    /// unrelated to whatever the shard streamed into while it ran.
+    pub fn merge(&mut self, other: &StressTestMetrics) {
+        self.total_requests += other.total_requests;
+        self.successful_requests += other.successful_requests;
+        self.failed_requests += other.failed_requests;
+        self.latency_sum_ms += other.latency_sum_ms;
+        self.latency_count += other.latency_count;
+        self.latency_quantiles.merge(&other.latency_quantiles);
+        self.load_balancing_decisions
+            .extend(other.load_balancing_decisions.iter().cloned());
+
+        if other.start_time < self.start_time {
+            self.start_time = other.start_time;
+        }
+        self.end_time = match (self.end_time, other.end_time) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (Some(a), None) => Some(a),
+            (None, other_end) => other_end,
+        };
+    }
+
     pub fn duration_seconds(&self) -> f64 {
         if let Some(end_time) = self.end_time {
             (end_time - self.start_time).num_milliseconds() as f64 / 1000.0
@@ -83,22 +207,27 @@ impl StressTestMetrics {
     }
 
     pub fn avg_latency_ms(&self) -> f64 {
-        if !self.request_durations_ms.is_empty() {
-            let sum: u64 = self.request_durations_ms.iter().sum();
-            sum as f64 / self.request_durations_ms.len() as f64
+        if self.latency_count > 0 {
+            self.latency_sum_ms as f64 / self.latency_count as f64
         } else {
             0.0
         }
     }
 
+    pub fn p50_latency_ms(&self) -> u64 {
+        self.latency_quantiles.p50.value().unwrap_or(0.0).round() as u64
+    }
+
+    pub fn p90_latency_ms(&self) -> u64 {
+        self.latency_quantiles.p90.value().unwrap_or(0.0).round() as u64
+    }
+
     pub fn p95_latency_ms(&self) -> u64 {
-        if self.request_durations_ms.is_empty() {
-            return 0;
-        }
-        let mut sorted = self.request_durations_ms.clone();
-        sorted.sort();
-        let index = (sorted.len() as f64 * 0.95) as usize;
-        sorted[index.min(sorted.len() - 1)]
+        self.latency_quantiles.p95.value().unwrap_or(0.0).round() as u64
+    }
+
+    pub fn p99_latency_ms(&self) -> u64 {
+        self.latency_quantiles.p99.value().unwrap_or(0.0).round() as u64
     }
 
     pub fn print_summary(&self) {
@@ -115,7 +244,10 @@ impl StressTestMetrics {
         println!();
         println!("Latency Statistics:");
         println!("  Average:             {:.2} ms", self.avg_latency_ms());
+        println!("  P50:                 {} ms", self.p50_latency_ms());
+        println!("  P90:                 {} ms", self.p90_latency_ms());
         println!("  P95:                 {} ms", self.p95_latency_ms());
+        println!("  P99:                 {} ms", self.p99_latency_ms());
         println!();
         println!("Load Balancing Decisions: {}", self.load_balancing_decisions.len());
 
@@ -141,9 +273,13 @@ impl StressTestMetrics {
     }
 }
 
-/// Thread-safe metrics collector
-pub type MetricsCollector = Arc<Mutex<StressTestMetrics>>;
+/// Thread-safe shared aggregate. An `RwLock` rather than a `Mutex` because
+/// `run_stress_test` only needs to write it once per client (on merge) and
+/// monitors only ever read it - readers shouldn't queue up behind one
+/// another, and the rare writer uses a non-blocking `try_write` fast path
+/// (see `run_stress_test`) so it doesn't serialize concurrent clients.
+pub type MetricsCollector = Arc<RwLock<StressTestMetrics>>;
 
 pub fn new_metrics_collector() -> MetricsCollector {
-    Arc::new(Mutex::new(StressTestMetrics::new()))
+    Arc::new(RwLock::new(StressTestMetrics::new()))
 }
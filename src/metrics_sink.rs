@@ -0,0 +1,145 @@
+//! Streaming export of live stress-test metrics, as an alternative to
+//! waiting for `StressTestMetrics::print_summary` to dump aggregates to
+//! stdout at the very end of a run. `MetricsSink` is the extension point;
+//! `KafkaMetricsSink` is the one concrete implementation, backed by an
+//! rdkafka `FutureProducer`, so an operator can watch throughput, latency,
+//! and per-node load decisions off-box while a run is still in progress.
+
+use crate::metrics::LoadBalancingDecision;
+use async_trait::async_trait;
+use log::warn;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Something `StressTestMetrics` can push each request and load-balancing
+/// event into as it happens, in addition to its own in-memory aggregates.
+/// `Send + Sync` so it can be shared behind an `Arc` across the many
+/// concurrently spawned client tasks a stress test runs.
+#[async_trait]
+pub trait MetricsSink: Send + Sync {
+    /// Called alongside `StressTestMetrics::record_request`'s own bookkeeping.
+    async fn record_request(&self, success: bool, duration_ms: u64);
+
+    /// Called alongside `StressTestMetrics::record_load_balancing`'s own
+    /// bookkeeping.
+    async fn record_load_balancing(&self, decision: &LoadBalancingDecision);
+}
+
+fn default_buffer_size() -> usize {
+    100
+}
+
+fn default_client_id() -> String {
+    "distributed-image-cloud-stress-test".to_string()
+}
+
+/// Config for `KafkaMetricsSink`, parsed from JSON the same way the rest of
+/// the crate loads config (see `bootstrap::ClusterConfig::load`), plus a
+/// `from_env` constructor for operators who'd rather not manage a separate
+/// config file for a single stress-test run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProducerConfig {
+    pub brokers: String,
+    pub topic: String,
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+    /// Upper bound on messages rdkafka will queue locally before `send`
+    /// starts backpressuring the caller.
+    #[serde(default = "default_buffer_size")]
+    pub buffer_size: usize,
+}
+
+impl ProducerConfig {
+    /// Parse a JSON config file in the same shape this struct derives.
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read producer config {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse producer config {}: {}", path.display(), e))
+    }
+
+    /// Build a config from `METRICS_KAFKA_BROKERS` and `METRICS_KAFKA_TOPIC`
+    /// (required), plus optional `METRICS_KAFKA_CLIENT_ID` and
+    /// `METRICS_KAFKA_BUFFER_SIZE` overrides.
+    pub fn from_env() -> Result<Self, String> {
+        let brokers = std::env::var("METRICS_KAFKA_BROKERS")
+            .map_err(|_| "METRICS_KAFKA_BROKERS is not set".to_string())?;
+        let topic = std::env::var("METRICS_KAFKA_TOPIC")
+            .map_err(|_| "METRICS_KAFKA_TOPIC is not set".to_string())?;
+        let client_id = std::env::var("METRICS_KAFKA_CLIENT_ID").unwrap_or_else(|_| default_client_id());
+        let buffer_size = std::env::var("METRICS_KAFKA_BUFFER_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(default_buffer_size);
+
+        Ok(Self {
+            brokers,
+            topic,
+            client_id,
+            buffer_size,
+        })
+    }
+}
+
+/// Publishes `StressTestMetrics` events to a Kafka topic as JSON records.
+/// Load-balancing decisions are keyed by `selected_node` so a consumer can
+/// partition by node id, as requested; per-request latency has no node id
+/// in today's data model (`StressTestMetrics::record_request` takes none),
+/// so those records are keyed by a fixed string instead of a node id that
+/// doesn't exist yet.
+pub struct KafkaMetricsSink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaMetricsSink {
+    pub fn new(config: &ProducerConfig) -> Result<Self, String> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .set("client.id", &config.client_id)
+            .set("queue.buffering.max.messages", &config.buffer_size.to_string())
+            .create()
+            .map_err(|e| format!("Failed to create Kafka producer: {}", e))?;
+
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+        })
+    }
+
+    /// Send one JSON-encoded record. A send failure is logged and swallowed
+    /// rather than propagated - losing one live-metrics record shouldn't
+    /// fail, or even slow down, the stress test that's still running.
+    async fn send(&self, key: &str, payload: String) {
+        let record = FutureRecord::to(&self.topic).key(key).payload(&payload);
+        if let Err((e, _)) = self.producer.send(record, Duration::from_secs(5)).await {
+            warn!("Failed to publish metrics record to Kafka: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl MetricsSink for KafkaMetricsSink {
+    async fn record_request(&self, success: bool, duration_ms: u64) {
+        let payload = serde_json::json!({
+            "type": "request",
+            "success": success,
+            "duration_ms": duration_ms,
+        })
+        .to_string();
+        self.send("request", payload).await;
+    }
+
+    async fn record_load_balancing(&self, decision: &LoadBalancingDecision) {
+        let payload = serde_json::json!({
+            "type": "load_balancing_decision",
+            "timestamp": decision.timestamp,
+            "selected_node": decision.selected_node,
+            "node_loads": decision.node_loads,
+        })
+        .to_string();
+        self.send(&decision.selected_node.to_string(), payload).await;
+    }
+}
@@ -0,0 +1,100 @@
+use log::{debug, warn};
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::lookup_host;
+use tokio::time::{sleep, Duration};
+
+/// How many times to retry a transient DNS failure before giving up on a peer.
+const DNS_RETRY_ATTEMPTS: u32 = 3;
+const DNS_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Resolve a `host:port` peer entry (hostname or literal IP) to a concrete
+/// socket address, retrying a few times on transient DNS failure. Raw
+/// `127.0.0.1:800x`-style entries resolve instantly since `lookup_host`
+/// accepts literal IPs too.
+pub async fn resolve_peer_address(address: &str) -> Result<SocketAddr, String> {
+    let mut last_error = String::new();
+
+    for attempt in 1..=DNS_RETRY_ATTEMPTS {
+        match lookup_host(address).await {
+            Ok(mut addrs) => {
+                if let Some(addr) = addrs.next() {
+                    return Ok(addr);
+                }
+                last_error = format!("DNS lookup for {} returned no addresses", address);
+            }
+            Err(e) => {
+                last_error = format!("DNS lookup for {} failed: {}", address, e);
+            }
+        }
+
+        if attempt < DNS_RETRY_ATTEMPTS {
+            debug!("Retrying DNS resolution for {} (attempt {}/{})", address, attempt, DNS_RETRY_ATTEMPTS);
+            sleep(DNS_RETRY_DELAY).await;
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Whether `addr` is a private/loopback/link-local address that should not be
+/// dialed as a remote peer unless local testing has been explicitly enabled.
+pub fn is_local_address(addr: &SocketAddr) -> bool {
+    match addr.ip() {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xffc0) == 0xfe80,
+    }
+}
+
+/// Resolve every peer entry, filtering out local/private addresses unless
+/// `allow_local_addresses` is set. Misconfigured private addresses in a
+/// production deployment are dropped (with a warning) rather than allowed to
+/// silently create a half-open cluster; entries that fail DNS resolution
+/// entirely are dropped the same way.
+pub async fn resolve_and_filter_peers(
+    raw_addresses: &std::collections::HashMap<crate::messages::NodeId, String>,
+    allow_local_addresses: bool,
+) -> std::collections::HashMap<crate::messages::NodeId, String> {
+    let mut resolved = std::collections::HashMap::new();
+
+    for (&node_id, raw_addr) in raw_addresses {
+        match resolve_peer_address(raw_addr).await {
+            Ok(socket_addr) => {
+                if is_local_address(&socket_addr) && !allow_local_addresses {
+                    warn!(
+                        "Dropping peer {} ({}): local/private address and allow_local_addresses is false",
+                        node_id, raw_addr
+                    );
+                    continue;
+                }
+                resolved.insert(node_id, raw_addr.clone());
+            }
+            Err(e) => {
+                warn!("Dropping peer {} ({}): {}", node_id, raw_addr, e);
+            }
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_and_private_addresses_are_local() {
+        let loopback: SocketAddr = "127.0.0.1:8001".parse().unwrap();
+        let private: SocketAddr = "10.0.0.5:8001".parse().unwrap();
+        let public: SocketAddr = "93.184.216.34:80".parse().unwrap();
+
+        assert!(is_local_address(&loopback));
+        assert!(is_local_address(&private));
+        assert!(!is_local_address(&public));
+    }
+
+    #[tokio::test]
+    async fn resolve_peer_address_accepts_literal_ip() {
+        let addr = resolve_peer_address("127.0.0.1:8001").await.unwrap();
+        assert_eq!(addr.port(), 8001);
+    }
+}
@@ -1,87 +1,544 @@
 use crate::chunking::{ChunkReassembler, ChunkedMessage};
-use crate::election::{ElectionManager, ElectionResult};
+use crate::cluster_layout::{self, ClusterLayout, NodeCapability, ShardConfig};
+use crate::compression::CompressionCodec;
+use crate::election::{self, ElectionManager};
 use crate::encryption;
-use crate::messages::{Message, NodeId, NodeState, ReceivedImageInfo};
+use crate::gossip::GossipTable;
+use crate::identity::{self, NodeIdentity, PairingProof};
+use crate::image_store::{self, ImageBlob, ImageStore};
+use crate::membership::MembershipTable;
+use crate::messages::{
+    self, DeliveryState, GossipRecord, IntegrityError, Message, NodeId, NodeState, ReceivedImageInfo, ReplicatedImage,
+};
+use crate::net_address;
+use crate::peer_store::{self, PeerStore};
+use crate::phi_detector::PHI_FAILURE_THRESHOLD;
+use crate::rapid_membership::{self, EdgeStatus, MembershipService, MultiNodeCut, ReportOutcome};
+use crate::replication;
+use crate::request_strategy::{broadcast_request, RequestStrategy};
+use crate::secure_session::{self, HandshakeState, Role, SessionReader, SessionWriter};
+use crate::telemetry::{ClusterEvent, ClusterEventKind, ClusterTelemetrySink};
+use crate::upload_session::{self, UploadSession};
+use crate::user_directory::{self, UserDirectory};
 use log::{debug, error, info, warn};
+use rand::seq::SliceRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::net::UdpSocket;
-use tokio::sync::{Mutex, RwLock};
+use tokio::signal;
+use tokio::sync::{Mutex, RwLock, Semaphore};
 use tokio::time::{interval, sleep};
 
-/// Stored image data
-#[derive(Clone, Debug)]
+/// Stored image metadata. The bytes themselves live behind `blob` - inline
+/// for small images, spilled to its own zstd-compressed file on disk for
+/// anything at or above `image_store::INLINE_THRESHOLD` - see `image_store.rs`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StoredImage {
     pub image_id: String,
     pub from_username: String,
-    pub encrypted_data: Vec<u8>,
+    pub blob: ImageBlob,
     pub remaining_views: u32,
     pub max_views: u32,
     pub timestamp: i64,
+    // SHA-256 digest of the image's decompressed bytes, computed once at
+    // ingest (see the `SendImage` handler) and checked again on `ViewImage`
+    // and replica transfer to catch corruption - see `messages::checksum`.
+    pub checksum: [u8; 32],
+    // Whether the recipient has already been told about this image - either
+    // live via `push_notification`'s `ImageNotification` at `SendImage` time
+    // (recipient online), or via the `pending_images` flush on their next
+    // `Message::SessionRegister` (recipient was offline). Lets that flush
+    // send only images the recipient genuinely hasn't heard about yet,
+    // rather than re-announcing their whole history every time they log
+    // back in.
+    pub notified: bool,
 }
 
-/// Cached load information for a peer node
-#[derive(Clone, Debug)]
-pub struct CachedLoadInfo {
-    pub load: f64,
-    pub processed_count: usize,
-    pub timestamp: Instant,
+/// An established per-peer secure channel (see `secure_session.rs`): one
+/// reader/writer half each, so sealing an outbound message and opening an
+/// inbound one never contend on the same lock for longer than they have to.
+struct PeerSession {
+    reader: SessionReader,
+    writer: SessionWriter,
+}
+
+/// Generate a fresh, random session token for a successful `SessionRegister`,
+/// stored server-side keyed by username and checked back against whatever a
+/// later `Handshake` presents.
+fn generate_session_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Number of `in_flight_requests` stripes: duplicate-detection for two
+/// request IDs that hash to different stripes proceeds without contending
+/// on the same lock. A `std::sync::RwLock` is used for each stripe (rather
+/// than `tokio::sync::RwLock`, as everywhere else in this struct) because
+/// `ActiveRequestGuard::drop` needs to remove the request synchronously;
+/// the critical section is a plain `HashSet` membership check that's never
+/// held across an `.await`, so a std lock costs nothing over the tokio one
+/// it replaces.
+const IN_FLIGHT_STRIPES: usize = 16;
+
+/// Number of random live peers `gossip_task` pulls from (and separately,
+/// proactively pushes to) per round. Keeping this small and fixed (rather
+/// than all known peers) is what bounds gossip's per-round outbound traffic
+/// independent of cluster size.
+const GOSSIP_FANOUT: usize = 2;
+
+/// Max number of records `gossip_task` includes in an unsolicited push.
+/// Pushing everything we know every round would make push traffic grow with
+/// cluster size just like the old heartbeat fan-out did; capping it and
+/// picking a random subset each round still gets every record to every peer
+/// within a few rounds, just spread out instead of all at once.
+const GOSSIP_PUSH_SAMPLE: usize = 4;
+
+/// How long an exhausted (`remaining_views == 0`) or TTL-expired image sits
+/// as a tombstone candidate before `image_gc_task` will actually remove it.
+/// Gives a view that's still in flight on another replica (not yet synced
+/// via `ReplicationPush`/`ReplicationSync`) time to land first, rather than
+/// racing a delete against it.
+const TOMBSTONE_GRACE_SECS: i64 = 60;
+
+/// Images older than this are expired regardless of remaining view count.
+const IMAGE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// How long an `UploadSession` can sit unfinished (no `UploadPart`/
+/// `CompleteUpload` progress checked) before `upload_session_gc_task`
+/// reclaims its temp file - a client that began an upload and never
+/// finished it shouldn't leak disk space forever.
+const UPLOAD_SESSION_TTL: Duration = Duration::from_secs(300);
+
+/// How often `key_rotation_task` ratchets each established secure session's
+/// key forward (see `secure_session::SessionWriter::rotate`). Long enough
+/// that rotation overhead (one extra envelope per peer per interval) is
+/// negligible, short enough to bound how much traffic any one compromised
+/// key could have exposed.
+const KEY_ROTATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long `graceful_leave_task` waits for `Message::LeaveAck` replies
+/// before exiting the process anyway - peers that don't answer in time just
+/// fall back to detecting the departure the normal way (gossip staleness).
+const LEAVE_ACK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Total outgoing bytes allowed in flight at once across every concurrent
+/// send (see `outgoing_bytes_budget`). A `broadcast_request` fan-out to many
+/// peers, or several large `EncryptionRequest` forwards overlapping, can
+/// otherwise queue enough simultaneous `send_to` calls to exhaust the OS
+/// socket send buffer ("No buffer space available", the same class of error
+/// `RETRANSMIT_BYTE_BUDGET`'s 2ms inter-chunk delay already works around for
+/// a single large transfer).
+const OUTGOING_BYTES_BUDGET: usize = 4 * 1024 * 1024;
+
+/// Number of subjects each node monitors in the Rapid-style expander
+/// topology (see `rapid_membership::ExpanderTopology`). Small clusters get
+/// an all-to-all-equivalent graph anyway since `degree <= member_count - 1`,
+/// but this still bounds monitoring fan-out as the cluster grows.
+const RAPID_K: usize = 4;
+
+/// Stable-report threshold: an edge status is only proposed for the next
+/// `MultiNodeCut` once this many distinct observers agree on it.
+const RAPID_H: usize = 3;
+
+/// Unstable-zone floor: fewer than this many corroborating observers is
+/// treated as noise rather than even an "unstable, still waiting" signal.
+const RAPID_L: usize = 2;
+
+/// How often `rapid_monitor_task` re-probes this node's assigned subjects.
+const RAPID_MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Bytes of data-store capacity one unit of `NodeCapability::capacity` is
+/// taken to represent, for the `NodeStats::store_capacity_bytes` free-space
+/// report - `capacity` is already a relative, operator-chosen unit (see
+/// `cluster_layout.rs`) rather than a literal measurement, so total store
+/// capacity is derived from it the same way rather than querying the
+/// filesystem for a number that wouldn't mean anything comparable across
+/// nodes with different capacity tags anyway.
+const STORE_BYTES_PER_CAPACITY_UNIT: u64 = 1024 * 1024 * 1024;
+
+fn in_flight_stripe_index(request_id: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    request_id.hash(&mut hasher);
+    (hasher.finish() as usize) % IN_FLIGHT_STRIPES
+}
+
+/// Registers `request_id` as active for as long as it's held: bumps the
+/// shared `active_requests` counter on construction and both undoes that
+/// and removes the request from its in-flight stripe on drop, so every
+/// return path out of the `EncryptionRequest` handler cleans up without
+/// having to remember to.
+struct ActiveRequestGuard {
+    active_requests: Arc<AtomicUsize>,
+    in_flight_requests: Arc<Vec<std::sync::RwLock<HashSet<String>>>>,
+    request_id: String,
+}
+
+impl Drop for ActiveRequestGuard {
+    fn drop(&mut self) {
+        self.active_requests.fetch_sub(1, Ordering::Relaxed);
+        let stripe = &self.in_flight_requests[in_flight_stripe_index(&self.request_id)];
+        if let Ok(mut set) = stripe.write() {
+            set.remove(&self.request_id);
+        }
+    }
 }
 
 /// Cloud Node that participates in the distributed system
+#[derive(Clone)]
 pub struct CloudNode {
     pub id: NodeId,
     pub address: String,
     pub state: Arc<RwLock<NodeState>>,
     pub election_manager: Arc<Mutex<ElectionManager>>,
-    pub current_load: Arc<RwLock<f64>>,
-    pub active_requests: Arc<RwLock<usize>>, // Number of requests currently being processed
+    pub active_requests: Arc<AtomicUsize>, // Number of requests currently being processed; current_load is derived from this, see `current_load()`
     pub peer_addresses: HashMap<NodeId, String>,
     pub processed_requests: Arc<RwLock<usize>>, // Total completed (for metrics only)
     pub active_sessions: Arc<RwLock<HashMap<String, String>>>, // username -> client_id
     pub stored_images: Arc<RwLock<HashMap<String, Vec<StoredImage>>>>, // username -> list of images
+    pub image_store: Arc<ImageStore>, // Spills large image bytes to disk and persists stored_images' metadata across restarts - see image_store.rs
     pub chunk_reassembler: Arc<Mutex<ChunkReassembler>>, // For reassembling multi-packet messages
-    pub in_flight_requests: Arc<RwLock<HashSet<String>>>, // Track active request IDs to prevent duplicates
+    pub in_flight_requests: Arc<Vec<std::sync::RwLock<HashSet<String>>>>, // Sharded (see `IN_FLIGHT_STRIPES`) to prevent duplicates without a single global lock
     pub chunk_cache: Arc<RwLock<HashMap<String, Vec<ChunkedMessage>>>>, // Cache sent chunks for retransmission
-    pub last_heartbeat: Arc<RwLock<HashMap<NodeId, Instant>>>, // Track last heartbeat from each peer
     pub failed_nodes: Arc<RwLock<HashSet<NodeId>>>, // Nodes detected as failed
-    pub peer_load_cache: Arc<RwLock<HashMap<NodeId, CachedLoadInfo>>>, // Cached load info from heartbeats
+    pub gossip: Arc<RwLock<GossipTable>>, // Anti-entropy view of every peer's load/liveness, disseminated by gossip_task instead of direct heartbeats
+    pub membership: Arc<RwLock<MembershipTable>>, // Gossiped view of the live cluster
+    pub identity: Arc<NodeIdentity>, // Persistent ed25519 keypair proving this node's identity
+    pub verified_peers: Arc<RwLock<HashSet<NodeId>>>, // Peers that completed the pairing handshake
+    known_static_keys: Arc<RwLock<HashMap<NodeId, [u8; 32]>>>, // Static public keys learned from completed pairings, the trust anchor for secure_sessions' handshake
+    secure_sessions: Arc<RwLock<HashMap<NodeId, PeerSession>>>, // Established per-peer secure channels (see secure_session.rs)
+    client_trusted_keys: Arc<RwLock<HashMap<String, [u8; 32]>>>, // Static public keys pinned to a client username on its first secure handshake - trust-on-first-use, since clients have no out-of-band pairing step like known_static_keys does for peers
+    client_secure_sessions: Arc<RwLock<HashMap<String, PeerSession>>>, // Established per-client secure channels, keyed by username instead of NodeId
+    pub peer_store: Arc<Mutex<PeerStore>>, // On-disk record of every peer ever seen, survives restarts
+    pub allow_local_addresses: bool, // Whether private/loopback/link-local peer addresses may be dialed
+    pub mdns_daemon: Arc<Mutex<Option<mdns_sd::ServiceDaemon>>>, // Kept alive for as long as we advertise over mDNS
+    pub user_directory: Arc<Mutex<UserDirectory>>, // On-disk record of every username ever registered, survives restarts
+    pub notification_subscribers: Arc<RwLock<HashMap<String, SocketAddr>>>, // username -> address of its long-lived notification socket
+    pub session_tokens: Arc<RwLock<HashMap<String, String>>>, // username -> token issued at SessionRegister, checked during Handshake
+    authenticated_sessions: Arc<RwLock<HashSet<String>>>, // Usernames that completed AuthProve against their password verifier; checked by QueryReceivedImages/ViewImage when user_directory.is_protected
+    pending_auth_challenges: Arc<RwLock<HashMap<String, [u8; 16]>>>, // username -> nonce issued by the most recent AuthChallenge, consumed by the matching AuthProve
+    upload_sessions: Arc<RwLock<HashMap<String, UploadSession>>>, // request_id -> in-progress BeginUpload/UploadPart staging, see upload_session.rs
+    upload_temp_dir: PathBuf, // Where upload_sessions' staged temp files live
+    outgoing_bytes_budget: Arc<Semaphore>, // Caps total in-flight outgoing bytes across concurrent sends (see `send_message_to_node_once`), so a `broadcast_request` fan-out can't exhaust the OS's socket send buffers
+    telemetry: Arc<RwLock<Option<Arc<dyn ClusterTelemetrySink>>>>, // Optional Kafka mirror of cluster events (see `telemetry.rs`); a no-op when unset
+    rapid: Arc<Mutex<MembershipService>>, // Agreed-upon membership view (see `rapid_membership.rs`), consulted before each coordinator election so it can't diverge across nodes
+    capability: Arc<RwLock<NodeCapability>>, // This node's own advertised placement capacity/zone (see `cluster_layout.rs`); defaults to capacity 1.0 in "default" zone until `set_capability` is called
+    peer_capabilities: Arc<RwLock<HashMap<NodeId, NodeCapability>>>, // Capacities/zones learned from peers' `LoadResponse`s, fed into `cluster_layout` recomputation
+    cluster_layout: Arc<RwLock<ClusterLayout>>, // Versioned partition-assignment table recomputed whenever the Rapid-committed member set changes
+    shard_config: Arc<RwLock<ShardConfig>>, // This node's own modulo-sharded keyspace slice (see `cluster_layout::ShardConfig`); defaults to a single shard covering everything until `set_shard_config` is called
+    peer_shard_configs: Arc<RwLock<HashMap<NodeId, ShardConfig>>>, // Shard assignments learned from peers' `AnnounceShardConfig`, cached alongside `peer_load_cache` (`gossip`)
 }
 
 impl CloudNode {
     pub fn new(id: NodeId, address: String, peer_addresses: HashMap<NodeId, String>) -> Self {
+        Self::new_with_options(id, address, peer_addresses, false)
+    }
+
+    /// Like `new`, but with explicit control over whether private/loopback
+    /// addresses are allowed. Local multi-process testing (`127.0.0.1:800x`)
+    /// needs `allow_local_addresses = true`; production deployments should
+    /// leave it `false` so a misconfigured private address doesn't silently
+    /// create a half-open cluster.
+    pub fn new_with_options(
+        id: NodeId,
+        address: String,
+        peer_addresses: HashMap<NodeId, String>,
+        allow_local_addresses: bool,
+    ) -> Self {
         let election_manager = ElectionManager::new(id, peer_addresses.clone());
+        let membership = MembershipTable::bootstrap(&peer_addresses);
+        let peer_addresses_for_rapid: HashSet<NodeId> = peer_addresses.keys().copied().collect();
+
+        let peer_store_path = peer_store::default_peer_store_path(&address);
+        let peer_store = PeerStore::load(&peer_store_path, &peer_addresses);
+
+        let user_directory_path = user_directory::default_user_directory_path(&address);
+        let user_directory = UserDirectory::load(&user_directory_path);
+
+        let image_store = ImageStore::new(
+            image_store::default_image_store_dir(&address),
+            image_store::default_image_index_path(&address),
+        );
+        let stored_images = image_store.load_index();
+        info!(
+            "[Node {}] Loaded {} image record(s) from the on-disk index",
+            id,
+            stored_images.values().map(|images| images.len()).sum::<usize>()
+        );
+
+        let identity_path = identity::default_identity_path(&address);
+        let identity = match NodeIdentity::load_or_generate(&identity_path) {
+            Ok(identity) => identity,
+            Err(e) => {
+                warn!(
+                    "[Node {}] Failed to load/generate persistent identity ({}), using an ephemeral key",
+                    id, e
+                );
+                NodeIdentity {
+                    signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng),
+                }
+            }
+        };
 
         Self {
             id,
             address: address.clone(),
             state: Arc::new(RwLock::new(NodeState::Active)),
             election_manager: Arc::new(Mutex::new(election_manager)),
-            current_load: Arc::new(RwLock::new(0.0)),
-            active_requests: Arc::new(RwLock::new(0)),
+            active_requests: Arc::new(AtomicUsize::new(0)),
             peer_addresses,
             processed_requests: Arc::new(RwLock::new(0)),
             active_sessions: Arc::new(RwLock::new(HashMap::new())),
-            stored_images: Arc::new(RwLock::new(HashMap::new())),
+            stored_images: Arc::new(RwLock::new(stored_images)),
+            image_store: Arc::new(image_store),
             chunk_reassembler: Arc::new(Mutex::new(ChunkReassembler::new())),
-            in_flight_requests: Arc::new(RwLock::new(HashSet::new())),
+            in_flight_requests: Arc::new((0..IN_FLIGHT_STRIPES).map(|_| std::sync::RwLock::new(HashSet::new())).collect()),
             chunk_cache: Arc::new(RwLock::new(HashMap::new())),
-            last_heartbeat: Arc::new(RwLock::new(HashMap::new())),
             failed_nodes: Arc::new(RwLock::new(HashSet::new())),
-            peer_load_cache: Arc::new(RwLock::new(HashMap::new())),
+            gossip: Arc::new(RwLock::new(GossipTable::new(id))),
+            membership: Arc::new(RwLock::new(membership)),
+            identity: Arc::new(identity),
+            verified_peers: Arc::new(RwLock::new(HashSet::new())),
+            known_static_keys: Arc::new(RwLock::new(HashMap::new())),
+            secure_sessions: Arc::new(RwLock::new(HashMap::new())),
+            client_trusted_keys: Arc::new(RwLock::new(HashMap::new())),
+            client_secure_sessions: Arc::new(RwLock::new(HashMap::new())),
+            peer_store: Arc::new(Mutex::new(peer_store)),
+            allow_local_addresses,
+            mdns_daemon: Arc::new(Mutex::new(None)),
+            user_directory: Arc::new(Mutex::new(user_directory)),
+            notification_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            session_tokens: Arc::new(RwLock::new(HashMap::new())),
+            authenticated_sessions: Arc::new(RwLock::new(HashSet::new())),
+            pending_auth_challenges: Arc::new(RwLock::new(HashMap::new())),
+            upload_sessions: Arc::new(RwLock::new(HashMap::new())),
+            upload_temp_dir: upload_session::default_upload_temp_dir(&address),
+            outgoing_bytes_budget: Arc::new(Semaphore::new(OUTGOING_BYTES_BUDGET)),
+            telemetry: Arc::new(RwLock::new(None)),
+            rapid: Arc::new(Mutex::new(MembershipService::new(
+                id,
+                peer_addresses_for_rapid,
+                RAPID_K,
+                RAPID_H,
+                RAPID_L,
+            ))),
+            capability: Arc::new(RwLock::new(NodeCapability::default())),
+            peer_capabilities: Arc::new(RwLock::new(HashMap::new())),
+            cluster_layout: Arc::new(RwLock::new(ClusterLayout::compute(
+                &HashMap::from([(id, NodeCapability::default())]),
+                cluster_layout::REPLICATION_FACTOR,
+                cluster_layout::NUM_PARTITIONS,
+                0,
+            ))),
+            shard_config: Arc::new(RwLock::new(ShardConfig::default())),
+            peer_shard_configs: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Attach a cluster-telemetry sink (e.g. `KafkaClusterTelemetrySink`),
+    /// mirroring heartbeats, failure detection, election outcomes, and load
+    /// reports to it from here on. Left unset, every `emit_telemetry` call
+    /// is a no-op - callers that don't configure Kafka pay nothing for this.
+    pub async fn set_telemetry(&self, sink: Arc<dyn ClusterTelemetrySink>) {
+        *self.telemetry.write().await = Some(sink);
+    }
+
+    /// Publish one cluster event if a telemetry sink is attached; otherwise
+    /// does nothing. Takes the event by value since callers build it fresh
+    /// at each call site.
+    async fn emit_telemetry(&self, event: ClusterEvent) {
+        if let Some(sink) = self.telemetry.read().await.clone() {
+            sink.publish(event);
+        }
+    }
+
+    /// Current load, derived directly from the active-request counter
+    /// rather than tracked as a separate value that could drift out of
+    /// sync with it.
+    fn current_load(&self) -> f64 {
+        self.active_requests.load(Ordering::Relaxed) as f64
+    }
+
+    /// Advertise this node's relative processing capacity and availability
+    /// zone for data-placement purposes (see `cluster_layout.rs`), and
+    /// immediately recompute the local `ClusterLayout` so it takes effect.
+    /// Left uncalled, a node advertises the default (`capacity: 1.0`,
+    /// `zone: "default"`).
+    pub async fn set_capability(&self, capacity: f64, zone: String) {
+        *self.capability.write().await = NodeCapability { capacity, zone };
+        self.recompute_layout().await;
+    }
+
+    /// This node's currently advertised capacity/zone, e.g. so an admin API
+    /// handler can default a partial `set_capability` update to whatever
+    /// isn't being changed.
+    pub async fn capability(&self) -> NodeCapability {
+        self.capability.read().await.clone()
+    }
+
+    /// Rebuild `cluster_layout` from this node's own capability plus
+    /// whatever peer capabilities have been learned so far, restricted to
+    /// the Rapid-committed member set, and bump its version. Called after
+    /// `set_capability` and whenever the committed member set itself
+    /// changes (see `propose_pending_rapid_cut`), so every node's table
+    /// stays in sync with the same deterministic inputs.
+    async fn recompute_layout(&self) {
+        let committed = self.rapid.lock().await.committed_members();
+        let mut capabilities: HashMap<NodeId, NodeCapability> =
+            self.peer_capabilities.read().await.clone();
+        capabilities.retain(|id, _| committed.contains(id));
+        capabilities.insert(self.id, self.capability.read().await.clone());
+
+        let next_version = self.cluster_layout.read().await.version() + 1;
+        let layout = ClusterLayout::compute(
+            &capabilities,
+            cluster_layout::REPLICATION_FACTOR,
+            cluster_layout::NUM_PARTITIONS,
+            next_version,
+        );
+        info!("[Node {}] Recomputed cluster layout (v{}, {} nodes)", self.id, next_version, capabilities.len());
+        *self.cluster_layout.write().await = layout;
+    }
+
+    /// Record a peer's advertised capacity/zone, learned from a
+    /// `LoadResponse`, for the next `recompute_layout` call.
+    async fn note_peer_capability(&self, peer_id: NodeId, capacity: f64, zone: String) {
+        self.peer_capabilities.write().await.insert(peer_id, NodeCapability { capacity, zone });
+    }
+
+    /// Advertise this node's slice of the modulo-sharded keyspace (see
+    /// `cluster_layout::ShardConfig`) and broadcast it to every known peer
+    /// via `AnnounceShardConfig`. Left uncalled, a node advertises the
+    /// default single shard covering the whole keyspace.
+    pub async fn set_shard_config(&self, num_shards: usize, shard_id: usize) {
+        *self.shard_config.write().await = ShardConfig { num_shards, shard_id };
+        self.announce_shard_config().await;
+    }
+
+    async fn announce_shard_config(&self) {
+        let config = *self.shard_config.read().await;
+        for peer_id in self.peer_addresses.keys().copied() {
+            let message = Message::AnnounceShardConfig {
+                node_id: self.id,
+                num_shards: config.num_shards,
+                shard_id: config.shard_id,
+            };
+            if let Err(e) = self.send_secure_message_to_node(peer_id, message).await {
+                debug!("[Node {}] Failed to announce shard config to Node {}: {}", self.id, peer_id, e);
+            }
+        }
+    }
+
+    /// Peers (not including ourself) whose advertised `ShardConfig` covers
+    /// `image_id`, ordered least-loaded first using the gossiped
+    /// `peer_load_cache` (`self.gossip`) - for use when a node needs a
+    /// chunk or image it doesn't hold locally, instead of guessing at every
+    /// peer or falling back to the whole live set.
+    async fn shard_aware_candidates(&self, image_id: &str) -> Vec<NodeId> {
+        let failed = self.failed_nodes.read().await.clone();
+        let peer_shard_configs = self.peer_shard_configs.read().await;
+        let gossip = self.gossip.read().await;
+
+        let mut candidates: Vec<(NodeId, f64)> = peer_shard_configs
+            .iter()
+            .filter(|(id, config)| !failed.contains(id) && config.covers(image_id))
+            .map(|(&id, _)| {
+                let load = gossip.get(id).map(|record| record.load).unwrap_or(f64::MAX);
+                (id, load)
+            })
+            .collect();
+
+        // load comes from gossip, which merges whatever a peer last
+        // reported with no validation - a NaN must not panic this sort.
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        candidates.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Whether this node's own `ShardConfig` is satisfied for `image_id`:
+    /// vacuously true if our shard doesn't cover it at all (someone else's
+    /// responsibility), otherwise true only once a local copy actually
+    /// landed in `stored_images`. Checked before telling a client an image
+    /// finished storing, so a node can't ack a write for a shard it doesn't
+    /// actually own a copy of.
+    async fn shard_finalized(&self, image_id: &str) -> bool {
+        if !self.shard_config.read().await.covers(image_id) {
+            return true;
+        }
+        self.stored_images.read().await.values().any(|images| images.iter().any(|img| img.image_id == image_id))
+    }
+
+    /// Check `request_id` against its in-flight stripe and, unless it's a
+    /// plain (non-forwarded) duplicate, register it as active. Returns the
+    /// guard to hold for the duration of handling - dropping it undoes both
+    /// the stripe membership and the active-request count - or `None` if
+    /// this is a duplicate that should be ignored.
+    fn admit_request(&self, request_id: &str, forwarded: bool) -> Option<ActiveRequestGuard> {
+        let stripe = &self.in_flight_requests[in_flight_stripe_index(request_id)];
+        let already_in_flight = {
+            let mut set = stripe.write().ok()?;
+            if set.contains(request_id) {
+                true
+            } else {
+                set.insert(request_id.to_string());
+                false
+            }
+        };
+
+        if already_in_flight {
+            if !forwarded {
+                return None;
+            }
+            // Coordinator has selected us to process this - override duplicate detection
+            info!(
+                "[Node {}] Processing coordinator-forwarded request {} despite duplicate (coordinator override)",
+                self.id, request_id
+            );
         }
+
+        self.active_requests.fetch_add(1, Ordering::Relaxed);
+        Some(ActiveRequestGuard {
+            active_requests: Arc::clone(&self.active_requests),
+            in_flight_requests: Arc::clone(&self.in_flight_requests),
+            request_id: request_id.to_string(),
+        })
     }
 
     /// Start the cloud node server
     pub async fn start(self: Arc<Self>) -> Result<(), Box<dyn std::error::Error>> {
         info!("[Node {}] Starting on {}", self.id, self.address);
 
+        // Resolve hostnames and drop private/loopback/link-local peers unless
+        // local testing was explicitly enabled, so a misconfigured address
+        // doesn't silently create a half-open cluster.
+        let filtered_peers = net_address::resolve_and_filter_peers(&self.peer_addresses, self.allow_local_addresses).await;
+        if filtered_peers.len() != self.peer_addresses.len() {
+            warn!(
+                "[Node {}] {} of {} configured peer(s) were dropped by address filtering",
+                self.id,
+                self.peer_addresses.len() - filtered_peers.len(),
+                self.peer_addresses.len()
+            );
+        }
+        *self.membership.write().await = MembershipTable::bootstrap(&filtered_peers);
+
         let socket = UdpSocket::bind(&self.address).await?;
         info!("[Node {}] Listening on {} (UDP)", self.id, self.address);
 
+        // Advertise over mDNS so clients on the LAN can discover us without
+        // hand-typed IPs. Non-fatal if unavailable (e.g. no multicast route).
+        match crate::mdns_discovery::advertise_node(self.id, &self.address) {
+            Ok(daemon) => *self.mdns_daemon.lock().await = Some(daemon),
+            Err(e) => warn!("[Node {}] mDNS advertisement unavailable: {}", self.id, e),
+        }
+
         // Start background tasks
         // PRODUCTION MODE: Failure simulation disabled for controlled testing
         // let self_clone = self.clone();
@@ -99,10 +556,25 @@ impl CloudNode {
             self_clone.trigger_election().await;
         });
 
-        // Start heartbeat sender (ping all peers every 2 seconds)
+        // Pair with each known peer so both sides prove ownership of their node key
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(1)).await;
+            self_clone.pair_with_known_peers().await;
+        });
+
+        // Start anti-entropy gossip dissemination (each round pulls load/liveness
+        // from up to `GOSSIP_FANOUT` random live peers and pushes to as many
+        // others, instead of pinging every peer)
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.gossip_task().await;
+        });
+
+        // Start replica anti-entropy sync for stored images
         let self_clone = self.clone();
         tokio::spawn(async move {
-            self_clone.heartbeat_sender_task().await;
+            self_clone.replication_sync_task().await;
         });
 
         // Start failure detector (check for failed nodes every 3 seconds)
@@ -117,6 +589,78 @@ impl CloudNode {
             self_clone.load_monitoring_task().await;
         });
 
+        // Start membership gossip (periodically compare known-hosts digests with peers)
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.membership_gossip_task().await;
+        });
+
+        // Sweep the membership table for peers unseen past its own TTL
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.membership_timeout_task().await;
+        });
+
+        // Announce ourselves to the bootstrap peers so a late join doesn't
+        // have to wait for the next gossip round to be noticed
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.announce_join_task().await;
+        });
+
+        // Probe our assigned Rapid subjects and propose/commit agreed
+        // membership cuts before coordinator elections run
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.rapid_monitor_task().await;
+        });
+
+        // Periodically flush the peer store so a crash doesn't lose recently
+        // learned reconnection info
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.peer_store_flush_task().await;
+        });
+
+        // Periodically flush the user directory for the same reason
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.user_directory_flush_task().await;
+        });
+
+        // Periodically flush the image metadata index so stored_images
+        // survives a restart (spilled blob files are already on disk as
+        // soon as they're written, independent of this flush)
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.image_store_flush_task().await;
+        });
+
+        // Periodically reclaim exhausted/expired stored images
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.image_gc_task().await;
+        });
+
+        // Periodically reclaim abandoned multipart upload sessions
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.upload_session_gc_task().await;
+        });
+
+        // Periodically ratchet established secure-channel keys forward
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.key_rotation_task().await;
+        });
+
+        // Leave the cluster cleanly on Ctrl-C/SIGTERM instead of just
+        // vanishing and waiting to be detected as failed
+        let self_clone = self.clone();
+        tokio::spawn(async move {
+            self_clone.graceful_leave_task().await;
+        });
+
         // Receive incoming datagrams
         let socket = Arc::new(socket);
         let mut buffer = vec![0u8; 65535]; // Max UDP packet size
@@ -129,7 +673,7 @@ impl CloudNode {
                     let socket_clone = socket.clone();
 
                     // Log active request count when new request arrives
-                    let active_count = *self.active_requests.read().await;
+                    let active_count = self.active_requests.load(Ordering::Relaxed);
                     if active_count > 0 {
                         debug!("[Node {}] Received new datagram ({} bytes) while {} requests active - spawning concurrent handler",
                                self.id, n, active_count);
@@ -169,26 +713,46 @@ impl CloudNode {
             Ok(chunked_message) => {
                 // Check if it's a retransmit request
                 if let ChunkedMessage::RetransmitRequest { chunk_id, missing_indices } = chunked_message {
-                    info!("[Node {}] Received retransmit request for {} chunks (ID: {})", 
+                    // Cap how many bytes one retransmit request can push back
+                    // out, so a flood of requests (or one asking for many
+                    // chunks) can't saturate the link.
+                    const RETRANSMIT_BYTE_BUDGET: usize = 200_000;
+
+                    info!("[Node {}] Received retransmit request for {} chunks (ID: {})",
                           self.id, missing_indices.len(), &chunk_id[..8]);
-                    
+
                     // Look up cached chunks
                     let cache = self.chunk_cache.read().await;
                     if let Some(cached_chunks) = cache.get(&chunk_id) {
                         info!("[Node {}] Retransmitting {} missing chunks", self.id, missing_indices.len());
-                        
-                        // Resend only the missing chunks
+
+                        // Resend only the missing chunks, bounded by a byte
+                        // budget so one (or many concurrent) retransmit
+                        // request(s) can't saturate the outbound link; the
+                        // client re-requests whatever's left on its next
+                        // stall, same as it would for any other lost chunk.
+                        let mut bytes_sent = 0usize;
+                        let mut sent_count = 0usize;
                         for &index in &missing_indices {
+                            if bytes_sent >= RETRANSMIT_BYTE_BUDGET {
+                                warn!(
+                                    "[Node {}] Retransmit byte budget exhausted for {} after {}/{} chunk(s); remaining will wait for the next request",
+                                    self.id, chunk_id, sent_count, missing_indices.len()
+                                );
+                                break;
+                            }
                             if let Some(chunk) = cached_chunks.get(index as usize) {
                                 let chunk_bytes = serde_json::to_vec(chunk)?;
+                                bytes_sent += chunk_bytes.len();
+                                sent_count += 1;
                                 socket.send_to(&chunk_bytes, addr).await?;
                                 debug!("[Node {}] Retransmitted chunk {}", self.id, index);
-                                
+
                                 // Small delay between retransmissions
                                 tokio::time::sleep(Duration::from_millis(2)).await;
                             }
                         }
-                        info!("[Node {}] Retransmission complete", self.id);
+                        info!("[Node {}] Retransmission complete ({} chunk(s) sent)", self.id, sent_count);
                     } else {
                         warn!("[Node {}] No cached chunks found for ID {}", self.id, chunk_id);
                     }
@@ -233,8 +797,10 @@ impl CloudNode {
 
         debug!("[Node {}] Received from {}: {}", self.id, addr, message);
 
-        // Process message based on type
-        let response = self.process_message(message, addr).await;
+        // Process message based on type. Arrived in the clear, so no client
+        // username is authenticated yet - see `ClientSecureEnvelope`'s arm
+        // for the path that is.
+        let response = self.process_message(message, addr, None).await;
 
         // Send response if any
         if let Some(response) = response {
@@ -332,10 +898,44 @@ impl CloudNode {
         Ok(())
     }
 
-    /// Process incoming message
-    async fn process_message(&self, message: Message, addr: SocketAddr) -> Option<Message> {
+    /// Process incoming message. `verified_client` is `Some(username)` when
+    /// `message` was recovered from a `ClientSecureEnvelope` whose sender
+    /// already authenticated as that username (see `claimed_client_sender_matches`);
+    /// `None` for anything that arrived in the clear, node-to-node included.
+    async fn process_message(
+        &self,
+        message: Message,
+        addr: SocketAddr,
+        verified_client: Option<&str>,
+    ) -> Option<Message> {
         match message {
             Message::SessionRegister { client_id, username } => {
+                if self.client_trusted_keys.read().await.contains_key(&username) && verified_client != Some(username.as_str()) {
+                    warn!(
+                        "[Node {}] Rejected session registration for '{}' - this username has a pinned identity, complete the secure handshake first",
+                        self.id, username
+                    );
+                    return Some(Message::SessionRegisterResponse {
+                        success: false,
+                        error: Some("Username requires an authenticated secure channel; complete the handshake first".to_string()),
+                        session_token: None,
+                        pending_images: Vec::new(),
+                    });
+                }
+
+                if *self.state.read().await == NodeState::Draining {
+                    info!(
+                        "[Node {}] Rejected session registration for '{}' - draining, not accepting new sessions",
+                        self.id, username
+                    );
+                    return Some(Message::SessionRegisterResponse {
+                        success: false,
+                        error: Some("Node is draining; try another node".to_string()),
+                        session_token: None,
+                        pending_images: Vec::new(),
+                    });
+                }
+
                 let mut sessions = self.active_sessions.write().await;
 
                 // Check if username is already taken
@@ -344,14 +944,51 @@ impl CloudNode {
                     Some(Message::SessionRegisterResponse {
                         success: false,
                         error: Some(format!("Username '{}' is already in use", username)),
+                        session_token: None,
+                        pending_images: Vec::new(),
                     })
                 } else {
                     // Register the session
                     sessions.insert(username.clone(), client_id.clone());
                     info!("[Node {}] Session registered: username '{}' for client '{}'", self.id, username, client_id);
+                    drop(sessions);
+
+                    if self.user_directory.lock().await.record(&username) {
+                        debug!("[Node {}] Added '{}' to the user directory", self.id, username);
+                    }
+
+                    let token = generate_session_token();
+                    self.session_tokens.write().await.insert(username.clone(), token.clone());
+
+                    // Store-and-forward flush: hand back every image that
+                    // arrived while this username had no active session
+                    // (`StoredImage::notified == false`), then mark them
+                    // notified so a later registration from the same user
+                    // doesn't redeliver its whole history.
+                    let mut pending_images = Vec::new();
+                    if let Some(images) = self.stored_images.write().await.get_mut(&username) {
+                        for image in images.iter_mut().filter(|img| !img.notified && img.remaining_views > 0) {
+                            pending_images.push(ReceivedImageInfo {
+                                image_id: image.image_id.clone(),
+                                from_username: image.from_username.clone(),
+                                remaining_views: image.remaining_views,
+                                timestamp: image.timestamp,
+                            });
+                            image.notified = true;
+                        }
+                    }
+                    if !pending_images.is_empty() {
+                        info!(
+                            "[Node {}] Flushing {} pending image(s) to '{}' on registration",
+                            self.id, pending_images.len(), username
+                        );
+                    }
+
                     Some(Message::SessionRegisterResponse {
                         success: true,
                         error: None,
+                        session_token: Some(token),
+                        pending_images,
                     })
                 }
             }
@@ -359,10 +996,101 @@ impl CloudNode {
             Message::SessionUnregister { client_id: _, username } => {
                 let mut sessions = self.active_sessions.write().await;
                 sessions.remove(&username);
+                self.notification_subscribers.write().await.remove(&username);
+                self.session_tokens.write().await.remove(&username);
+                self.authenticated_sessions.write().await.remove(&username);
                 info!("[Node {}] Session unregistered: username '{}'", self.id, username);
                 None
             }
 
+            Message::AuthChallenge { username } => {
+                let salt = self.user_directory.lock().await.salt(&username);
+                if let Some(salt) = salt {
+                    let nonce: [u8; 16] = rand::random();
+                    self.pending_auth_challenges.write().await.insert(username.clone(), nonce);
+                    Some(Message::AuthChallengeResponse { nonce: Some(nonce), salt: Some(salt) })
+                } else {
+                    Some(Message::AuthChallengeResponse { nonce: None, salt: None })
+                }
+            }
+
+            Message::AuthProve { username, nonce, proof } => {
+                let expected_nonce = self.pending_auth_challenges.write().await.remove(&username);
+                let verified = expected_nonce == Some(nonce)
+                    && self.user_directory.lock().await.verify_proof(&username, &nonce, &proof);
+
+                if verified {
+                    self.authenticated_sessions.write().await.insert(username.clone());
+                    info!("[Node {}] '{}' completed password authentication", self.id, username);
+                } else {
+                    warn!("[Node {}] Password authentication failed for '{}'", self.id, username);
+                }
+
+                Some(Message::AuthProveResponse { verified })
+            }
+
+            Message::ChangePassword { username, old_nonce, old_proof, new_salt, new_verifier } => {
+                let mut directory = self.user_directory.lock().await;
+
+                if directory.is_protected(&username) {
+                    // Require old_nonce to be one we ourselves issued via
+                    // AuthChallenge and haven't already consumed - same
+                    // remove-on-use check AuthProve does - rather than just
+                    // re-deriving whether (old_nonce, old_proof) is
+                    // self-consistent. verify_proof alone is a pure function
+                    // of (verifier, nonce), so without this check any
+                    // previously-observed proof for this user (e.g. a
+                    // captured AuthProve) could be replayed indefinitely to
+                    // authorize a password change, defeating the freshness
+                    // guarantee AuthChallenge/AuthProve establish elsewhere.
+                    let expected_nonce = self.pending_auth_challenges.write().await.remove(&username);
+                    let blank = old_nonce == [0u8; 16] && old_proof == [0u8; 32];
+                    if blank
+                        || expected_nonce != Some(old_nonce)
+                        || !directory.verify_proof(&username, &old_nonce, &old_proof)
+                    {
+                        warn!(
+                            "[Node {}] Rejected password change for '{}' - wrong, missing, or stale proof of the current password",
+                            self.id, username
+                        );
+                        return Some(Message::ChangePasswordResponse {
+                            success: false,
+                            error: Some("Incorrect current password proof".to_string()),
+                        });
+                    }
+                }
+
+                directory.set_verifier(&username, new_salt, new_verifier);
+                drop(directory);
+                self.authenticated_sessions.write().await.remove(&username);
+                info!("[Node {}] Updated password protection for '{}'", self.id, username);
+                Some(Message::ChangePasswordResponse { success: true, error: None })
+            }
+
+            Message::Handshake { client_username, session_token, supported_codecs } => {
+                let valid = match self.session_tokens.read().await.get(&client_username) {
+                    Some(expected) => session_token.as_deref() == Some(expected.as_str()),
+                    None => false,
+                };
+
+                if valid {
+                    let codec = CompressionCodec::negotiate(&supported_codecs);
+                    debug!("[Node {}] Handshake accepted for '{}', codec: {:?}", self.id, client_username, codec);
+                    Some(Message::HandshakeResponse {
+                        accepted: true,
+                        codec,
+                        error: None,
+                    })
+                } else {
+                    warn!("[Node {}] Handshake rejected for '{}': bad or missing session token", self.id, client_username);
+                    Some(Message::HandshakeResponse {
+                        accepted: false,
+                        codec: CompressionCodec::None,
+                        error: Some("BadToken: invalid or missing session token".to_string()),
+                    })
+                }
+            }
+
             Message::EncryptionRequest {
                 request_id,
                 client_username,
@@ -371,39 +1099,20 @@ impl CloudNode {
                 quota,
                 forwarded,
                 client_address,
+                codec,
             } => {
-                // Check if this request is already being processed (deduplication)
-                {
-                    let mut in_flight = self.in_flight_requests.write().await;
-                    if in_flight.contains(&request_id) {
-                        if !forwarded {
-                            // Only ignore non-forwarded duplicates
-                            // Forwarded requests from coordinator MUST be processed even if duplicate
-                            warn!("[Node {}] Ignoring duplicate request {} (already in flight)", self.id, request_id);
-                            return None;
-                        } else {
-                            // Coordinator has selected us to process this - override duplicate detection
-                            info!("[Node {}] Processing coordinator-forwarded request {} despite duplicate (coordinator override)",
-                                  self.id, request_id);
-                        }
-                    } else {
-                        // Mark request as in-flight
-                        in_flight.insert(request_id.clone());
-                    }
-                }
-
-                // Increment active requests at the START of handling (whether processing or forwarding)
-                // This tracks all active tasks on this node
-                {
-                    let mut active = self.active_requests.write().await;
-                    *active += 1;
-                    let mut load = self.current_load.write().await;
-                    *load = *active as f64;
-                    info!("[Node {}] Handling request {} (active requests now: {})",
-                          self.id, request_id, *active);
-                }
+                // Check for an already-in-flight duplicate and, unless
+                // overridden by `forwarded`, admit this request: the guard
+                // holds the active-request slot and in-flight stripe entry
+                // for the rest of this arm, releasing both on drop however
+                // handling ends up returning.
+                let Some(_guard) = self.admit_request(&request_id, forwarded) else {
+                    warn!("[Node {}] Ignoring duplicate request {} (already in flight)", self.id, request_id);
+                    return None;
+                };
+                info!("[Node {}] Handling request {} (active requests now: {})",
+                      self.id, request_id, self.active_requests.load(Ordering::Relaxed));
 
-                // Process request and ensure cleanup happens regardless of outcome
                 let response = if forwarded {
                     // Request forwarded by coordinator - MUST process locally
                     info!("[Node {}] Processing forwarded request {} locally (from coordinator)", self.id, request_id);
@@ -411,7 +1120,7 @@ impl CloudNode {
                     // Process encryption (active_requests incremented inside process_encryption_request)
                     let self_clone = Arc::new(self.clone());
                     let result = self_clone
-                        .process_encryption_request(request_id.clone(), image_data, usernames, quota)
+                        .process_encryption_request(request_id.clone(), image_data, usernames, quota, codec)
                         .await;
 
                     // If we have a client_address, send response directly to client
@@ -484,6 +1193,7 @@ impl CloudNode {
                             quota,
                             forwarded: false, // Coordinator will do load balancing
                             client_address: client_addr,
+                            codec,
                         };
 
                         match self.send_message_to_node(coordinator_id, forward_message).await {
@@ -530,7 +1240,7 @@ impl CloudNode {
                             // Process encryption (active_requests managed inside process_encryption_request)
                             let self_clone = Arc::new(self.clone());
                             let result = self_clone
-                                .process_encryption_request(request_id.clone(), image_data, usernames, quota)
+                                .process_encryption_request(request_id.clone(), image_data, usernames, quota, codec)
                                 .await;
 
                             Some(result)
@@ -547,6 +1257,7 @@ impl CloudNode {
                                 quota,
                                 forwarded: true, // Mark as forwarded to prevent loops
                                 client_address, // Pass through client address for direct response
+                                codec,
                             };
 
                             match self.send_message_to_node(lowest_load_node, forward_message).await {
@@ -580,23 +1291,165 @@ impl CloudNode {
                     }
                 };
 
-                // Remove request from in-flight set now that it's complete
-                {
-                    let mut in_flight = self.in_flight_requests.write().await;
-                    in_flight.remove(&request_id);
+                // `_guard` drops here, decrementing active_requests and
+                // removing request_id from its in-flight stripe regardless
+                // of which branch above produced `response`.
+                info!("[Node {}] Finished handling request {} (active requests now: {})",
+                      self.id, request_id, self.active_requests.load(Ordering::Relaxed).saturating_sub(1));
+
+                response
+            }
+
+            Message::BeginUpload { request_id, client_username, usernames, quota, codec, client_address, forwarded } => {
+                if !forwarded {
+                    let assigned = self.find_lowest_load_node().await;
+                    if assigned != self.id {
+                        info!(
+                            "[Node {}] Forwarding upload {} to lowest-load Node {}",
+                            self.id, request_id, assigned
+                        );
+                        let forward = Message::BeginUpload {
+                            request_id: request_id.clone(),
+                            client_username,
+                            usernames,
+                            quota,
+                            codec,
+                            client_address,
+                            forwarded: true,
+                        };
+                        return match self.send_message_to_node(assigned, forward).await {
+                            Ok(Some(response)) => Some(response),
+                            Ok(None) => Some(Message::BeginUploadResponse {
+                                request_id,
+                                accepted: false,
+                                error: Some("Assigned node did not respond".to_string()),
+                            }),
+                            Err(e) => Some(Message::BeginUploadResponse {
+                                request_id,
+                                accepted: false,
+                                error: Some(format!("Assigned node unreachable: {}", e)),
+                            }),
+                        };
+                    }
                 }
 
-                // Decrement active requests now that handling is complete
-                {
-                    let mut active = self.active_requests.write().await;
-                    *active = active.saturating_sub(1);
-                    let mut load = self.current_load.write().await;
-                    *load = *active as f64;
-                    info!("[Node {}] Finished handling request {} (active requests now: {})",
-                          self.id, request_id, *active);
+                match UploadSession::create(
+                    &self.upload_temp_dir,
+                    &request_id,
+                    client_username,
+                    usernames,
+                    quota,
+                    codec,
+                    client_address,
+                ) {
+                    Ok(session) => {
+                        self.upload_sessions.write().await.insert(request_id.clone(), session);
+                        info!("[Node {}] Accepted upload {}", self.id, request_id);
+                        Some(Message::BeginUploadResponse { request_id, accepted: true, error: None })
+                    }
+                    Err(e) => {
+                        error!("[Node {}] Failed to start upload {}: {}", self.id, request_id, e);
+                        Some(Message::BeginUploadResponse { request_id, accepted: false, error: Some(e) })
+                    }
                 }
+            }
 
-                response
+            Message::BeginUploadResponse { .. } => {
+                // Consumed directly as the reply to `send_message_to_node`'s
+                // forwarding call above.
+                None
+            }
+
+            Message::UploadPart { request_id, part_number, data } => {
+                let mut sessions = self.upload_sessions.write().await;
+                let Some(session) = sessions.get_mut(&request_id) else {
+                    return Some(Message::UploadPartResponse {
+                        request_id,
+                        part_number,
+                        success: false,
+                        error: Some("Unknown or expired upload session".to_string()),
+                    });
+                };
+
+                match session.write_part(part_number, &data) {
+                    Ok(()) => Some(Message::UploadPartResponse { request_id, part_number, success: true, error: None }),
+                    Err(e) => Some(Message::UploadPartResponse { request_id, part_number, success: false, error: Some(e) }),
+                }
+            }
+
+            Message::UploadPartResponse { .. } => None,
+
+            Message::CompleteUpload { request_id } => {
+                let Some(session) = self.upload_sessions.write().await.remove(&request_id) else {
+                    return Some(Message::EncryptionResponse {
+                        request_id,
+                        encrypted_image: vec![],
+                        success: false,
+                        error: Some("Unknown or expired upload session".to_string()),
+                    });
+                };
+
+                // Removing the session above already guarantees at most one
+                // `CompleteUpload` reaches here per `request_id`, but still
+                // admit it so `active_requests`/`current_load` account for
+                // the encryption work about to happen, same as
+                // `EncryptionRequest`'s locally-processed branches.
+                let Some(_guard) = self.admit_request(&request_id, true) else {
+                    return Some(Message::EncryptionResponse {
+                        request_id,
+                        encrypted_image: vec![],
+                        success: false,
+                        error: Some("Duplicate completion already in flight".to_string()),
+                    });
+                };
+
+                let client_address = session.client_address.clone();
+                let usernames = session.usernames.clone();
+                let quota = session.quota;
+                let codec = session.codec;
+
+                let result = match session.finalize() {
+                    Ok(image_data) => {
+                        let self_clone = Arc::new(self.clone());
+                        self_clone
+                            .process_encryption_request(request_id.clone(), image_data, usernames, quota, codec)
+                            .await
+                    }
+                    Err(e) => {
+                        error!("[Node {}] Failed to finalize upload {}: {}", self.id, request_id, e);
+                        Message::EncryptionResponse {
+                            request_id: request_id.clone(),
+                            encrypted_image: vec![],
+                            success: false,
+                            error: Some(e),
+                        }
+                    }
+                };
+
+                // Same direct-to-client short-circuit `EncryptionRequest`'s
+                // forwarded branch uses, since `BeginUpload` already routed
+                // this upload to the node that's now finishing it.
+                if let Some(ref client_addr_str) = client_address {
+                    match client_addr_str.parse::<SocketAddr>() {
+                        Ok(client_sock_addr) => {
+                            if let Err(e) = self.send_response_to_client(client_sock_addr, result.clone()).await {
+                                error!(
+                                    "[Node {}] Failed to send direct response for upload {} to client {}: {}",
+                                    self.id, request_id, client_addr_str, e
+                                );
+                                Some(result)
+                            } else {
+                                None
+                            }
+                        }
+                        Err(e) => {
+                            error!("[Node {}] Failed to parse client address '{}': {}", self.id, client_addr_str, e);
+                            Some(result)
+                        }
+                    }
+                } else {
+                    Some(result)
+                }
             }
 
             Message::DecryptionRequest {
@@ -619,39 +1472,78 @@ impl CloudNode {
             }
 
             Message::Election { from_node } => {
-                let load = *self.current_load.read().await;
+                if self.secure_sessions.read().await.contains_key(&from_node) {
+                    warn!(
+                        "[Node {}] Rejected plaintext ELECTION claiming Node {} (secure channel already established)",
+                        self.id, from_node
+                    );
+                    return None;
+                }
+
+                let load = self.current_load();
+                let capability = self.capability.read().await.clone();
+                let phi = self.gossip.read().await.max_peer_phi();
                 let manager = self.election_manager.lock().await;
 
                 let send_fn = |node: NodeId, msg: Message| {
                     let self_clone = self.clone();
                     tokio::spawn(async move {
-                        let _ = self_clone.send_message_to_node(node, msg).await;
+                        let _ = self_clone.send_secure_message_to_node(node, msg).await;
                     });
                     true
                 };
 
-                manager.handle_election_message(from_node, load, send_fn);
+                manager.handle_election_message(from_node, load, capability.capacity, capability.zone, phi, send_fn);
                 None
             }
 
             Message::LoadQuery { from_node: _ } => {
-                let load = *self.current_load.read().await;
-                let active = *self.active_requests.read().await;
+                let load = self.current_load();
+                let active = self.active_requests.load(Ordering::Relaxed);
                 let processed = *self.processed_requests.read().await;
+                let capability = self.capability.read().await.clone();
+                let phi = self.gossip.read().await.max_peer_phi();
                 Some(Message::LoadResponse {
                     node_id: self.id,
                     load,
                     queue_length: active, // Report active requests as "queue"
                     processed_count: processed,
+                    capacity: capability.capacity,
+                    zone: capability.zone,
+                    phi,
                 })
             }
 
-            Message::Coordinator { node_id, load } => {
-                let mut manager = self.election_manager.lock().await;
-                manager.update_coordinator(node_id, load);
+            Message::AnnounceShardConfig { node_id, num_shards, shard_id } => {
+                self.peer_shard_configs.write().await.insert(node_id, ShardConfig { num_shards, shard_id });
                 None
             }
 
+            Message::Coordinator { node_id, load, term, layout_version } => {
+                if self.secure_sessions.read().await.contains_key(&node_id) {
+                    warn!(
+                        "[Node {}] Rejected plaintext COORDINATOR claiming Node {} (secure channel already established)",
+                        self.id, node_id
+                    );
+                    return None;
+                }
+
+                let mut manager = self.election_manager.lock().await;
+                manager.update_coordinator(node_id, load, term, layout_version);
+                drop(manager);
+
+                // A peer's layout is ahead of ours (it saw a committed
+                // membership change we haven't folded in yet) - recompute
+                // rather than keep placing data against a stale table.
+                if layout_version > self.cluster_layout.read().await.version() {
+                    self.recompute_layout().await;
+                }
+
+                // Ack so a quorum-mode commit round (or a plain request/reply
+                // caller) can tell this node actually saw the message.
+                Some(Message::Ok { from_node: self.id })
+            }
+
             Message::StateSync { from_node: _ } => {
                 let manager = self.election_manager.lock().await;
                 let coordinator_id = manager.get_coordinator().unwrap_or(self.id);
@@ -676,60 +1568,427 @@ impl CloudNode {
                 })
             }
 
-            Message::Heartbeat { from_node, load, processed_count } => {
-                // Record that we received a heartbeat from this node
-                {
-                    let now = Instant::now();
-                    let mut heartbeats = self.last_heartbeat.write().await;
-                    heartbeats.insert(from_node, now);
-
-                    // Cache the load information
-                    let mut load_cache = self.peer_load_cache.write().await;
-                    load_cache.insert(from_node, CachedLoadInfo {
-                        load,
-                        processed_count,
-                        timestamp: now,
-                    });
+            Message::GossipPull { from_node, known } => {
+                if self.secure_sessions.read().await.contains_key(&from_node) {
+                    warn!(
+                        "[Node {}] Rejected plaintext GOSSIP_PULL claiming Node {} (secure channel already established)",
+                        self.id, from_node
+                    );
+                    return None;
+                }
+
+                // A peer is pulling from us - make sure our own record is
+                // fresh before replying, then hand back only what we hold
+                // that they don't already know about.
+                let my_load = self.current_load();
+                let my_processed = *self.processed_requests.read().await;
+                let my_state = self.state.read().await.clone();
+                self.gossip.write().await.record_self(my_state, my_load, my_processed);
+
+                let records = self.gossip.read().await.push_for(&known);
 
-                    // If this node was marked as failed, remove it from failed set
-                    let mut failed = self.failed_nodes.write().await;
-                    if failed.remove(&from_node) {
-                        info!("[Node {}] Node {} recovered (heartbeat received)", self.id, from_node);
+                // A pull reaching us at all means this peer is alive; if we
+                // had it marked failed, let membership know it's back.
+                let mut failed = self.failed_nodes.write().await;
+                if failed.remove(&from_node) {
+                    info!("[Node {}] Node {} recovered (gossip pull received)", self.id, from_node);
+                    if let Some(addr) = self.peer_addresses.get(&from_node) {
+                        self.membership.write().await.merge(&[(from_node, addr.clone())]);
                     }
                 }
+                drop(failed);
 
-                // Send acknowledgment with our current load
-                let my_load = *self.current_load.read().await;
-                let my_processed = *self.processed_requests.read().await;
-                Some(Message::HeartbeatAck {
+                Some(Message::GossipPush {
                     from_node: self.id,
-                    load: my_load,
-                    processed_count: my_processed,
+                    records,
                 })
             }
 
-            Message::HeartbeatAck { from_node, load, processed_count } => {
-                // Update last heartbeat time for this node
+            Message::GossipPush { from_node, records } => {
+                if self.secure_sessions.read().await.contains_key(&from_node) {
+                    warn!(
+                        "[Node {}] Rejected plaintext GOSSIP_PUSH claiming Node {} (secure channel already established)",
+                        self.id, from_node
+                    );
+                    return None;
+                }
+
+                let changed = self.gossip.write().await.merge(records);
+                if changed {
+                    debug!("[Node {}] Merged newer gossip records from Node {}", self.id, from_node);
+                }
+
+                // A push reply means this peer is alive; if we had it marked
+                // failed, let membership know it's back.
+                let mut failed = self.failed_nodes.write().await;
+                if failed.remove(&from_node) {
+                    info!("[Node {}] Node {} recovered (gossip push received)", self.id, from_node);
+                    if let Some(addr) = self.peer_addresses.get(&from_node) {
+                        self.membership.write().await.merge(&[(from_node, addr.clone())]);
+                    }
+                }
+                None
+            }
+
+            Message::ReplicationPush { image } => {
+                // Acked so `SendImage`'s write-quorum check (see
+                // `replication::write_quorum`) can tell whether this replica
+                // actually durably applied the push, rather than assuming a
+                // reply always means success.
+                let (accepted, error) = match self.apply_replicated_image(image).await {
+                    Ok(()) => (true, None),
+                    Err(e) => (false, Some(e.to_string())),
+                };
+                Some(Message::ReplicationPushResponse { from_node: self.id, accepted, error })
+            }
+
+            Message::ReplicationPushResponse { .. } => {
+                // Consumed directly as the reply to the `ReplicationPush`
+                // send above.
+                None
+            }
+
+            Message::ReplicationSync { from_node, bucket_hashes } => {
+                if self.secure_sessions.read().await.contains_key(&from_node) {
+                    warn!(
+                        "[Node {}] Rejected plaintext REPLICATION_SYNC claiming Node {} (secure channel already established)",
+                        self.id, from_node
+                    );
+                    return None;
+                }
+
+                let local_entries = self.replication_entries().await;
+                let local_hashes = replication::bucket_hashes(&local_entries);
+                let mismatched = replication::mismatched_buckets(&local_hashes, &bucket_hashes);
+
+                let records = if mismatched.is_empty() {
+                    Vec::new()
+                } else {
+                    self.stored_images
+                        .read()
+                        .await
+                        .iter()
+                        .flat_map(|(username, images)| {
+                            images.iter().filter_map(move |image| {
+                                if !mismatched.contains(&replication::bucket_of(username, &image.image_id)) {
+                                    return None;
+                                }
+                                match self.image_store.get(&image.blob) {
+                                    Ok(encrypted_data) => Some(ReplicatedImage {
+                                        username: username.clone(),
+                                        image_id: image.image_id.clone(),
+                                        from_username: image.from_username.clone(),
+                                        encrypted_data,
+                                        remaining_views: image.remaining_views,
+                                        max_views: image.max_views,
+                                        timestamp: image.timestamp,
+                                        checksum: image.checksum,
+                                        notified: image.notified,
+                                    }),
+                                    Err(e) => {
+                                        warn!(
+                                            "[Node {}] Skipping image {} in replication sync - failed to read: {}",
+                                            self.id, image.image_id, e
+                                        );
+                                        None
+                                    }
+                                }
+                            })
+                        })
+                        .collect()
+                };
+
+                debug!(
+                    "[Node {}] Replication sync from Node {}: {} mismatched bucket(s), sending {} record(s)",
+                    self.id, from_node, mismatched.len(), records.len()
+                );
+
+                Some(Message::ReplicationSyncResponse { from_node: self.id, records })
+            }
+
+            Message::ReplicationSyncResponse { from_node, records } => {
+                if self.secure_sessions.read().await.contains_key(&from_node) {
+                    warn!(
+                        "[Node {}] Rejected plaintext REPLICATION_SYNC_RESPONSE claiming Node {} (secure channel already established)",
+                        self.id, from_node
+                    );
+                    return None;
+                }
+
+                let count = records.len();
+                for image in records {
+                    self.apply_replicated_image(image).await;
+                }
+                if count > 0 {
+                    debug!(
+                        "[Node {}] Applied {} replicated record(s) from Node {}'s sync response",
+                        self.id, count, from_node
+                    );
+                }
+                None
+            }
+
+            Message::NeedImageQuery { username, image_id } => {
+                let still_needed = self
+                    .stored_images
+                    .read()
+                    .await
+                    .get(&username)
+                    .map(|images| images.iter().any(|img| img.image_id == image_id && img.remaining_views > 0))
+                    .unwrap_or(false);
+
+                Some(Message::NeedImageQueryResponse { still_needed })
+            }
+
+            Message::NeedImageQueryResponse { .. } => {
+                // Consumed directly as the reply to `image_gc_task`'s own
+                // `send_secure_message_to_node` call - see its arm above for
+                // `GossipPull`/`GossipPush` and `SecureHandshakeAck` for the
+                // same request/reply pattern.
+                None
+            }
+
+            Message::SecureHandshakeInit { from_node, ephemeral_public, signature } => {
+                let Some(peer_static_key) = self.known_static_keys.read().await.get(&from_node).copied() else {
+                    warn!(
+                        "[Node {}] Rejected secure handshake from Node {} (no static key on file - pair first)",
+                        self.id, from_node
+                    );
+                    return None;
+                };
+
+                let (state, offer) = HandshakeState::begin(&self.identity);
+                let peer_message = secure_session::HandshakeMessage { ephemeral_public, signature };
+                match state.finish(Role::Responder, &peer_static_key, &peer_message) {
+                    Ok(session) => {
+                        let (reader, writer) = session.split();
+                        self.secure_sessions.write().await.insert(from_node, PeerSession { reader, writer });
+                        info!("[Node {}] Established secure channel with Node {}", self.id, from_node);
+                        Some(Message::SecureHandshakeAck {
+                            from_node: self.id,
+                            ephemeral_public: offer.ephemeral_public,
+                            signature: offer.signature,
+                        })
+                    }
+                    Err(e) => {
+                        warn!("[Node {}] Secure handshake with Node {} failed: {}", self.id, from_node, e);
+                        None
+                    }
+                }
+            }
+
+            Message::SecureHandshakeAck { .. } => {
+                // Normally consumed directly as the reply to
+                // `establish_secure_session`'s own `send_message_to_node` call
+                // (same request/reply pattern as `GossipPull`/`GossipPush` -
+                // see their arms above), so there's no in-progress handshake
+                // state here to finish if one arrives unsolicited.
+                None
+            }
+
+            Message::SecureEnvelope { from_node, sealed } => {
+                let Some(inner) = self.open_from_peer(from_node, &sealed).await else {
+                    warn!(
+                        "[Node {}] Rejected undecryptable/unauthenticated secure envelope claiming Node {}",
+                        self.id, from_node
+                    );
+                    return None;
+                };
+
+                if !Self::claimed_sender_matches(&inner, from_node) {
+                    warn!(
+                        "[Node {}] Secure envelope from Node {} carried a message claiming a different sender - rejected",
+                        self.id, from_node
+                    );
+                    return None;
+                }
+
+                let response = Box::pin(self.process_message(inner, addr, None)).await?;
+                Some(self.seal_for_peer(from_node, &response).await.unwrap_or(response))
+            }
+
+            Message::ClientSecureHandshakeInit { client_username, static_public, ephemeral_public, signature } => {
                 {
-                    let now = Instant::now();
-                    let mut heartbeats = self.last_heartbeat.write().await;
-                    heartbeats.insert(from_node, now);
-
-                    // Cache the load information
-                    let mut load_cache = self.peer_load_cache.write().await;
-                    load_cache.insert(from_node, CachedLoadInfo {
-                        load,
-                        processed_count,
-                        timestamp: now,
-                    });
+                    let mut trusted = self.client_trusted_keys.write().await;
+                    if let Some(pinned) = trusted.get(&client_username) {
+                        if *pinned != static_public {
+                            warn!(
+                                "[Node {}] Rejected secure handshake for client '{}' - static key does not match the one pinned on first use",
+                                self.id, client_username
+                            );
+                            return None;
+                        }
+                    } else {
+                        trusted.insert(client_username.clone(), static_public);
+                    }
+                }
 
-                    // If this node was marked as failed, remove it from failed set
-                    let mut failed = self.failed_nodes.write().await;
-                    if failed.remove(&from_node) {
-                        info!("[Node {}] Node {} recovered (heartbeat ack)", self.id, from_node);
+                let (state, offer) = HandshakeState::begin(&self.identity);
+                let peer_message = secure_session::HandshakeMessage { ephemeral_public, signature };
+                match state.finish(Role::Responder, &static_public, &peer_message) {
+                    Ok(session) => {
+                        let (reader, writer) = session.split();
+                        self.client_secure_sessions.write().await.insert(client_username.clone(), PeerSession { reader, writer });
+                        info!("[Node {}] Established secure channel with client '{}'", self.id, client_username);
+                        Some(Message::ClientSecureHandshakeAck {
+                            ephemeral_public: offer.ephemeral_public,
+                            signature: offer.signature,
+                        })
                     }
+                    Err(e) => {
+                        warn!("[Node {}] Secure handshake with client '{}' failed: {}", self.id, client_username, e);
+                        None
+                    }
+                }
+            }
+
+            Message::ClientSecureHandshakeAck { .. } => {
+                // Consumed directly as the reply to the client's own
+                // handshake send, same as `SecureHandshakeAck` above.
+                None
+            }
+
+            Message::ClientSecureEnvelope { client_username, sealed } => {
+                let Some(inner) = self.open_from_client(&client_username, &sealed).await else {
+                    warn!(
+                        "[Node {}] Rejected undecryptable/unauthenticated secure envelope claiming to be client '{}'",
+                        self.id, client_username
+                    );
+                    return None;
+                };
+
+                if !Self::claimed_client_sender_matches(&inner, &client_username) {
+                    warn!(
+                        "[Node {}] Secure envelope from client '{}' carried a message claiming a different sender - rejected",
+                        self.id, client_username
+                    );
+                    return None;
+                }
+
+                let response = Box::pin(self.process_message(inner, addr, Some(&client_username))).await?;
+                Some(self.seal_for_client(&client_username, &response).await.unwrap_or(response))
+            }
+
+            Message::KeyRotation { from_node } => {
+                // This arrived decrypted under `from_node`'s current send
+                // key (via the `SecureEnvelope` arm above), so it's safe to
+                // ratchet our matching reader forward now - `from_node` only
+                // rotates its writer after this same marker finished sending.
+                if let Some(session) = self.secure_sessions.write().await.get_mut(&from_node) {
+                    session.reader.rotate();
+                    debug!("[Node {}] Rotated recv key for Node {}", self.id, from_node);
+                }
+                None
+            }
+
+            Message::Leave { from_node, successor_hint } => {
+                info!(
+                    "[Node {}] Node {} is leaving the cluster cleanly (successor hint: {:?})",
+                    self.id, from_node, successor_hint
+                );
+
+                // Same bookkeeping `failure_detector_task` does for a node it
+                // gives up on, just without the "FAILURE DETECTED" framing -
+                // this departure was announced, not discovered.
+                self.failed_nodes.write().await.insert(from_node);
+                self.gossip.write().await.mark_failed_locally(from_node);
+                self.membership.write().await.remove(from_node);
+
+                let manager = self.election_manager.lock().await;
+                let was_coordinator = manager.get_coordinator() == Some(from_node);
+                drop(manager);
+
+                if was_coordinator {
+                    info!("[Node {}] Departing node {} was coordinator, triggering election now instead of waiting for the gossip timeout", self.id, from_node);
+                    let self_clone = self.clone();
+                    tokio::spawn(async move {
+                        self_clone.trigger_election().await;
+                    });
+                }
+
+                Some(Message::LeaveAck { from_node: self.id })
+            }
+
+            Message::LeaveAck { .. } => None,
+
+            Message::MembershipDigest { from_node, digest } => {
+                let my_digest = self.membership.read().await.digest();
+
+                if my_digest != digest {
+                    debug!(
+                        "[Node {}] Membership digest mismatch with Node {} ({:x} != {:x}), exchanging",
+                        self.id, from_node, my_digest, digest
+                    );
+                    let peers = self.membership.read().await.active_list();
+                    Some(Message::MembershipExchange {
+                        from_node: self.id,
+                        peers,
+                    })
+                } else {
+                    None
+                }
+            }
+
+            Message::MembershipExchange { from_node, peers } => {
+                let changed = self.membership.write().await.merge(&peers);
+                if changed {
+                    info!(
+                        "[Node {}] Membership updated from Node {}'s gossip exchange",
+                        self.id, from_node
+                    );
+                }
+                None
+            }
+
+            Message::Join { node_id: joined_id, addrs } => {
+                let changed = self.membership.write().await.join(joined_id, &addrs);
+                if changed {
+                    info!("[Node {}] Node {} announced itself via Join at {:?}", self.id, joined_id, addrs);
+                    // Already-failed nodes can rejoin under the same ID.
+                    self.failed_nodes.write().await.remove(&joined_id);
+                }
+                None
+            }
+
+            Message::RapidAlert { observer, subject, up } => {
+                let status = if up { EdgeStatus::Up } else { EdgeStatus::Down };
+                let outcome = self.rapid.lock().await.report_alert(observer, subject, status);
+                if outcome == ReportOutcome::Stable {
+                    self.propose_pending_rapid_cut().await;
+                }
+                None
+            }
+
+            Message::RapidCutProposal { proposer: _, to_add: _, to_remove: _ } => {
+                // Acking is deliberately unconditional: the proposal was
+                // only sent because the proposer's own `AlertAggregator`
+                // already crossed the `H` stable threshold, and `commit` is
+                // idempotent, so there's nothing this node can usefully
+                // veto here.
+                Some(Message::Ok { from_node: self.id })
+            }
+
+            Message::PairingRequest { nonce } => {
+                let proof = PairingProof::new(&self.identity, vec![self.address.clone()], nonce);
+                Some(Message::PairingResponse { proof })
+            }
+
+            Message::PairingResponse { proof } => {
+                if proof.verify() {
+                    let claimed_id = identity::node_id_from_public_key(&proof.node_information.public_key);
+                    self.verified_peers.write().await.insert(claimed_id);
+                    self.known_static_keys
+                        .write()
+                        .await
+                        .insert(claimed_id, proof.node_information.public_key);
+                    info!(
+                        "[Node {}] Completed pairing handshake with Node {} (software {})",
+                        self.id, claimed_id, proof.node_information.software_version
+                    );
+                } else {
+                    warn!("[Node {}] Rejected pairing response with invalid signature", self.id);
                 }
-                None // No response needed
+                None
             }
 
             Message::SendImage {
@@ -738,35 +1997,194 @@ impl CloudNode {
                 encrypted_image,
                 max_views,
                 image_id,
+                codec,
+                checksum: expected_checksum,
             } => {
-                let mut stored = self.stored_images.write().await;
+                if !upload_session::is_valid_path_segment(&image_id) {
+                    warn!("[Node {}] Rejected SendImage with unsafe image_id '{}'", self.id, image_id);
+                    return Some(Message::SendImageResponse {
+                        success: false,
+                        image_id,
+                        error: Some("Invalid image_id".to_string()),
+                        delivery: vec![],
+                    });
+                }
+
+                let encrypted_image = match codec.decompress(&encrypted_image) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("[Node {}] Failed to decompress image {} ({:?}): {}", self.id, image_id, codec, e);
+                        return Some(Message::SendImageResponse {
+                            success: false,
+                            image_id,
+                            error: Some(format!("Decompression failed: {}", e)),
+                            delivery: vec![],
+                        });
+                    }
+                };
+
+                let checksum = messages::checksum(&encrypted_image);
+                if let Some(expected) = expected_checksum {
+                    if checksum != expected {
+                        error!(
+                            "[Node {}] Checksum mismatch for image {} from {} - rejecting upload",
+                            self.id, image_id, from_username
+                        );
+                        return Some(Message::SendImageResponse {
+                            success: false,
+                            image_id,
+                            error: Some("Integrity check failed: checksum mismatch".to_string()),
+                            delivery: vec![],
+                        });
+                    }
+                }
+
+                let known_users = self.user_directory.lock().await.usernames();
+                let online_users = self.active_sessions.read().await;
+
+                let mut delivery = Vec::with_capacity(to_usernames.len());
+                let mut deliverable = Vec::new();
+                let mut online_recipients = std::collections::HashSet::new();
+                for username in &to_usernames {
+                    if !known_users.contains(username) {
+                        delivery.push((username.clone(), DeliveryState::Failed("Unknown user".to_string())));
+                    } else if online_users.contains_key(username) {
+                        delivery.push((username.clone(), DeliveryState::Delivered));
+                        deliverable.push(username.clone());
+                        online_recipients.insert(username.clone());
+                    } else {
+                        delivery.push((username.clone(), DeliveryState::Pending));
+                        deliverable.push(username.clone());
+                    }
+                }
+                drop(online_users);
+
                 let timestamp = chrono::Utc::now().timestamp();
+                // Spill/compress once up front (identical for every
+                // recipient) rather than inside the write-locked loop below.
+                let blob = self.image_store.put(&image_id, encrypted_image.clone());
+                {
+                    let mut stored = self.stored_images.write().await;
+                    for username in &deliverable {
+                        let image = StoredImage {
+                            image_id: image_id.clone(),
+                            from_username: from_username.clone(),
+                            blob: blob.clone(),
+                            remaining_views: max_views,
+                            max_views,
+                            timestamp,
+                            checksum,
+                            notified: online_recipients.contains(username),
+                        };
 
-                for username in to_usernames {
-                    let image = StoredImage {
-                        image_id: image_id.clone(),
-                        from_username: from_username.clone(),
-                        encrypted_data: encrypted_image.clone(),
-                        remaining_views: max_views,
-                        max_views,
-                        timestamp,
-                    };
+                        stored.entry(username.clone()).or_insert_with(Vec::new).push(image);
+                    }
+                }
+
+                info!(
+                    "[Node {}] Stored image {} from {} for {} recipient(s) ({} unknown)",
+                    self.id, image_id, from_username, deliverable.len(), to_usernames.len() - deliverable.len()
+                );
+
+                // Push a copy to the other replica(s) consistent hashing
+                // picks for this image and wait for their acks, rather than
+                // only relying on the next anti-entropy round to catch them
+                // up (see replication.rs). The local copy already stored
+                // above counts as the first ack towards the write quorum.
+                {
+                    let replicas: Vec<NodeId> = self
+                        .replica_set(&image_id)
+                        .await
+                        .into_iter()
+                        .filter(|id| *id != self.id)
+                        .collect();
+                    let write_quorum = replication::write_quorum(replication::REPLICATION_FACTOR);
+
+                    for username in &deliverable {
+                        let replicated = ReplicatedImage {
+                            username: username.clone(),
+                            image_id: image_id.clone(),
+                            from_username: from_username.clone(),
+                            encrypted_data: encrypted_image.clone(),
+                            remaining_views: max_views,
+                            max_views,
+                            timestamp,
+                            checksum,
+                            notified: online_recipients.contains(username),
+                        };
 
-                    stored.entry(username.clone()).or_insert_with(Vec::new).push(image);
+                        let mut acks = 1; // the copy already stored locally above
+                        for &replica_id in &replicas {
+                            let message = Message::ReplicationPush { image: replicated.clone() };
+                            match self.send_secure_message_to_node(replica_id, message).await {
+                                Ok(Some(Message::ReplicationPushResponse { accepted: true, .. })) => acks += 1,
+                                Ok(Some(Message::ReplicationPushResponse { accepted: false, error, .. })) => warn!(
+                                    "[Node {}] Replica push of {} to Node {} was rejected: {}",
+                                    self.id, image_id, replica_id, error.unwrap_or_default()
+                                ),
+                                Ok(_) => warn!(
+                                    "[Node {}] Replica push of {} to Node {} wasn't acked",
+                                    self.id, image_id, replica_id
+                                ),
+                                Err(e) => warn!(
+                                    "[Node {}] Failed to push replica of {} to Node {}: {}",
+                                    self.id, image_id, replica_id, e
+                                ),
+                            }
+                        }
+
+                        if acks < write_quorum {
+                            warn!(
+                                "[Node {}] Image {} for '{}' did not reach write quorum ({}/{} copies acked) - durability relies on anti-entropy catching it up",
+                                self.id, image_id, username, acks, write_quorum
+                            );
+                        }
+                    }
                 }
 
-                info!("[Node {}] Stored image {} from {}", self.id, image_id, from_username);
+                for username in &deliverable {
+                    self.push_notification(
+                        username,
+                        Message::ImageNotification {
+                            to_username: username.clone(),
+                            from_username: from_username.clone(),
+                            image_id: image_id.clone(),
+                            remaining_views: max_views,
+                        },
+                    )
+                    .await;
+                }
+
+                let delivered_to_anyone = delivery.iter().any(|(_, state)| !matches!(state, DeliveryState::Failed(_)));
+                let success = delivered_to_anyone && self.shard_finalized(&image_id).await;
+                if delivered_to_anyone && !success {
+                    warn!(
+                        "[Node {}] Image {} delivered but this node's shard doesn't have a finalized copy yet",
+                        self.id, image_id
+                    );
+                }
 
                 Some(Message::SendImageResponse {
-                    success: true,
+                    success,
                     image_id,
                     error: None,
+                    delivery,
                 })
             }
 
-            Message::QueryReceivedImages { username } => {
+            Message::QueryReceivedImages { username, offset, limit } => {
+                if self.user_directory.lock().await.is_protected(&username)
+                    && !self.authenticated_sessions.read().await.contains(&username)
+                {
+                    return Some(Message::QueryReceivedImagesResponse {
+                        images: Vec::new(),
+                        has_more: false,
+                        error: Some("Password-protected account; complete AuthChallenge/AuthProve first".to_string()),
+                    });
+                }
+
                 let stored = self.stored_images.read().await;
-                let images = stored
+                let mut all: Vec<ReceivedImageInfo> = stored
                     .get(&username)
                     .map(|imgs| {
                         imgs.iter()
@@ -781,7 +2199,14 @@ impl CloudNode {
                     })
                     .unwrap_or_default();
 
-                Some(Message::QueryReceivedImagesResponse { images })
+                // Newest first, and a stable order so paging in doesn't shift
+                // already-fetched pages around.
+                all.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| a.image_id.cmp(&b.image_id)));
+
+                let has_more = offset + limit < all.len();
+                let images = all.into_iter().skip(offset).take(limit).collect();
+
+                Some(Message::QueryReceivedImagesResponse { images, has_more, error: None })
             }
 
             Message::CheckUsernameAvailable { username } => {
@@ -793,29 +2218,148 @@ impl CloudNode {
                 })
             }
 
+            Message::QueryDirectory => {
+                let usernames = self.user_directory.lock().await.usernames();
+                let sessions = self.active_sessions.read().await;
+                let entries = usernames
+                    .into_iter()
+                    .map(|username| {
+                        let online = sessions.contains_key(&username);
+                        (username, online)
+                    })
+                    .collect();
+
+                Some(Message::QueryDirectoryResponse { entries })
+            }
+
+            Message::SubscribeNotifications { username } => {
+                self.notification_subscribers.write().await.insert(username.clone(), addr);
+                info!("[Node {}] '{}' subscribed to push notifications at {}", self.id, username, addr);
+                Some(Message::SubscribeNotificationsResponse { success: true })
+            }
+
+            Message::ImageNotification { .. } => {
+                // Only ever sent node -> client; a node should never receive one.
+                None
+            }
+
             Message::ViewImage { username, image_id } => {
-                let mut stored = self.stored_images.write().await;
+                if self.user_directory.lock().await.is_protected(&username)
+                    && !self.authenticated_sessions.read().await.contains(&username)
+                {
+                    return Some(Message::ViewImageResponse {
+                        success: false,
+                        image_data: None,
+                        remaining_views: None,
+                        error: Some("Password-protected account; complete AuthChallenge/AuthProve first".to_string()),
+                    });
+                }
 
-                if let Some(user_images) = stored.get_mut(&username) {
-                    if let Some(img) = user_images.iter_mut().find(|i| i.image_id == image_id) {
-                        if img.remaining_views > 0 {
-                            img.remaining_views -= 1;
-                            info!(
-                                "[Node {}] User {} viewed image {} (remaining: {})",
-                                self.id, username, image_id, img.remaining_views
-                            );
-                            Some(Message::ViewImageResponse {
-                                success: true,
-                                image_data: Some(img.encrypted_data.clone()),
-                                remaining_views: Some(img.remaining_views),
-                                error: None,
-                            })
+                // Serialize the remaining_views decrement through the
+                // primary replica (the first in replica_nodes' ring order)
+                // instead of racing whichever node each client happens to
+                // reach, so concurrent views from different entry nodes
+                // can't double-spend the quota.
+                if let Some(&primary_id) = self.replica_set(&image_id).await.first() {
+                    if primary_id != self.id {
+                        if let Some(response) = self.try_forward_view_image(primary_id, &username, &image_id).await {
+                            return Some(response);
+                        }
+
+                        warn!(
+                            "[Node {}] Primary Node {} for image {} unreachable - trying shard-aware candidates",
+                            self.id, primary_id, image_id
+                        );
+                        for candidate_id in self.shard_aware_candidates(&image_id).await {
+                            if candidate_id == primary_id || candidate_id == self.id {
+                                continue;
+                            }
+                            if let Some(response) = self.try_forward_view_image(candidate_id, &username, &image_id).await {
+                                info!(
+                                    "[Node {}] Served image {} via shard-aware fallback to Node {}",
+                                    self.id, image_id, candidate_id
+                                );
+                                return Some(response);
+                            }
+                        }
+                        warn!(
+                            "[Node {}] No shard-aware candidate held image {} - falling back to local copy",
+                            self.id, image_id
+                        );
+                    }
+                }
+
+                // Only reached as the primary (or as a fallback when neither
+                // the primary nor any shard-aware candidate above could be
+                // reached).
+                let mut propagate: Option<ReplicatedImage> = None;
+                let response = {
+                    let mut stored = self.stored_images.write().await;
+
+                    if let Some(user_images) = stored.get_mut(&username) {
+                        if let Some(img) = user_images.iter_mut().find(|i| i.image_id == image_id) {
+                            match self.image_store.get(&img.blob) {
+                                Err(e) => {
+                                    error!(
+                                        "[Node {}] Failed to read stored image {} for {}: {}",
+                                        self.id, image_id, username, e
+                                    );
+                                    Some(Message::ViewImageResponse {
+                                        success: false,
+                                        image_data: None,
+                                        remaining_views: Some(img.remaining_views),
+                                        error: Some("Stored image is unavailable".to_string()),
+                                    })
+                                }
+                                Ok(data) if messages::checksum(&data) != img.checksum => {
+                                    error!(
+                                        "[Node {}] Checksum mismatch serving image {} for {} - refusing to return corrupted data",
+                                        self.id, image_id, username
+                                    );
+                                    Some(Message::ViewImageResponse {
+                                        success: false,
+                                        image_data: None,
+                                        remaining_views: Some(img.remaining_views),
+                                        error: Some("Integrity check failed: stored image is corrupted".to_string()),
+                                    })
+                                }
+                                Ok(data) if img.remaining_views > 0 => {
+                                    img.remaining_views -= 1;
+                                    info!(
+                                        "[Node {}] User {} viewed image {} (remaining: {})",
+                                        self.id, username, image_id, img.remaining_views
+                                    );
+                                    propagate = Some(ReplicatedImage {
+                                        username: username.clone(),
+                                        image_id: image_id.clone(),
+                                        from_username: img.from_username.clone(),
+                                        encrypted_data: data.clone(),
+                                        remaining_views: img.remaining_views,
+                                        max_views: img.max_views,
+                                        timestamp: img.timestamp,
+                                        checksum: img.checksum,
+                                        notified: img.notified,
+                                    });
+                                    Some(Message::ViewImageResponse {
+                                        success: true,
+                                        image_data: Some(data),
+                                        remaining_views: Some(img.remaining_views),
+                                        error: None,
+                                    })
+                                }
+                                Ok(_) => Some(Message::ViewImageResponse {
+                                    success: false,
+                                    image_data: None,
+                                    remaining_views: Some(0),
+                                    error: Some("No views remaining".to_string()),
+                                }),
+                            }
                         } else {
                             Some(Message::ViewImageResponse {
                                 success: false,
                                 image_data: None,
-                                remaining_views: Some(0),
-                                error: Some("No views remaining".to_string()),
+                                remaining_views: None,
+                                error: Some("Image not found".to_string()),
                             })
                         }
                     } else {
@@ -823,17 +2367,31 @@ impl CloudNode {
                             success: false,
                             image_data: None,
                             remaining_views: None,
-                            error: Some("Image not found".to_string()),
+                            error: Some("No images for this user".to_string()),
                         })
                     }
-                } else {
-                    Some(Message::ViewImageResponse {
-                        success: false,
-                        image_data: None,
-                        remaining_views: None,
-                        error: Some("No images for this user".to_string()),
-                    })
+                };
+
+                // As the primary, propagate the new remaining_views to the
+                // other replicas right away instead of waiting for the
+                // next anti-entropy round to catch them up.
+                if let Some(replicated) = propagate {
+                    let self_clone = self.clone();
+                    tokio::spawn(async move {
+                        let replicas: Vec<NodeId> = self_clone
+                            .replica_set(&replicated.image_id)
+                            .await
+                            .into_iter()
+                            .filter(|id| *id != self_clone.id)
+                            .collect();
+                        for replica_id in replicas {
+                            let message = Message::ReplicationPush { image: replicated.clone() };
+                            let _ = self_clone.send_secure_message_to_node(replica_id, message).await;
+                        }
+                    });
                 }
+
+                response
             }
 
             _ => None,
@@ -847,19 +2405,42 @@ impl CloudNode {
         image_data: Vec<u8>,
         usernames: Vec<String>,
         quota: u32,
+        codec: CompressionCodec,
     ) -> Message {
         let start_time = Instant::now();
 
         // Get current active count for logging (active_requests already incremented by caller)
-        let active_count = *self.active_requests.read().await;
+        let active_count = self.active_requests.load(Ordering::Relaxed);
 
         info!(
             "[Node {}] START encrypting request {} (current active: {})",
             self.id, request_id, active_count
         );
 
+        let image_data = match codec.decompress(&image_data) {
+            Ok(data) => data,
+            Err(e) => {
+                error!(
+                    "[Node {}] Failed to decompress request {} ({:?}): {}",
+                    self.id, request_id, codec, e
+                );
+                return Message::EncryptionResponse {
+                    request_id,
+                    encrypted_image: vec![],
+                    success: false,
+                    error: Some(format!("Decompression failed: {}", e)),
+                };
+            }
+        };
+
         // Perform encryption
-        let result = match encryption::encrypt_image(image_data, usernames, quota).await {
+        let result = match encryption::encrypt_image(
+            image_data,
+            usernames,
+            quota,
+            &self.identity,
+            encryption::DEFAULT_BIT_PLANES,
+        ).await {
             Ok(encrypted_image) => {
                 let mut processed = self.processed_requests.write().await;
                 *processed += 1;
@@ -931,13 +2512,296 @@ impl CloudNode {
                     self.id, request_id, e
                 );
 
-                Message::DecryptionResponse {
-                    request_id,
-                    decrypted_image: vec![],
-                    success: false,
-                    error: Some(e),
-                }
+                Message::DecryptionResponse {
+                    request_id,
+                    decrypted_image: vec![],
+                    success: false,
+                    error: Some(e),
+                }
+            }
+        }
+    }
+
+    /// The full replica set for `image_id` (including self, if self
+    /// qualifies), per `replication::replica_nodes` over every currently
+    /// live node. The first entry is the primary - the replica through
+    /// which `ViewImage` serializes its `remaining_views` decrement.
+    async fn replica_set(&self, image_id: &str) -> Vec<NodeId> {
+        let live_nodes: Vec<NodeId> = self
+            .membership
+            .read()
+            .await
+            .addresses()
+            .keys()
+            .copied()
+            .chain(std::iter::once(self.id))
+            .collect();
+        let failed = self.failed_nodes.read().await.clone();
+        let live_nodes: Vec<NodeId> = live_nodes.into_iter().filter(|id| !failed.contains(id)).collect();
+        replication::replica_nodes(image_id, &live_nodes, replication::REPLICATION_FACTOR)
+    }
+
+    /// Forward a `ViewImage` to `peer_id` and apply read repair on success
+    /// (shrink our own cached `remaining_views` down to the authoritative
+    /// count the peer reports), returning `None` on any failure so the
+    /// caller can try the next candidate. Shared by the primary-replica
+    /// forward and the shard-aware fallback in the `ViewImage` handler.
+    async fn try_forward_view_image(&self, peer_id: NodeId, username: &str, image_id: &str) -> Option<Message> {
+        let forwarded = Message::ViewImage { username: username.to_string(), image_id: image_id.to_string() };
+        match self.send_secure_message_to_node(peer_id, forwarded).await {
+            Ok(Some(response @ Message::ViewImageResponse { .. })) => {
+                if let Message::ViewImageResponse { remaining_views: Some(rv), .. } = &response {
+                    if let Some(user_images) = self.stored_images.write().await.get_mut(username) {
+                        if let Some(img) = user_images.iter_mut().find(|i| i.image_id == image_id) {
+                            img.remaining_views = img.remaining_views.min(*rv);
+                        }
+                    }
+                }
+                Some(response)
+            }
+            _ => None,
+        }
+    }
+
+    /// Snapshot every locally stored image as `(username, image_id,
+    /// leaf_hash)` triples - the input to `replication::bucket_hashes`.
+    async fn replication_entries(&self) -> Vec<(String, String, u64)> {
+        self.stored_images
+            .read()
+            .await
+            .iter()
+            .flat_map(|(username, images)| {
+                images.iter().map(move |image| {
+                    (
+                        username.clone(),
+                        image.image_id.clone(),
+                        replication::leaf_hash(&image.image_id, image.remaining_views, image.timestamp),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Merge one replicated record into `stored_images`: newest `timestamp`
+    /// wins for the image content, and `remaining_views` is reconciled by
+    /// taking the minimum of the two sides, so a sync can never hand back
+    /// views that were already spent against either copy. Rejects with
+    /// `IntegrityError` rather than applying anything if the digest doesn't
+    /// match, so a caller can tell a corrupted push apart from a successful one.
+    async fn apply_replicated_image(&self, incoming: ReplicatedImage) -> Result<(), IntegrityError> {
+        if !upload_session::is_valid_path_segment(&incoming.image_id) {
+            warn!(
+                "[Node {}] Rejected replicated image with unsafe image_id '{}' for {}",
+                self.id, incoming.image_id, incoming.username
+            );
+            return Err(IntegrityError::InvalidImageId);
+        }
+
+        if messages::checksum(&incoming.encrypted_data) != incoming.checksum {
+            warn!(
+                "[Node {}] Rejected replicated image {} for {} - checksum mismatch",
+                self.id, incoming.image_id, incoming.username
+            );
+            return Err(IntegrityError::ChecksumMismatch);
+        }
+
+        let mut stored = self.stored_images.write().await;
+        let user_images = stored.entry(incoming.username.clone()).or_insert_with(Vec::new);
+
+        if let Some(existing) = user_images.iter_mut().find(|img| img.image_id == incoming.image_id) {
+            if incoming.timestamp > existing.timestamp {
+                let old_blob = existing.blob.clone();
+                existing.from_username = incoming.from_username;
+                existing.blob = self.image_store.put(&incoming.image_id, incoming.encrypted_data);
+                existing.max_views = incoming.max_views;
+                existing.timestamp = incoming.timestamp;
+                existing.checksum = incoming.checksum;
+                self.image_store.remove(&old_blob);
+            }
+            existing.remaining_views = existing.remaining_views.min(incoming.remaining_views);
+            // Once we've told the recipient about an image, a stale or
+            // duplicate replication push for it (e.g. a retried
+            // ReplicationPush, or a late anti-entropy sync) must not flip it
+            // back to "pending" and re-surface it on the next registration.
+            existing.notified = existing.notified || incoming.notified;
+        } else {
+            let blob = self.image_store.put(&incoming.image_id, incoming.encrypted_data);
+            user_images.push(StoredImage {
+                image_id: incoming.image_id,
+                from_username: incoming.from_username,
+                blob,
+                remaining_views: incoming.remaining_views,
+                max_views: incoming.max_views,
+                timestamp: incoming.timestamp,
+                checksum: incoming.checksum,
+                notified: incoming.notified,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether `message`'s own claimed sender field agrees with `from_node`,
+    /// the node that authenticated the `SecureEnvelope` it arrived sealed
+    /// in - so an envelope genuinely from Node A can't smuggle a message
+    /// claiming to be from Node B. Only meaningful for the node-to-node
+    /// message types that carry a sender field at all; anything else is
+    /// passed through unchecked.
+    fn claimed_sender_matches(message: &Message, from_node: NodeId) -> bool {
+        match message {
+            Message::Election { from_node: claimed }
+            | Message::GossipPull { from_node: claimed, .. }
+            | Message::GossipPush { from_node: claimed, .. }
+            | Message::ReplicationSync { from_node: claimed, .. }
+            | Message::ReplicationSyncResponse { from_node: claimed, .. }
+            | Message::KeyRotation { from_node: claimed }
+            | Message::Leave { from_node: claimed, .. }
+            | Message::LeaveAck { from_node: claimed } => *claimed == from_node,
+            Message::Coordinator { node_id, .. } => *node_id == from_node,
+            Message::Join { node_id, .. } => *node_id == from_node,
+            Message::RapidAlert { observer, .. } => *observer == from_node,
+            Message::RapidCutProposal { proposer, .. } => *proposer == from_node,
+            _ => true,
+        }
+    }
+
+    /// Decrypt and deserialize a `SecureEnvelope` payload claiming to be
+    /// from `from_node`, using the cached session for that peer. Returns
+    /// `None` if we hold no session with them, or authentication fails
+    /// (wrong key, tampered ciphertext, a replayed/too-stale nonce counter -
+    /// see `secure_session::SessionReader::open`).
+    async fn open_from_peer(&self, from_node: NodeId, sealed: &[u8]) -> Option<Message> {
+        let mut sessions = self.secure_sessions.write().await;
+        let session = sessions.get_mut(&from_node)?;
+        let plaintext = session.reader.open(sealed).ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+
+    /// Seal `message` for `peer_id` into a `SecureEnvelope`, using the
+    /// cached session. Returns `None` if no session with that peer is
+    /// established yet.
+    async fn seal_for_peer(&self, peer_id: NodeId, message: &Message) -> Option<Message> {
+        let mut sessions = self.secure_sessions.write().await;
+        let session = sessions.get_mut(&peer_id)?;
+        let plaintext = serde_json::to_vec(message).ok()?;
+        let sealed = session.writer.seal(&plaintext).ok()?;
+        Some(Message::SecureEnvelope { from_node: self.id, sealed })
+    }
+
+    /// Whether `message`'s own claimed username field agrees with
+    /// `client_username`, the client that authenticated the
+    /// `ClientSecureEnvelope` it arrived sealed in - same purpose as
+    /// `claimed_sender_matches`, just for client-carried credentials.
+    fn claimed_client_sender_matches(message: &Message, client_username: &str) -> bool {
+        match message {
+            Message::SessionRegister { username, .. } => username == client_username,
+            Message::EncryptionRequest { client_username: claimed, .. } => claimed == client_username,
+            Message::SendImage { from_username, .. } => from_username == client_username,
+            Message::ViewImage { username, .. } => username == client_username,
+            Message::QueryReceivedImages { username, .. } => username == client_username,
+            Message::AuthChallenge { username } => username == client_username,
+            Message::AuthProve { username, .. } => username == client_username,
+            Message::ChangePassword { username, .. } => username == client_username,
+            _ => true,
+        }
+    }
+
+    /// Decrypt and deserialize a `ClientSecureEnvelope` payload claiming to
+    /// be from `client_username`, using the cached session for that
+    /// client. `None` if we hold no session with them yet, or
+    /// authentication fails (see `open_from_peer`).
+    async fn open_from_client(&self, client_username: &str, sealed: &[u8]) -> Option<Message> {
+        let mut sessions = self.client_secure_sessions.write().await;
+        let session = sessions.get_mut(client_username)?;
+        let plaintext = session.reader.open(sealed).ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+
+    /// Seal `message` for `client_username` into a `ClientSecureEnvelope`,
+    /// using the cached session. `None` if no session with that client is
+    /// established yet.
+    async fn seal_for_client(&self, client_username: &str, message: &Message) -> Option<Message> {
+        let mut sessions = self.client_secure_sessions.write().await;
+        let session = sessions.get_mut(client_username)?;
+        let plaintext = serde_json::to_vec(message).ok()?;
+        let sealed = session.writer.seal(&plaintext).ok()?;
+        Some(Message::ClientSecureEnvelope { client_username: client_username.to_string(), sealed })
+    }
+
+    /// Perform the static-key handshake (see `secure_session.rs`) with
+    /// `peer_id` and cache the resulting session. Requires we already hold
+    /// `peer_id`'s static public key from a completed `PairingRequest`/
+    /// `PairingResponse` exchange - a peer we've never paired with has no
+    /// trust anchor to verify the handshake's signed ephemeral key against.
+    async fn establish_secure_session(&self, peer_id: NodeId) -> Result<(), String> {
+        let peer_static_key = self
+            .known_static_keys
+            .read()
+            .await
+            .get(&peer_id)
+            .copied()
+            .ok_or_else(|| format!("No known static key for Node {} (not yet paired)", peer_id))?;
+
+        let (state, offer) = HandshakeState::begin(&self.identity);
+        let response = self
+            .send_message_to_node(
+                peer_id,
+                Message::SecureHandshakeInit {
+                    from_node: self.id,
+                    ephemeral_public: offer.ephemeral_public,
+                    signature: offer.signature,
+                },
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let Some(Message::SecureHandshakeAck { from_node, ephemeral_public, signature }) = response else {
+            return Err(format!("Node {} did not complete the secure handshake", peer_id));
+        };
+        if from_node != peer_id {
+            return Err(format!("Handshake ack claimed Node {} instead of Node {}", from_node, peer_id));
+        }
+
+        let peer_message = secure_session::HandshakeMessage { ephemeral_public, signature };
+        let session = state.finish(Role::Initiator, &peer_static_key, &peer_message)?;
+        let (reader, writer) = session.split();
+        self.secure_sessions.write().await.insert(peer_id, PeerSession { reader, writer });
+
+        info!("[Node {}] Established secure channel with Node {}", self.id, peer_id);
+        Ok(())
+    }
+
+    /// Establish a secure session with `peer_id` if one isn't already cached.
+    async fn ensure_secure_session(&self, peer_id: NodeId) -> Result<(), String> {
+        if self.secure_sessions.read().await.contains_key(&peer_id) {
+            return Ok(());
+        }
+        self.establish_secure_session(peer_id).await
+    }
+
+    /// Like `send_message_to_node`, but wraps the outbound message in an
+    /// authenticated-encrypted `SecureEnvelope` when a session with `peer_id`
+    /// can be established, establishing one on demand. Used for the traffic
+    /// classes this hardening targets - election, gossip, and replication
+    /// messages - where a forged plaintext `Coordinator`/`GossipPush`/
+    /// `ReplicationSync` could otherwise hijack leader election or poison
+    /// load/liveness data. Falls back to an unauthenticated send if no
+    /// session can be established (e.g. we haven't paired with this peer
+    /// yet) rather than failing outright, since pairing isn't guaranteed to
+    /// have completed with every peer at every moment.
+    async fn send_secure_message_to_node(&self, peer_id: NodeId, message: Message) -> Result<Option<Message>, String> {
+        if self.ensure_secure_session(peer_id).await.is_err() {
+            return self.send_message_to_node(peer_id, message).await.map_err(|e| e.to_string());
+        }
+
+        let Some(envelope) = self.seal_for_peer(peer_id, &message).await else {
+            return self.send_message_to_node(peer_id, message).await.map_err(|e| e.to_string());
+        };
+
+        match self.send_message_to_node(peer_id, envelope).await.map_err(|e| e.to_string())? {
+            Some(Message::SecureEnvelope { from_node, sealed }) if from_node == peer_id => {
+                Ok(self.open_from_peer(peer_id, &sealed).await)
             }
+            other => Ok(other),
         }
     }
 
@@ -945,10 +2809,11 @@ impl CloudNode {
     /// Uses hybrid scoring: 70% current load + 30% historical work percentage
     /// This ensures fair distribution over time while still being responsive to current load
     ///
-    /// OPTIMIZED: Uses cached load data from heartbeats instead of querying every node
-    /// This dramatically reduces network overhead (from N queries per request to 0)
+    /// Reads from the gossiped `GossipTable` instead of querying every node -
+    /// load/liveness propagates across the cluster via `gossip_task`, so this
+    /// needs zero network round-trips per call.
     async fn find_lowest_load_node(&self) -> NodeId {
-        let my_load = *self.current_load.read().await;
+        let my_load = self.current_load();
         let my_processed = *self.processed_requests.read().await;
 
         debug!("[Node {}] Finding lowest load node (my load: {:.2}, processed: {})",
@@ -961,10 +2826,7 @@ impl CloudNode {
         // Get list of failed nodes to skip them
         let failed = self.failed_nodes.read().await.clone();
 
-        // Get cached load data from heartbeats
-        let load_cache = self.peer_load_cache.read().await;
-        let now = Instant::now();
-        const CACHE_TTL: Duration = Duration::from_secs(5); // Consider cache stale after 5 seconds
+        let gossip = self.gossip.read().await;
 
         for (peer_id, _) in &self.peer_addresses {
             // Skip failed nodes
@@ -973,31 +2835,25 @@ impl CloudNode {
                 continue;
             }
 
-            // Try to use cached data first
-            if let Some(cached) = load_cache.get(peer_id) {
-                let age = now.duration_since(cached.timestamp);
-
-                if age < CACHE_TTL {
-                    // Cache is fresh - use it!
-                    debug!("[Node {}] Using cached load for Node {} (age: {:.1}s, load: {:.2})",
-                           self.id, peer_id, age.as_secs_f64(), cached.load);
-                    node_data.insert(*peer_id, (cached.load, cached.processed_count));
-                } else {
-                    // Cache is stale - log it but still use it as fallback
-                    debug!("[Node {}] Stale cache for Node {} (age: {:.1}s), using anyway",
-                           self.id, peer_id, age.as_secs_f64());
-                    node_data.insert(*peer_id, (cached.load, cached.processed_count));
+            // Use whatever the gossip table has converged on for this peer
+            if let Some(record) = gossip.get(*peer_id) {
+                if record.state == NodeState::Failed {
+                    debug!("[Node {}] Skipping Node {} (marked failed in gossip table)", self.id, peer_id);
+                    continue;
                 }
+                debug!("[Node {}] Using gossiped load for Node {} (load: {:.2})",
+                       self.id, peer_id, record.load);
+                node_data.insert(*peer_id, (record.load, record.processed_count));
             } else {
-                // No cached data - this node might not have sent heartbeat yet
-                // Use conservative estimate (assume moderate load)
-                debug!("[Node {}] No cached data for Node {}, assuming moderate load",
+                // Gossip hasn't reached us about this node yet - use a
+                // conservative estimate (assume moderate load)
+                debug!("[Node {}] No gossip data for Node {}, assuming moderate load",
                        self.id, peer_id);
                 node_data.insert(*peer_id, (my_load, 0)); // Assume similar load to self
             }
         }
 
-        drop(load_cache);
+        drop(gossip);
         drop(failed);
 
         // Calculate total processed requests across all nodes
@@ -1079,278 +2935,878 @@ impl CloudNode {
     }
 
     /// Send message to another node (single attempt)
+    /// Fire-and-forget push to a subscribed client, e.g. a new-image
+    /// notification. Uses a throwaway socket since we don't need (or wait
+    /// for) a reply; a client that isn't subscribed simply misses the push
+    /// and falls back to its next poll.
+    async fn push_notification(&self, to_username: &str, notification: Message) {
+        let Some(addr) = self.notification_subscribers.read().await.get(to_username).copied() else {
+            return;
+        };
+
+        let bytes = match serde_json::to_vec(&notification) {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("[Node {}] Failed to serialize notification for {}: {}", self.id, to_username, e);
+                return;
+            }
+        };
+
+        match UdpSocket::bind("0.0.0.0:0").await {
+            Ok(socket) => {
+                if let Err(e) = socket.send_to(&bytes, addr).await {
+                    warn!("[Node {}] Failed to push notification to {} ({}): {}", self.id, to_username, addr, e);
+                } else {
+                    debug!("[Node {}] Pushed notification to {} at {}", self.id, to_username, addr);
+                }
+            }
+            Err(e) => warn!("[Node {}] Failed to create notification socket: {}", self.id, e),
+        }
+    }
+
     async fn send_message_to_node_once(&self, node_id: NodeId, message: Message) -> Result<Option<Message>, Box<dyn std::error::Error>> {
-        if let Some(address_str) = self.peer_addresses.get(&node_id) {
-            // Parse the address string to SocketAddr
-            let address: SocketAddr = address_str.parse()
-                .map_err(|e| format!("Invalid address '{}': {}", address_str, e))?;
-
-            // Create a temporary UDP socket bound to a specific port (node's port + 1000)
-            // This avoids using random ephemeral ports that might be blocked
-            let bind_addr = format!("0.0.0.0:{}", 9000 + self.id);
-            let socket = match UdpSocket::bind(&bind_addr).await {
-                Ok(s) => s,
-                Err(_) => {
-                    // Fallback to any available port if specific port fails
-                    UdpSocket::bind("0.0.0.0:0").await?
+        // Prefer the live, gossiped membership view so nodes learned after
+        // startup (via mDNS discovery, Join, or membership exchange) are
+        // reachable too, falling back to the bootstrap list for addresses
+        // gossip hasn't touched yet. Alt addresses (multi-homed peers, or a
+        // peer that changed address) ride along as further candidates.
+        let mut candidates: Vec<String> = {
+            let membership = self.membership.read().await;
+            match membership.address(node_id) {
+                Some(primary) => {
+                    let mut addrs = vec![primary];
+                    addrs.extend(membership.alt_addresses(node_id));
+                    addrs
                 }
-            };
+                None => Vec::new(),
+            }
+        };
+        if candidates.is_empty() {
+            candidates.extend(self.peer_addresses.get(&node_id).cloned());
+        }
+        if candidates.is_empty() {
+            return Err(format!("Unknown node ID: {}", node_id).into());
+        }
 
-            let message_bytes = serde_json::to_vec(&message)?;
+        // Try the primary address first; only fall back to an alternate if
+        // the send/recv round trip against it hard-errors (an unreachable-
+        // but-not-yet-timed-out peer still returns `Ok(None)`, which is left
+        // alone so the existing retry/backoff in `send_message_to_node`
+        // keeps working the way it always has).
+        let mut last_err = None;
+        for (i, address_str) in candidates.iter().enumerate() {
+            match self.send_message_to_address_once(node_id, &message, address_str).await {
+                Ok(response) => {
+                    self.membership.write().await.touch(node_id);
+                    return Ok(response);
+                }
+                Err(e) => {
+                    if i + 1 < candidates.len() {
+                        debug!(
+                            "[Node {}] Send to Node {} at {} failed ({}), trying alt address",
+                            self.id, node_id, address_str, e
+                        );
+                    }
+                    last_err = Some(e);
+                }
+            }
+        }
 
-            // Determine if message needs chunking
-            let needs_chunking = matches!(message,
-                Message::EncryptionRequest { .. } |
-                Message::EncryptionResponse { .. } |
-                Message::DecryptionRequest { .. } |
-                Message::DecryptionResponse { .. }
-            );
+        Err(last_err.expect("candidates is non-empty, so the loop ran at least once"))
+    }
 
-            // Use appropriate timeouts based on message type
-            let timeout_duration = match message {
-                Message::EncryptionRequest { .. } | Message::DecryptionRequest { .. } => Duration::from_secs(30),
-                Message::LoadQuery { .. } => Duration::from_secs(3), // Increased from 500ms - nodes may be busy
-                Message::Election { .. } | Message::Coordinator { .. } => Duration::from_secs(2), // Critical messages
-                _ => Duration::from_secs(1), // Default 1 second for other messages
-            };
+    /// Single send/receive attempt against one concrete address for
+    /// `node_id`. Split out of `send_message_to_node_once` so it can be
+    /// retried against each of a peer's alternate addresses in turn.
+    async fn send_message_to_address_once(
+        &self,
+        node_id: NodeId,
+        message: &Message,
+        address_str: &str,
+    ) -> Result<Option<Message>, Box<dyn std::error::Error>> {
+        // Parse the address string to SocketAddr
+        let address: SocketAddr = address_str.parse()
+            .map_err(|e| format!("Invalid address '{}': {}", address_str, e))?;
+
+        // Create a temporary UDP socket bound to a specific port (9000 + this node's id)
+        // This avoids using random ephemeral ports that might be blocked
+        let bind_addr = format!("0.0.0.0:{}", 9000 + self.id);
+        let socket = match UdpSocket::bind(&bind_addr).await {
+            Ok(s) => s,
+            Err(_) => {
+                // Fallback to any available port if specific port fails
+                UdpSocket::bind("0.0.0.0:0").await?
+            }
+        };
 
-            if needs_chunking && message_bytes.len() > 45000 {
-                // Use chunking for large messages
-                let chunks = ChunkedMessage::fragment(message_bytes);
+        let message_bytes = serde_json::to_vec(message)?;
 
-                // Send all chunks
-                for (i, chunk) in chunks.iter().enumerate() {
-                    let chunk_bytes = serde_json::to_vec(&chunk)?;
-                    socket.send_to(&chunk_bytes, address).await?;
+        // Held for the rest of this send/receive round trip so the
+        // concurrent sends a `broadcast_request` fan-out (or several
+        // overlapping forwards) issues at once can't collectively
+        // exhaust the OS socket send buffer - see `OUTGOING_BYTES_BUDGET`.
+        let permit_size = message_bytes.len().min(OUTGOING_BYTES_BUDGET).max(1) as u32;
+        let _send_permit = self.outgoing_bytes_budget.acquire_many(permit_size).await?;
 
-                    // Delay between chunks to prevent buffer exhaustion and packet loss
-                    // 2ms prevents "No buffer space available" errors (OS error 105)
-                    if i < chunks.len() - 1 {
-                        tokio::time::sleep(Duration::from_millis(2)).await;
-                    }
+        // Determine if message needs chunking
+        let needs_chunking = matches!(message,
+            Message::EncryptionRequest { .. } |
+            Message::EncryptionResponse { .. } |
+            Message::DecryptionRequest { .. } |
+            Message::DecryptionResponse { .. }
+        );
+
+        // Use appropriate timeouts based on message type
+        let timeout_duration = match message {
+            Message::EncryptionRequest { .. } | Message::DecryptionRequest { .. } => Duration::from_secs(30),
+            Message::LoadQuery { .. } => Duration::from_secs(3), // Increased from 500ms - nodes may be busy
+            Message::Election { .. } | Message::Coordinator { .. } => Duration::from_secs(2), // Critical messages
+            _ => Duration::from_secs(1), // Default 1 second for other messages
+        };
+
+        if needs_chunking && message_bytes.len() > 45000 {
+            // Use chunking for large messages
+            let chunks = ChunkedMessage::fragment(message_bytes);
+
+            // Send all chunks
+            for (i, chunk) in chunks.iter().enumerate() {
+                let chunk_bytes = serde_json::to_vec(&chunk)?;
+                socket.send_to(&chunk_bytes, address).await?;
+
+                // Delay between chunks to prevent buffer exhaustion and packet loss
+                // 2ms prevents "No buffer space available" errors (OS error 105)
+                if i < chunks.len() - 1 {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
                 }
+            }
 
-                // Receive and reassemble chunked response
-                let mut chunk_buffer = vec![0u8; 65535];
-                let mut reassembler = ChunkReassembler::new();
-
-                loop {
-                    match tokio::time::timeout(timeout_duration, socket.recv_from(&mut chunk_buffer)).await {
-                        Ok(Ok((n, _))) => {
-                            // Try to parse as chunked message
-                            if let Ok(chunk_msg) = serde_json::from_slice::<ChunkedMessage>(&chunk_buffer[..n]) {
-                                if let Some(complete_data) = reassembler.process_chunk(chunk_msg) {
-                                    // Got complete message
-                                    let response: Message = serde_json::from_slice(&complete_data)?;
-                                    debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
-                                    return Ok(Some(response));
-                                }
-                                // Continue receiving more chunks
+            // Receive and reassemble chunked response
+            let mut chunk_buffer = vec![0u8; 65535];
+            let mut reassembler = ChunkReassembler::new();
+
+            loop {
+                match tokio::time::timeout(timeout_duration, socket.recv_from(&mut chunk_buffer)).await {
+                    Ok(Ok((n, _))) => {
+                        // Try to parse as chunked message
+                        if let Ok(chunk_msg) = serde_json::from_slice::<ChunkedMessage>(&chunk_buffer[..n]) {
+                            if let Some(complete_data) = reassembler.process_chunk(chunk_msg) {
+                                // Got complete message
+                                let response: Message = serde_json::from_slice(&complete_data)?;
+                                debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
+                                return Ok(Some(response));
+                            }
+                            // Continue receiving more chunks
+                        } else {
+                            // Not a chunked message, try parsing directly
+                            if let Ok(response) = serde_json::from_slice::<Message>(&chunk_buffer[..n]) {
+                                debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
+                                return Ok(Some(response));
                             } else {
-                                // Not a chunked message, try parsing directly
-                                if let Ok(response) = serde_json::from_slice::<Message>(&chunk_buffer[..n]) {
-                                    debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
-                                    return Ok(Some(response));
-                                } else {
-                                    debug!("[Node {}] Received invalid message from Node {} ({} bytes)", self.id, node_id, n);
-                                }
+                                debug!("[Node {}] Received invalid message from Node {} ({} bytes)", self.id, node_id, n);
                             }
                         }
-                        Ok(Err(e)) => {
-                            debug!("[Node {}] Socket error waiting for response from Node {}: {}", self.id, node_id, e);
-                            return Ok(None);
-                        }
-                        Err(_) => {
-                            debug!("[Node {}] Timeout waiting for response from Node {} after {:?}",
-                                   self.id, node_id, timeout_duration);
-                            return Ok(None);
-                        }
+                    }
+                    Ok(Err(e)) => {
+                        debug!("[Node {}] Socket error waiting for response from Node {}: {}", self.id, node_id, e);
+                        return Ok(None);
+                    }
+                    Err(_) => {
+                        debug!("[Node {}] Timeout waiting for response from Node {} after {:?}",
+                               self.id, node_id, timeout_duration);
+                        return Ok(None);
                     }
                 }
-            } else {
-                // Small message - send directly without chunking
-                if message_bytes.len() > 65507 {
-                    return Err("Message exceeds UDP packet size limit".into());
-                }
-
-                socket.send_to(&message_bytes, address).await?;
-
-                // Receive response (might be chunked)
-                let mut chunk_buffer = vec![0u8; 65535];
-                let mut reassembler = ChunkReassembler::new();
-
-                loop {
-                    match tokio::time::timeout(timeout_duration, socket.recv_from(&mut chunk_buffer)).await {
-                        Ok(Ok((n, _))) => {
-                            // Try to parse as chunked message first
-                            if let Ok(chunk_msg) = serde_json::from_slice::<ChunkedMessage>(&chunk_buffer[..n]) {
-                                if let Some(complete_data) = reassembler.process_chunk(chunk_msg) {
-                                    // Got complete message
-                                    let response: Message = serde_json::from_slice(&complete_data)?;
-                                    debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
-                                    return Ok(Some(response));
-                                }
-                                // Continue receiving more chunks
+            }
+        } else {
+            // Small message - send directly without chunking
+            if message_bytes.len() > 65507 {
+                return Err("Message exceeds UDP packet size limit".into());
+            }
+
+            socket.send_to(&message_bytes, address).await?;
+
+            // Receive response (might be chunked)
+            let mut chunk_buffer = vec![0u8; 65535];
+            let mut reassembler = ChunkReassembler::new();
+
+            loop {
+                match tokio::time::timeout(timeout_duration, socket.recv_from(&mut chunk_buffer)).await {
+                    Ok(Ok((n, _))) => {
+                        // Try to parse as chunked message first
+                        if let Ok(chunk_msg) = serde_json::from_slice::<ChunkedMessage>(&chunk_buffer[..n]) {
+                            if let Some(complete_data) = reassembler.process_chunk(chunk_msg) {
+                                // Got complete message
+                                let response: Message = serde_json::from_slice(&complete_data)?;
+                                debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
+                                return Ok(Some(response));
+                            }
+                            // Continue receiving more chunks
+                        } else {
+                            // Not a chunked message, try parsing directly
+                            if let Ok(response) = serde_json::from_slice::<Message>(&chunk_buffer[..n]) {
+                                debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
+                                return Ok(Some(response));
                             } else {
-                                // Not a chunked message, try parsing directly
-                                if let Ok(response) = serde_json::from_slice::<Message>(&chunk_buffer[..n]) {
-                                    debug!("[Node {}] Received response from Node {}: {}", self.id, node_id, response);
-                                    return Ok(Some(response));
-                                } else {
-                                    debug!("[Node {}] Received invalid message from Node {} ({} bytes)", self.id, node_id, n);
-                                }
+                                debug!("[Node {}] Received invalid message from Node {} ({} bytes)", self.id, node_id, n);
                             }
                         }
-                        Ok(Err(e)) => {
-                            debug!("[Node {}] Socket error waiting for response from Node {}: {}", self.id, node_id, e);
-                            return Ok(None);
+                    }
+                    Ok(Err(e)) => {
+                        debug!("[Node {}] Socket error waiting for response from Node {}: {}", self.id, node_id, e);
+                        return Ok(None);
+                    }
+                    Err(_) => {
+                        debug!("[Node {}] Timeout waiting for response from Node {} after {:?}",
+                               self.id, node_id, timeout_duration);
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Periodic failure simulation task
+    #[allow(dead_code)]
+    async fn failure_simulation_task(&self) {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        let mut interval = interval(Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            // Random chance to enter Failed state
+            if rng.gen_bool(0.2) {
+                // 20% chance every 30 seconds
+                info!("[Node {}] *** Entering FAILED state ***", self.id);
+                {
+                    let mut state = self.state.write().await;
+                    *state = NodeState::Failed;
+                }
+
+                // Stay in failed state for up to 20 seconds
+                let failure_duration = Duration::from_secs(rng.gen_range(10..=20));
+                sleep(failure_duration).await;
+
+                info!("[Node {}] *** Entering RECOVERING state ***", self.id);
+                {
+                    let mut state = self.state.write().await;
+                    *state = NodeState::Recovering;
+                }
+
+                // Perform state synchronization
+                self.recover_state().await;
+
+                info!("[Node {}] *** Returning to ACTIVE state ***", self.id);
+                {
+                    let mut state = self.state.write().await;
+                    *state = NodeState::Active;
+                }
+            }
+        }
+    }
+
+    /// Recover state from coordinator
+    #[allow(dead_code)]
+    async fn recover_state(&self) {
+        info!("[Node {}] Recovering state from peers...", self.id);
+
+        // Query coordinator for state
+        let manager = self.election_manager.lock().await;
+        if let Some(coordinator_id) = manager.get_coordinator() {
+            if coordinator_id != self.id {
+                let message = Message::StateSync { from_node: self.id };
+                if let Ok(Some(Message::StateSyncResponse { coordinator_id, .. })) =
+                    self.send_message_to_node(coordinator_id, message).await
+                {
+                    info!(
+                        "[Node {}] State synchronized with coordinator: Node {}",
+                        self.id, coordinator_id
+                    );
+                }
+            }
+        }
+
+        // Simulate recovery delay
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    /// Anti-entropy gossip task - every 2 seconds, refresh our own load/state
+    /// record, pull from up to `GOSSIP_FANOUT` random live peers (merging
+    /// back whatever each one knows that we don't), then proactively push a
+    /// random sample of our table to another `GOSSIP_FANOUT` random peers.
+    /// Replaces the old all-to-all heartbeat ping: this bounds our outbound
+    /// traffic to a small, fixed fanout per round while load/failure
+    /// information still reaches the whole cluster within O(log n) rounds,
+    /// via pull and push combined.
+    async fn gossip_task(&self) {
+        // Wait a bit for other nodes to start
+        sleep(Duration::from_secs(3)).await;
+
+        let mut interval = interval(Duration::from_secs(2));
+
+        loop {
+            interval.tick().await;
+
+            let current_load = self.current_load();
+            let current_processed = *self.processed_requests.read().await;
+            let current_state = self.state.read().await.clone();
+            self.gossip.write().await.record_self(current_state, current_load, current_processed);
+
+            self.emit_telemetry(
+                ClusterEvent::new(self.id, ClusterEventKind::Heartbeat)
+                    .with_load(current_load)
+                    .with_processed_count(current_processed),
+            )
+            .await;
+
+            let known_peers = self.membership.read().await.addresses();
+            let failed = self.failed_nodes.read().await.clone();
+            let candidates: Vec<NodeId> = known_peers
+                .keys()
+                .copied()
+                .filter(|id| *id != self.id && !failed.contains(id))
+                .collect();
+
+            let peers: Vec<NodeId> = candidates
+                .choose_multiple(&mut rand::thread_rng(), GOSSIP_FANOUT)
+                .copied()
+                .collect();
+
+            for peer_id in peers {
+                let known = self.gossip.read().await.known_indices();
+                let message = Message::GossipPull { from_node: self.id, known };
+
+                match self.send_secure_message_to_node(peer_id, message).await {
+                    Ok(Some(Message::GossipPush { records, .. })) => {
+                        let changed = self.gossip.write().await.merge(records);
+                        if changed {
+                            debug!("[Node {}] Gossip pull from Node {} brought newer records", self.id, peer_id);
                         }
-                        Err(_) => {
-                            debug!("[Node {}] Timeout waiting for response from Node {} after {:?}",
-                                   self.id, node_id, timeout_duration);
-                            return Ok(None);
+
+                        if let Some(peer_addr) = self.peer_addresses.get(&peer_id) {
+                            self.peer_store.lock().await.record_seen(peer_id, peer_addr);
                         }
                     }
+                    Ok(_) => {
+                        debug!("[Node {}] No gossip response from Node {}", self.id, peer_id);
+                    }
+                    Err(e) => {
+                        warn!("[Node {}] Gossip pull to Node {} failed: {}", self.id, peer_id, e);
+                    }
+                }
+            }
+
+            // Proactively push a random sample of what we hold to a random
+            // subset of peers, independent of the pull round above - pulling
+            // alone only disseminates a record when the holder happens to be
+            // the peer asked, so fresh information (e.g. our own load just
+            // recorded above) can otherwise sit unseen until someone else
+            // pulls from us. This is what lets state propagate transitively
+            // through the mesh instead of only on demand.
+            let push_targets: Vec<NodeId> =
+                candidates.choose_multiple(&mut rand::thread_rng(), GOSSIP_FANOUT).copied().collect();
+
+            if !push_targets.is_empty() {
+                let mut sample: Vec<(NodeId, GossipRecord)> =
+                    self.gossip.read().await.push_for(&HashMap::new()).into_iter().collect();
+                sample.shuffle(&mut rand::thread_rng());
+                sample.truncate(GOSSIP_PUSH_SAMPLE);
+                let records: HashMap<NodeId, GossipRecord> = sample.into_iter().collect();
+
+                for peer_id in push_targets {
+                    let message = Message::GossipPush { from_node: self.id, records: records.clone() };
+                    if let Err(e) = self.send_secure_message_to_node(peer_id, message).await {
+                        debug!("[Node {}] Gossip push to Node {} failed: {}", self.id, peer_id, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Merkle-bucket anti-entropy sync - every 7 seconds, pick one random
+    /// live peer and exchange bucket hashes over our respective
+    /// `stored_images`, catching up anything a `ReplicationPush` missed
+    /// (the peer was briefly unreachable, the push was dropped, it joined
+    /// the cluster after the image was first stored, ...). See
+    /// `replication.rs` for the hashing/placement scheme.
+    async fn replication_sync_task(&self) {
+        sleep(Duration::from_secs(4)).await;
+
+        let mut interval = interval(Duration::from_secs(7));
+
+        loop {
+            interval.tick().await;
+
+            let known_peers = self.membership.read().await.addresses();
+            let failed = self.failed_nodes.read().await.clone();
+            let candidates: Vec<NodeId> = known_peers
+                .keys()
+                .copied()
+                .filter(|id| *id != self.id && !failed.contains(id))
+                .collect();
+
+            let Some(&peer_id) = candidates.get(rand::thread_rng().gen_range(0..candidates.len().max(1))) else {
+                continue; // No live peers to sync with this round
+            };
+
+            let local_entries = self.replication_entries().await;
+            let bucket_hashes = replication::bucket_hashes(&local_entries);
+            let message = Message::ReplicationSync { from_node: self.id, bucket_hashes };
+
+            match self.send_secure_message_to_node(peer_id, message).await {
+                Ok(Some(Message::ReplicationSyncResponse { records, .. })) => {
+                    let count = records.len();
+                    for image in records {
+                        self.apply_replicated_image(image).await;
+                    }
+                    if count > 0 {
+                        debug!(
+                            "[Node {}] Replication sync with Node {} applied {} record(s)",
+                            self.id, peer_id, count
+                        );
+                    }
+                }
+                Ok(_) => {
+                    debug!("[Node {}] No replication sync response from Node {}", self.id, peer_id);
+                }
+                Err(e) => {
+                    warn!("[Node {}] Replication sync with Node {} failed: {}", self.id, peer_id, e);
+                }
+            }
+        }
+    }
+
+    /// Perform the authenticated pairing handshake with every peer we know
+    /// about at startup. Each side proves ownership of its node key by
+    /// signing a fresh nonce, closing the gap where integer node IDs could be
+    /// trivially spoofed on an untrusted network.
+    async fn pair_with_known_peers(&self) {
+        let peer_ids: Vec<NodeId> = self.peer_addresses.keys().copied().collect();
+
+        for peer_id in peer_ids {
+            let nonce: [u8; 16] = rand::random();
+            let message = Message::PairingRequest { nonce };
+
+            match self.send_message_to_node(peer_id, message).await {
+                Ok(Some(Message::PairingResponse { proof })) => {
+                    if proof.verify() {
+                        let claimed_id =
+                            identity::node_id_from_public_key(&proof.node_information.public_key);
+                        self.verified_peers.write().await.insert(claimed_id);
+                        info!(
+                            "[Node {}] Paired with Node {} (capabilities: {:?})",
+                            self.id, peer_id, proof.node_information.capabilities
+                        );
+                    } else {
+                        warn!("[Node {}] Node {} sent an invalid pairing proof", self.id, peer_id);
+                    }
+                }
+                Ok(_) => {
+                    debug!("[Node {}] No pairing response from Node {}", self.id, peer_id);
                 }
+                Err(e) => {
+                    debug!("[Node {}] Pairing handshake with Node {} failed: {}", self.id, peer_id, e);
+                }
+            }
+        }
+    }
+
+    /// Flush the on-disk peer store every 30 seconds so a crash loses at most
+    /// that much reconnection history.
+    async fn peer_store_flush_task(&self) {
+        let mut interval = interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            self.peer_store.lock().await.save();
+        }
+    }
+
+    /// Flush the on-disk user directory every 30 seconds, same cadence as
+    /// the peer store, so a crash loses at most that much directory history.
+    async fn user_directory_flush_task(&self) {
+        let mut interval = interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            self.user_directory.lock().await.save();
+        }
+    }
+
+    /// Flush the on-disk image metadata index every 30 seconds, same
+    /// cadence as the peer store and user directory, so a crash loses at
+    /// most that much of `stored_images` (already-spilled blob files on
+    /// disk are unaffected either way).
+    async fn image_store_flush_task(&self) {
+        let mut interval = interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            self.image_store.save_index(&*self.stored_images.read().await);
+        }
+    }
+
+    /// Reclaims exhausted (`remaining_views == 0`) and TTL-expired stored
+    /// images every 30 seconds, modeled on Garage's block refcounting/resync:
+    /// a `StoredImage` row is only a tombstone candidate once it's past
+    /// `TOMBSTONE_GRACE_SECS`, and is only actually deleted once every other
+    /// replica in its `replica_set` confirms (via `NeedImageQuery`) it isn't
+    /// still holding an unviewed copy either - an unresponsive/unreachable
+    /// replica is treated the same as "doesn't need it" rather than blocking
+    /// collection forever. Since `SendImage`'s fan-out gives every recipient
+    /// of one share their own row but all rows for an `image_id` share the
+    /// same spilled blob file on this node, the underlying blob is only
+    /// freed once no row on this node references that `image_id` anymore.
+    async fn image_gc_task(&self) {
+        sleep(Duration::from_secs(10)).await;
+
+        let mut interval = interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            self.collect_garbage_images().await;
+        }
+    }
+
+    async fn collect_garbage_images(&self) {
+        let now = chrono::Utc::now().timestamp();
+
+        let candidates: Vec<(String, String)> = self
+            .stored_images
+            .read()
+            .await
+            .iter()
+            .flat_map(|(username, images)| {
+                images.iter().filter_map(move |img| {
+                    let age = now - img.timestamp;
+                    let expired = (img.remaining_views == 0 || age > IMAGE_TTL_SECS) && age > TOMBSTONE_GRACE_SECS;
+                    expired.then(|| (username.clone(), img.image_id.clone()))
+                })
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return;
+        }
+
+        let mut removed_count = 0;
+        for (username, image_id) in candidates {
+            if self.replicas_still_need_image(&username, &image_id).await {
+                continue;
+            }
+
+            let mut stored = self.stored_images.write().await;
+            let Some(user_images) = stored.get_mut(&username) else { continue };
+            let Some(pos) = user_images.iter().position(|img| img.image_id == image_id) else { continue };
+            let removed = user_images.remove(pos);
+            if user_images.is_empty() {
+                stored.remove(&username);
+            }
+
+            let still_referenced = stored.values().flatten().any(|img| img.image_id == image_id);
+            if !still_referenced {
+                self.image_store.remove(&removed.blob);
+            }
+
+            removed_count += 1;
+        }
+
+        if removed_count > 0 {
+            info!("[Node {}] Garbage-collected {} exhausted/expired image record(s)", self.id, removed_count);
+        }
+    }
+
+    /// Asks every other replica in `image_id`'s replica set whether they
+    /// still consider `(username, image_id)` live, so a tombstone candidate
+    /// isn't deleted out from under a view that already landed elsewhere but
+    /// hasn't synced back here yet. Unreachable replicas don't block
+    /// collection - they're treated the same as a "not needed" answer.
+    async fn replicas_still_need_image(&self, username: &str, image_id: &str) -> bool {
+        for peer_id in self.replica_set(image_id).await {
+            if peer_id == self.id {
+                continue;
+            }
+
+            let message = Message::NeedImageQuery { username: username.to_string(), image_id: image_id.to_string() };
+            if let Ok(Some(Message::NeedImageQueryResponse { still_needed: true })) =
+                self.send_secure_message_to_node(peer_id, message).await
+            {
+                return true;
             }
-        } else {
-            Err(format!("Unknown node ID: {}", node_id).into())
         }
+        false
     }
 
-    /// Periodic failure simulation task
-    #[allow(dead_code)]
-    async fn failure_simulation_task(&self) {
-        use rand::SeedableRng;
-        let mut rng = rand::rngs::StdRng::from_entropy();
-        let mut interval = interval(Duration::from_secs(30));
-
+    /// Reclaims `UploadSession`s abandoned mid-upload (the client never
+    /// sent a `CompleteUpload`), so their temp files don't sit on disk
+    /// forever.
+    async fn upload_session_gc_task(&self) {
+        let mut interval = interval(Duration::from_secs(60));
         loop {
             interval.tick().await;
+            let before = self.upload_sessions.read().await.len();
+            self.upload_sessions.write().await.retain(|_, session| !session.is_expired(UPLOAD_SESSION_TTL));
+            let removed = before - self.upload_sessions.read().await.len();
+            if removed > 0 {
+                info!("[Node {}] Expired {} abandoned upload session(s)", self.id, removed);
+            }
+        }
+    }
 
-            // Random chance to enter Failed state
-            if rng.gen_bool(0.2) {
-                // 20% chance every 30 seconds
-                info!("[Node {}] *** Entering FAILED state ***", self.id);
-                {
-                    let mut state = self.state.write().await;
-                    *state = NodeState::Failed;
-                }
+    /// Periodically ratchets every established secure channel's key forward
+    /// (see `secure_session.rs`) so traffic captured before a rotation stays
+    /// unreadable even if a later key leaks. For each peer we currently hold
+    /// a session with, sends a `Message::KeyRotation` marker sealed under
+    /// the current send key, then rotates our writer - the peer rotates its
+    /// matching reader as soon as it successfully opens that marker (see the
+    /// `Message::KeyRotation` arm in `process_message`), so both sides
+    /// advance in lockstep without any other coordination.
+    async fn key_rotation_task(&self) {
+        let mut interval = interval(KEY_ROTATION_INTERVAL);
+        loop {
+            interval.tick().await;
 
-                // Stay in failed state for up to 20 seconds
-                let failure_duration = Duration::from_secs(rng.gen_range(10..=20));
-                sleep(failure_duration).await;
+            let peer_ids: Vec<NodeId> = self.secure_sessions.read().await.keys().copied().collect();
+            for peer_id in peer_ids {
+                let _ = self.send_secure_message_to_node(peer_id, Message::KeyRotation { from_node: self.id }).await;
 
-                info!("[Node {}] *** Entering RECOVERING state ***", self.id);
-                {
-                    let mut state = self.state.write().await;
-                    *state = NodeState::Recovering;
+                if let Some(session) = self.secure_sessions.write().await.get_mut(&peer_id) {
+                    session.writer.rotate();
+                    debug!("[Node {}] Rotated send key for Node {}", self.id, peer_id);
                 }
+            }
+        }
+    }
 
-                // Perform state synchronization
-                self.recover_state().await;
-
-                info!("[Node {}] *** Returning to ACTIVE state ***", self.id);
-                {
-                    let mut state = self.state.write().await;
-                    *state = NodeState::Active;
+    /// Waits for Ctrl-C or (on Unix) SIGTERM, then leaves the cluster
+    /// cleanly: broadcasts `Message::Leave` to every live peer so they drop
+    /// us from `failed_nodes`/membership immediately (and start an election
+    /// right away if we were coordinator) instead of waiting out the usual
+    /// gossip-staleness timeout and logging a false "FAILURE DETECTED".
+    /// Waits up to `LEAVE_ACK_TIMEOUT` for acks, then exits the process -
+    /// there's no shutdown plumbing threaded through this node's other
+    /// background tasks or its datagram loop today, so a clean in-process
+    /// return isn't possible; the announcement is the part worth doing
+    /// before going away.
+    async fn graceful_leave_task(&self) {
+        #[cfg(unix)]
+        {
+            let mut sigterm = match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!("[Node {}] Failed to install SIGTERM handler: {}", self.id, e);
+                    // Ctrl-C alone still works; fall through to waiting on it only.
+                    let _ = signal::ctrl_c().await;
+                    self.leave_and_exit().await;
                 }
+            };
+            tokio::select! {
+                _ = signal::ctrl_c() => {}
+                _ = sigterm.recv() => {}
             }
         }
+        #[cfg(not(unix))]
+        {
+            let _ = signal::ctrl_c().await;
+        }
+
+        self.leave_and_exit().await;
     }
 
-    /// Recover state from coordinator
-    #[allow(dead_code)]
-    async fn recover_state(&self) {
-        info!("[Node {}] Recovering state from peers...", self.id);
+    /// Broadcasts `Message::Leave`, waits briefly for acks, then exits.
+    /// Split out of `graceful_leave_task` so the SIGTERM-unavailable
+    /// fallback above can reach it too.
+    async fn leave_and_exit(&self) -> ! {
+        info!("[Node {}] Shutdown signal received, leaving the cluster", self.id);
 
-        // Query coordinator for state
         let manager = self.election_manager.lock().await;
-        if let Some(coordinator_id) = manager.get_coordinator() {
-            if coordinator_id != self.id {
-                let message = Message::StateSync { from_node: self.id };
-                if let Ok(Some(Message::StateSyncResponse { coordinator_id, .. })) =
-                    self.send_message_to_node(coordinator_id, message).await
-                {
-                    info!(
-                        "[Node {}] State synchronized with coordinator: Node {}",
-                        self.id, coordinator_id
+        let is_coordinator = manager.get_coordinator() == Some(self.id);
+        drop(manager);
+
+        let live_peers: Vec<NodeId> = {
+            let failed = self.failed_nodes.read().await;
+            self.membership.read().await.addresses().keys().copied().filter(|id| !failed.contains(id)).collect()
+        };
+
+        // Best-effort: if we're the coordinator, hint whichever live peer
+        // has the lowest node ID, mirroring the simple tie-break the Bully
+        // algorithm itself falls back on - the peers still decide for real
+        // via their own `trigger_election`, this is only a hint.
+        let successor_hint = if is_coordinator { live_peers.iter().min().copied() } else { None };
+
+        let strategy = RequestStrategy::with_timeout(LEAVE_ACK_TIMEOUT);
+        let acks = broadcast_request(&live_peers, strategy, |peer_id| async move {
+            match self.send_message_to_node(peer_id, Message::Leave { from_node: self.id, successor_hint }).await {
+                Ok(Some(Message::LeaveAck { .. })) => Some(()),
+                _ => None,
+            }
+        })
+        .await;
+
+        info!(
+            "[Node {}] Left the cluster ({}/{} peer(s) acked)",
+            self.id, acks.len(), live_peers.len()
+        );
+
+        std::process::exit(0);
+    }
+
+    /// Admin-triggered graceful drain: flips to `Draining` immediately (so
+    /// `SessionRegister`/election handling reject this node right away),
+    /// then migrates every locally held image to its `cluster_layout`
+    /// replicas and finally leaves via `leave_and_exit`. The migration and
+    /// leave run in a spawned task rather than being awaited here, so an
+    /// admin API handler can respond to the request before the process exits.
+    pub async fn start_draining(self: Arc<Self>) {
+        info!("[Node {}] Draining: rejecting new sessions and coordinator bids", self.id);
+        *self.state.write().await = NodeState::Draining;
+
+        tokio::spawn(async move { self.migrate_owned_images_and_leave().await });
+    }
+
+    /// Pushes every image this node holds to whichever peers `cluster_layout`
+    /// currently assigns it to (the first real consumer of
+    /// `ClusterLayout::nodes_for_image` - placement decisions elsewhere
+    /// still go through the older consistent-hashing `replica_set`), then
+    /// leaves. Best-effort: a failed push here just means anti-entropy on
+    /// the receiving replicas has to catch the image up some other way,
+    /// same as an unacked `ReplicationPush` during normal operation.
+    async fn migrate_owned_images_and_leave(&self) {
+        let entries: Vec<(String, StoredImage)> = self
+            .stored_images
+            .read()
+            .await
+            .iter()
+            .flat_map(|(username, images)| images.iter().map(move |image| (username.clone(), image.clone())))
+            .collect();
+
+        let layout = self.cluster_layout.read().await.clone();
+        for (username, image) in entries {
+            let targets: Vec<NodeId> =
+                layout.nodes_for_image(&image.image_id).iter().copied().filter(|&id| id != self.id).collect();
+            if targets.is_empty() {
+                continue;
+            }
+
+            let encrypted_data = match self.image_store.get(&image.blob) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!(
+                        "[Node {}] Skipping migration of {} for {}: {}",
+                        self.id, image.image_id, username, e
                     );
+                    continue;
+                }
+            };
+            let replicated = ReplicatedImage {
+                username: username.clone(),
+                image_id: image.image_id.clone(),
+                from_username: image.from_username.clone(),
+                encrypted_data,
+                remaining_views: image.remaining_views,
+                max_views: image.max_views,
+                timestamp: image.timestamp,
+                checksum: image.checksum,
+                notified: image.notified,
+            };
+
+            for target in targets {
+                let message = Message::ReplicationPush { image: replicated.clone() };
+                match self.send_secure_message_to_node(target, message).await {
+                    Ok(Some(Message::ReplicationPushResponse { accepted: true, .. })) => {}
+                    _ => warn!(
+                        "[Node {}] Failed to migrate {} to Node {} before draining",
+                        self.id, image.image_id, target
+                    ),
                 }
             }
         }
 
-        // Simulate recovery delay
-        sleep(Duration::from_millis(500)).await;
+        info!("[Node {}] Finished migrating stored images, leaving now", self.id);
+        self.leave_and_exit().await;
     }
 
-    /// Heartbeat sender task - sends heartbeat to all peers every 2 seconds
-    async fn heartbeat_sender_task(&self) {
-        // Wait a bit for other nodes to start
+    /// Membership gossip task - periodically exchanges known-hosts digests with
+    /// peers so the live peer set can grow/shrink without restarting the node.
+    /// A digest mismatch triggers a full exchange of active-connection lists,
+    /// letting nodes learn about peers they weren't started with.
+    async fn membership_gossip_task(&self) {
         sleep(Duration::from_secs(3)).await;
 
-        // Create a dedicated socket for heartbeats (reuse it instead of creating new ones)
-        let bind_addr = format!("0.0.0.0:{}", 10000 + self.id);
-        let heartbeat_socket = match UdpSocket::bind(&bind_addr).await {
-            Ok(s) => s,
-            Err(_) => {
-                // Fallback to any available port
-                match UdpSocket::bind("0.0.0.0:0").await {
-                    Ok(s) => s,
-                    Err(e) => {
-                        error!("[Node {}] Failed to create heartbeat socket: {}", self.id, e);
-                        return;
-                    }
-                }
-            }
-        };
-
-        let mut interval = interval(Duration::from_secs(2));
+        let mut interval = interval(Duration::from_secs(5));
 
         loop {
             interval.tick().await;
 
-            // Get current load and processed count to include in heartbeat
-            let current_load = *self.current_load.read().await;
-            let current_processed = *self.processed_requests.read().await;
+            let my_digest = self.membership.read().await.digest();
+            let known_peers = self.membership.read().await.addresses();
 
-            // Send heartbeat to all peers
-            for (peer_id, peer_addr) in &self.peer_addresses {
-                let message = Message::Heartbeat {
+            for (&peer_id, _) in &known_peers {
+                let message = Message::MembershipDigest {
                     from_node: self.id,
-                    load: current_load,
-                    processed_count: current_processed,
+                    digest: my_digest,
                 };
 
-                match serde_json::to_vec(&message) {
-                    Ok(message_bytes) => {
-                        match heartbeat_socket.send_to(&message_bytes, peer_addr).await {
-                            Ok(_) => {
-                                // Heartbeat sent successfully (silent success)
-                            }
-                            Err(e) => {
-                                // Log error but continue - temporary network issues shouldn't crash the task
-                                warn!("[Node {}] Failed to send heartbeat to Node {} ({}): {}",
-                                      self.id, peer_id, peer_addr, e);
-                            }
+                match self.send_message_to_node(peer_id, message).await {
+                    Ok(Some(Message::MembershipExchange { peers, .. })) => {
+                        let changed = self.membership.write().await.merge(&peers);
+                        if changed {
+                            info!(
+                                "[Node {}] Learned new membership info from Node {}",
+                                self.id, peer_id
+                            );
                         }
                     }
+                    Ok(_) => {
+                        // Digests already matched, nothing to merge
+                    }
                     Err(e) => {
-                        error!("[Node {}] Failed to serialize heartbeat: {}", self.id, e);
+                        debug!(
+                            "[Node {}] Membership gossip with Node {} failed: {}",
+                            self.id, peer_id, e
+                        );
                     }
                 }
             }
         }
     }
 
-    /// Failure detector task - checks for failed nodes every 3 seconds
+    /// Poll a service-discovery backend (Consul, Kubernetes, ...) on a fixed
+    /// interval and merge whatever peer addresses it resolves into the live
+    /// membership table, the same path the gossip protocol uses. Orchestrated
+    /// deployments can call this instead of/alongside a fixed peer list.
+    pub async fn run_discovery(self: Arc<Self>, backend: Box<dyn crate::bootstrap::DiscoveryBackend>, interval_secs: u64) {
+        let self_clone = self.clone();
+        crate::bootstrap::run_discovery_loop(backend, Duration::from_secs(interval_secs), move |resolved| {
+            let self_clone = self_clone.clone();
+            tokio::spawn(async move {
+                let peers: Vec<(NodeId, String, Vec<String>)> =
+                    resolved.into_iter().map(|(id, addr)| (id, addr, Vec::new())).collect();
+                let changed = self_clone.membership.write().await.merge(&peers);
+                if changed {
+                    info!("[Node {}] Discovery fed new peer addresses into membership", self_clone.id);
+                }
+            });
+        })
+        .await;
+    }
+
+    /// Failure detector task - checks every 3 seconds whether any peer's
+    /// phi-accrual suspicion level (see `phi_detector`) has crossed
+    /// `PHI_FAILURE_THRESHOLD`, in place of a fixed "stale for longer than
+    /// X seconds" timeout. Phi is derived from each peer's own gossip
+    /// inter-arrival history, so it tolerates a peer that's merely slow
+    /// this round without waiting out a worst-case fixed timeout, while
+    /// still reacting fast to one that's gone truly silent.
     async fn failure_detector_task(&self) {
-        // Wait longer for heartbeats to start flowing and all nodes to be ready
+        // Wait longer for gossip to start flowing and all nodes to be ready
         // This prevents false-positive failure detection at startup
         sleep(Duration::from_secs(15)).await;
 
         let mut interval = interval(Duration::from_secs(3));
-        const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(10); // Increased from 6s to 10s for more forgiveness
+        // Fallback for peers we hold a gossip record for but no inter-arrival
+        // history yet (phi has nothing to go on, so it reads as
+        // 0.0/unsuspicious forever) - without this a peer that dies before
+        // gossiping twice would never be detected. Peers with no gossip
+        // record at all are not covered by it.
+        const NEVER_SEEN_TIMEOUT: Duration = Duration::from_secs(10);
 
         loop {
             interval.tick().await;
@@ -1358,62 +3814,249 @@ impl CloudNode {
             let now = Instant::now();
             let mut newly_failed_nodes = Vec::new();
 
-            // Check all peers for heartbeat timeout
+            // Check all known peers for suspicion in the gossiped view - the
+            // live, gossiped membership view rather than just the bootstrap
+            // list, so nodes learned after startup get monitored too.
             {
-                let heartbeats = self.last_heartbeat.read().await;
+                let gossip = self.gossip.read().await;
                 let failed = self.failed_nodes.read().await;
+                let known_peers = self.membership.read().await.addresses();
 
-                for (&peer_id, _) in &self.peer_addresses {
+                for &peer_id in known_peers.keys() {
                     // Skip if already marked as failed
                     if failed.contains(&peer_id) {
                         continue;
                     }
 
-                    // Check if we've received a heartbeat from this peer
-                    if let Some(&last_seen) = heartbeats.get(&peer_id) {
-                        let elapsed = now.duration_since(last_seen);
-                        if elapsed > HEARTBEAT_TIMEOUT {
-                            debug!("[Node {}] Node {} missed heartbeat (last seen {:.1}s ago)",
-                                   self.id, peer_id, elapsed.as_secs_f64());
+                    if let Some(record) = gossip.get(peer_id) {
+                        let phi = gossip.phi(peer_id);
+                        if phi >= PHI_FAILURE_THRESHOLD {
+                            debug!(
+                                "[Node {}] Node {} suspicion phi={:.2} crossed threshold (last seen {:.1}s ago)",
+                                self.id, peer_id, phi, now.duration_since(record.last_seen).as_secs_f64()
+                            );
                             newly_failed_nodes.push(peer_id);
                         }
                     } else {
-                        // Never received a heartbeat from this peer - may not have started yet
+                        // Gossip hasn't told us anything about this peer yet - may not have started yet
                         // Only mark as failed if enough time has passed since our startup
                         // (we start checking after 8 seconds, so this is reasonable)
-                        debug!("[Node {}] Node {} never sent heartbeat (may not be started)", self.id, peer_id);
+                        debug!("[Node {}] Node {} not yet in gossip table (may not be started)", self.id, peer_id);
+                    }
+                }
+
+                // Peers with a record but no inter-arrival history yet (phi
+                // stuck at 0.0) still need a fallback timeout, or a node
+                // that dies before gossiping twice is never detected.
+                for &peer_id in known_peers.keys() {
+                    if failed.contains(&peer_id) || newly_failed_nodes.contains(&peer_id) {
+                        continue;
+                    }
+                    if let Some(record) = gossip.get(peer_id) {
+                        let elapsed = now.duration_since(record.last_seen);
+                        if gossip.phi(peer_id) == 0.0 && elapsed > NEVER_SEEN_TIMEOUT {
+                            debug!(
+                                "[Node {}] Node {} has no inter-arrival history yet, stale for {:.1}s",
+                                self.id, peer_id, elapsed.as_secs_f64()
+                            );
+                            newly_failed_nodes.push(peer_id);
+                        }
                     }
                 }
             }
 
             // Handle newly detected failures
-            if !newly_failed_nodes.is_empty() {
-                let mut failed = self.failed_nodes.write().await;
+            for failed_node in newly_failed_nodes {
+                let was_coordinator = self.mark_peer_failed(failed_node, "phi-accrual suspicion threshold crossed").await;
+                if was_coordinator {
+                    break; // Election already triggered; avoid overlapping ones this tick
+                }
+            }
+        }
+    }
+
+    /// Shared bookkeeping for a peer just determined to be dead - used by
+    /// both `failure_detector_task` (reacting to gossiped load staleness)
+    /// and `membership_timeout_task` (reacting to the membership table's own
+    /// TTL), so either detector drives the same `failed_nodes`/coordinator
+    /// fallout. Returns `true` if the failed node was the coordinator (an
+    /// election was triggered), so callers iterating several failures at
+    /// once can stop rather than triggering overlapping elections.
+    async fn mark_peer_failed(&self, failed_node: NodeId, reason: &str) -> bool {
+        if !self.failed_nodes.write().await.insert(failed_node) {
+            return false; // Already handled by the other detector
+        }
+        self.gossip.write().await.mark_failed_locally(failed_node);
+        self.membership.write().await.remove(failed_node);
+        warn!("[Node {}] FAILURE DETECTED: Node {} is not responding ({})", self.id, failed_node, reason);
+        self.emit_telemetry(ClusterEvent::new(failed_node, ClusterEventKind::FailureDetected))
+            .await;
 
-                for failed_node in newly_failed_nodes {
-                    failed.insert(failed_node);
-                    warn!("[Node {}] FAILURE DETECTED: Node {} is not responding", self.id, failed_node);
+        let manager = self.election_manager.lock().await;
+        let coordinator_id = manager.get_coordinator();
+        drop(manager);
 
-                    // Check if the failed node is the coordinator
-                    let manager = self.election_manager.lock().await;
-                    let coordinator_id = manager.get_coordinator();
-                    drop(manager);
-
-                    if coordinator_id == Some(failed_node) {
-                        // COORDINATOR FAILED - trigger election!
-                        error!("[Node {}] COORDINATOR Node {} has FAILED! Triggering election...", self.id, failed_node);
-                        drop(failed); // Release lock before triggering election
-                        self.trigger_election().await;
-                        break; // Exit loop to avoid multiple elections
+        if coordinator_id == Some(failed_node) {
+            error!("[Node {}] COORDINATOR Node {} has FAILED! Triggering election...", self.id, failed_node);
+            self.trigger_election().await;
+            true
+        } else {
+            // If non-coordinator node failed, just log it - the coordinator
+            // will notice when it tries to load balance to this node.
+            false
+        }
+    }
+
+    /// Membership-table TTL sweep - distinct from `failure_detector_task`,
+    /// which reacts to staleness in the gossiped load/liveness view. This
+    /// simply forgets peers the membership table itself hasn't heard from
+    /// (via gossip exchange, `Join`, or a successful request round trip -
+    /// see `MembershipTable::touch`) within `MEMBERSHIP_TTL`.
+    async fn membership_timeout_task(&self) {
+        sleep(Duration::from_secs(15)).await;
+
+        let mut interval = interval(Duration::from_secs(10));
+        const MEMBERSHIP_TTL: Duration = Duration::from_secs(30);
+
+        loop {
+            interval.tick().await;
+
+            let evicted = self.membership.write().await.timeout(MEMBERSHIP_TTL);
+            for node_id in evicted {
+                self.mark_peer_failed(node_id, "unseen past membership TTL").await;
+            }
+        }
+    }
+
+    /// Announce ourselves to every bootstrap peer shortly after startup, via
+    /// `Message::Join`, so a late-joining (or address-changed) node is
+    /// reachable immediately instead of waiting for the next digest-mismatch
+    /// exchange to notice it.
+    async fn announce_join_task(&self) {
+        sleep(Duration::from_secs(2)).await;
+
+        let addrs = vec![self.address.clone()];
+        let peer_ids: Vec<NodeId> = self.peer_addresses.keys().copied().collect();
+
+        for peer_id in peer_ids {
+            let message = Message::Join { node_id: self.id, addrs: addrs.clone() };
+            if let Err(e) = self.send_message_to_node(peer_id, message).await {
+                debug!("[Node {}] Join announcement to Node {} failed: {}", self.id, peer_id, e);
+            }
+        }
+    }
+
+    /// Probe this node's assigned subjects (see
+    /// `rapid_membership::ExpanderTopology::subjects_of`) every
+    /// `RAPID_MONITOR_INTERVAL`, broadcasting a `RapidAlert` whenever one's
+    /// reachability changes from what we last reported. A `LoadQuery` round
+    /// trip doubles as the reachability probe - no separate ping message
+    /// needed, since every live node already answers it.
+    async fn rapid_monitor_task(&self) {
+        sleep(Duration::from_secs(4)).await;
+
+        let mut interval = interval(RAPID_MONITOR_INTERVAL);
+        let mut last_status: HashMap<NodeId, EdgeStatus> = HashMap::new();
+
+        loop {
+            interval.tick().await;
+
+            let subjects = self.rapid.lock().await.subjects_to_monitor();
+            for subject in subjects {
+                if subject == self.id {
+                    continue;
+                }
+
+                let reachable = matches!(
+                    self.send_message_to_node(subject, Message::LoadQuery { from_node: self.id }).await,
+                    Ok(Some(Message::LoadResponse { .. }))
+                );
+                let status = if reachable { EdgeStatus::Up } else { EdgeStatus::Down };
+
+                if last_status.get(&subject) == Some(&status) {
+                    continue;
+                }
+                last_status.insert(subject, status);
+
+                let outcome = self.rapid.lock().await.report_alert(self.id, subject, status);
+                if outcome == ReportOutcome::Stable {
+                    self.propose_pending_rapid_cut().await;
+                }
+
+                let committed_peers: Vec<NodeId> =
+                    self.rapid.lock().await.committed_members().into_iter().filter(|&id| id != self.id).collect();
+                for peer_id in committed_peers {
+                    let message = Message::RapidAlert { observer: self.id, subject, up: reachable };
+                    if let Err(e) = self.send_message_to_node(peer_id, message).await {
+                        debug!("[Node {}] RapidAlert to Node {} failed: {}", self.id, peer_id, e);
                     }
-                    // If non-coordinator node failed, just log it
-                    // The coordinator will notice when it tries to load balance to this node
                 }
             }
         }
     }
 
-    /// Load monitoring task - logs load distribution every 10 seconds
+    /// Take whatever `MultiNodeCut` has accumulated in `self.rapid` and, if
+    /// non-empty, broadcast it as a `RapidCutProposal` and drive it through
+    /// `MembershipService::propose_cut`'s fast-path/classic-majority
+    /// agreement. A no-op if nothing has reached the `H` stable threshold
+    /// since the last call.
+    async fn propose_pending_rapid_cut(&self) {
+        let cut = self.rapid.lock().await.take_pending_cut();
+        if cut.is_empty() {
+            return;
+        }
+
+        let to_add: Vec<NodeId> = cut.to_add.iter().copied().collect();
+        let to_remove: Vec<NodeId> = cut.to_remove.iter().copied().collect();
+
+        let ack_self = self.clone();
+        let ack_peer = move |peer_id: NodeId| {
+            let self_clone = ack_self.clone();
+            let message = Message::RapidCutProposal {
+                proposer: self_clone.id,
+                to_add: to_add.clone(),
+                to_remove: to_remove.clone(),
+            };
+            async move {
+                matches!(self_clone.send_secure_message_to_node(peer_id, message).await, Ok(Some(Message::Ok { .. })))
+            }
+        };
+
+        let committed = self
+            .rapid
+            .lock()
+            .await
+            .propose_cut(cut.clone(), ack_peer, Duration::from_millis(500), election::DEFAULT_ELECTION_TIMEOUT)
+            .await;
+
+        if committed {
+            info!(
+                "[Node {}] Committed membership cut: +{:?} -{:?}",
+                self.id, cut.to_add, cut.to_remove
+            );
+            for &failed in &cut.to_remove {
+                self.mark_peer_failed(failed, "Rapid membership cut").await;
+            }
+            // The committed member set just changed, so the partition
+            // assignment needs to change with it - see `recompute_layout`.
+            self.recompute_layout().await;
+        } else {
+            warn!(
+                "[Node {}] Membership cut proposal (+{:?} -{:?}) didn't reach quorum; will retry once more alerts arrive",
+                self.id, cut.to_add, cut.to_remove
+            );
+        }
+    }
+
+    /// Load monitoring task - logs load distribution every 10 seconds.
+    ///
+    /// Reads peer load/state from `self.gossip` (see `gossip.rs`) instead of
+    /// issuing a fresh `LoadQuery` to every peer each cycle - that used to be
+    /// an O(n) fan-out purely to refresh a status printout, on top of the
+    /// fan-outs already done by `trigger_election` and anything else that
+    /// needs up-to-the-second numbers. `gossip_task`'s pull/push rounds keep
+    /// this table converged within a few seconds anyway, which is plenty
+    /// fresh for a display that only refreshes every 10s.
     async fn load_monitoring_task(&self) {
         // Wait for system to stabilize before starting monitoring
         sleep(Duration::from_secs(20)).await;
@@ -1429,94 +4072,67 @@ impl CloudNode {
             drop(manager);
 
             // Get failed nodes
-            let failed = self.failed_nodes.read().await;
+            let failed = self.failed_nodes.read().await.clone();
 
             // Collect load information from all nodes
             let mut load_info = Vec::new();
 
             // Add this node's info
-            let self_load = self.current_load.read().await;
+            let self_load = self.current_load();
             let self_processed = self.processed_requests.read().await;
-            let self_active = self.active_requests.read().await;
+            let self_active = self.active_requests.load(Ordering::Relaxed);
 
             let is_coordinator = coordinator_id == Some(self.id);
             let status = if is_coordinator { "COORDINATOR" } else { "Worker" };
 
             load_info.push((
                 self.id,
-                *self_load,
+                self_load,
                 *self_processed,
-                *self_active,
+                self_active,
                 status.to_string(),
             ));
 
-            drop(self_load);
             drop(self_processed);
-            drop(self_active);
 
-            // Query all peer nodes for their load
-            for (&peer_id, peer_addr) in &self.peer_addresses {
-                // Skip failed nodes
+            // peer_load_cache: a view over the gossip table rather than a
+            // fresh round trip per peer. "Active Reqs" isn't part of the
+            // gossiped record (only load/processed_count/state are), so it
+            // reads as 0 for peers - the same tradeoff `gossip_task` already
+            // makes for failure detection, in exchange for not fanning out
+            // to every peer every 10 seconds.
+            let gossip = self.gossip.read().await;
+            for peer_id in self.peer_addresses.keys().copied() {
                 if failed.contains(&peer_id) {
                     load_info.push((peer_id, 0.0, 0, 0, "FAILED".to_string()));
                     continue;
                 }
 
-                // Send load query
-                let message = Message::LoadQuery { from_node: self.id };
-
-                match serde_json::to_vec(&message) {
-                    Ok(message_bytes) => {
-                        // Create temporary socket for query
-                        match UdpSocket::bind("0.0.0.0:0").await {
-                            Ok(query_socket) => {
-                                // Set short timeout for monitoring query
-                                match tokio::time::timeout(
-                                    Duration::from_secs(2),
-                                    async {
-                                        // Send query
-                                        query_socket.send_to(&message_bytes, peer_addr).await?;
-
-                                        // Wait for response
-                                        let mut buf = vec![0u8; 65535];
-                                        let (n, _) = query_socket.recv_from(&mut buf).await?;
-                                        let response: Message = serde_json::from_slice(&buf[..n])?;
-
-                                        Ok::<Message, Box<dyn std::error::Error>>(response)
-                                    }
-                                ).await {
-                                    Ok(Ok(Message::LoadResponse { node_id, load, queue_length, processed_count })) => {
-                                        let status = if coordinator_id == Some(node_id) {
-                                            "COORDINATOR"
-                                        } else {
-                                            "Worker"
-                                        };
-                                        load_info.push((node_id, load, processed_count, queue_length, status.to_string()));
-                                    }
-                                    _ => {
-                                        // Query failed or timed out
-                                        load_info.push((peer_id, 0.0, 0, 0, "NO_RESPONSE".to_string()));
-                                    }
-                                }
-                            }
-                            Err(_) => {
-                                // Socket creation failed
-                                load_info.push((peer_id, 0.0, 0, 0, "ERROR".to_string()));
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // Serialization failed
-                        load_info.push((peer_id, 0.0, 0, 0, "ERROR".to_string()));
+                match gossip.get(peer_id) {
+                    Some(record) if record.state == NodeState::Active => {
+                        let status = if coordinator_id == Some(peer_id) { "COORDINATOR" } else { "Worker" };
+                        load_info.push((peer_id, record.load, record.processed_count, 0, status.to_string()));
                     }
+                    Some(_) => load_info.push((peer_id, 0.0, 0, 0, "FAILED".to_string())),
+                    None => load_info.push((peer_id, 0.0, 0, 0, "NO_RESPONSE".to_string())),
                 }
             }
-
-            drop(failed);
+            drop(gossip);
 
             // Sort by node ID for consistent display
             load_info.sort_by_key(|(id, _, _, _, _)| *id);
 
+            for (node_id, load, processed, queue_length, _status) in &load_info {
+                let mut event = ClusterEvent::new(*node_id, ClusterEventKind::LoadReport)
+                    .with_load(*load)
+                    .with_processed_count(*processed)
+                    .with_queue_length(*queue_length);
+                if let Some(coordinator_id) = coordinator_id {
+                    event = event.with_coordinator_id(coordinator_id);
+                }
+                self.emit_telemetry(event).await;
+            }
+
             // Log the load distribution
             info!("[Node {}] ════════════════ CLUSTER LOAD DISTRIBUTION ════════════════", self.id);
             info!("[Node {}] ┌────────┬────────────┬───────────┬────────────┬──────────────┐", self.id);
@@ -1558,118 +4174,200 @@ impl CloudNode {
 
     /// Trigger an election
     async fn trigger_election(&self) {
-        let current_load = *self.current_load.read().await;
+        let current_load = self.current_load();
         let current_processed = *self.processed_requests.read().await;
+        let current_phi = self.gossip.read().await.max_peer_phi();
         let mut manager = self.election_manager.lock().await;
 
-        // Collect load and processed counts from all nodes
-        let mut all_loads = HashMap::new();
-        let mut all_processed = HashMap::new();
-        all_loads.insert(self.id, current_load);
-        all_processed.insert(self.id, current_processed);
+        // Pick up any peers learned via membership gossip since the last
+        // election so they're eligible to be elected too.
+        manager.sync_addresses(self.membership.read().await.addresses());
+
+        // Skip failed and draining nodes - neither can be elected as
+        // coordinator. Draining peers are learned from the gossiped
+        // `peer_load_cache` (`self.gossip`), since `Draining` still
+        // gossips normally rather than going silent like `Failed`.
+        let mut excluded = self.failed_nodes.read().await.clone();
+        if *self.state.read().await == NodeState::Draining {
+            excluded.insert(self.id);
+        }
+        for (&peer_id, record) in self.gossip.read().await.peers() {
+            if record.state == NodeState::Draining {
+                excluded.insert(peer_id);
+            }
+        }
 
-        // Get list of failed nodes to skip them in election
-        let failed = self.failed_nodes.read().await;
+        let send_fn = |node: NodeId, msg: Message| {
+            let self_clone = self.clone();
+            tokio::spawn(async move {
+                let _ = self_clone.send_secure_message_to_node(node, msg).await;
+            });
+            true
+        };
 
-        for (&peer_id, _) in &self.peer_addresses {
-            // Skip failed nodes - they cannot be elected as coordinator
-            if failed.contains(&peer_id) {
-                info!("[Node {}] Skipping failed Node {} in election", self.id, peer_id);
-                continue;
+        let self_for_query = self.clone();
+        let query_peer = move |peer_id: NodeId| {
+            let self_clone = self_for_query.clone();
+            async move {
+                let message = Message::LoadQuery { from_node: self_clone.id };
+                match self_clone.send_message_to_node(peer_id, message).await {
+                    Ok(Some(Message::LoadResponse { load, processed_count, capacity, zone, phi, .. })) => {
+                        self_clone.note_peer_capability(peer_id, capacity, zone).await;
+                        Some((load, processed_count, phi))
+                    }
+                    _ => None,
+                }
             }
+        };
 
-            let message = Message::LoadQuery { from_node: self.id };
-            if let Ok(Some(Message::LoadResponse { node_id, load, processed_count, .. })) =
-                self.send_message_to_node(peer_id, message).await
-            {
-                all_loads.insert(node_id, load);
-                all_processed.insert(node_id, processed_count);
+        let result = manager
+            .start_election(
+                current_load,
+                current_processed,
+                current_phi,
+                &excluded,
+                send_fn,
+                query_peer,
+                election::DEFAULT_ELECTION_TIMEOUT,
+            )
+            .await;
+
+        let Some(result) = result else {
+            return;
+        };
+
+        let all_loads = result.all_loads.clone();
+        let all_processed = &result.all_processed;
+        let term = result.term;
+
+        // Run the lowest-load selection again, restricted to the agreed
+        // Rapid membership cut rather than every node that happened to
+        // answer this particular LoadQuery round. Two nodes with slightly
+        // different `all_loads` (a peer that answered one of them but not
+        // the other) still agree here, because both filter against the
+        // same committed set - see `rapid_membership::select_coordinator`.
+        //
+        // Comparison uses load normalized by each node's advertised
+        // capacity (`cluster_layout::normalized_load`) so a powerful node
+        // running the same absolute load as a weaker one is correctly
+        // treated as less loaded, rather than penalized for handling more
+        // total work.
+        // `excluded` (failed + draining) applies here too - a draining node
+        // must never win this second, authoritative selection either.
+        let committed_members: HashSet<NodeId> =
+            self.rapid.lock().await.committed_members().into_iter().filter(|id| !excluded.contains(id)).collect();
+        let peer_capabilities = self.peer_capabilities.read().await.clone();
+        let my_capability = self.capability.read().await.clone();
+        let capacity_of = |node_id: NodeId| -> f64 {
+            if node_id == self.id {
+                my_capability.capacity
+            } else {
+                peer_capabilities.get(&node_id).map(|c| c.capacity).unwrap_or(1.0)
             }
-        }
+        };
+        let normalized_loads: HashMap<NodeId, f64> = all_loads
+            .iter()
+            .map(|(&id, &load)| (id, cluster_layout::normalized_load(load, capacity_of(id))))
+            .collect();
+        let (lowest_node, lowest_load) =
+            match rapid_membership::select_coordinator(&committed_members, &normalized_loads) {
+                Some(node_id) => (node_id, *all_loads.get(&node_id).unwrap_or(&result.load)),
+                None => (result.coordinator_id, result.load),
+            };
 
-        drop(failed); // Release read lock
+        self.emit_telemetry(
+            ClusterEvent::new(self.id, ClusterEventKind::ElectionCompleted)
+                .with_load(lowest_load)
+                .with_coordinator_id(lowest_node),
+        )
+        .await;
 
         // Calculate total processed and percentages
         let total_processed: usize = all_processed.values().sum();
-        
-        // Find node with lowest load
-        if let Some((&lowest_node, &lowest_load)) =
-            all_loads.iter().min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-        {
-            let result = ElectionResult::new(lowest_node, lowest_load, all_loads.clone());
-            result.log_result();
-
-            // Log work distribution percentages
-            if total_processed > 0 {
-                info!("=== WORK DISTRIBUTION ===");
-                let mut sorted_nodes: Vec<_> = all_processed.iter().collect();
-                sorted_nodes.sort_by(|a, b| b.1.cmp(a.1)); // Sort by processed count descending
-                for (node_id, processed) in sorted_nodes {
-                    let percentage = (*processed as f64 / total_processed as f64) * 100.0;
-                    info!("  Node {}: {} requests ({:.1}%)", node_id, processed, percentage);
-                }
-                info!("  Total: {} requests", total_processed);
-                info!("=========================");
-            }
-
-            // Add hysteresis: only change coordinator if load difference is significant
-            // This prevents rapid coordinator changes due to minor load fluctuations
-            let current_coordinator = manager.get_coordinator();
-            let should_change = if let Some(current_coord) = current_coordinator {
-                if current_coord == lowest_node {
-                    // Already the right coordinator
-                    false
-                } else if let Some(&current_coord_load) = all_loads.get(&current_coord) {
-                    // Only change if the new coordinator has significantly lower load (>20% difference)
-                    let load_diff_ratio = (current_coord_load - lowest_load) / current_coord_load.max(0.01);
-                    if load_diff_ratio > 0.20 {
-                        info!("[Node {}] Coordinator change justified: current load {:.2}, new load {:.2} ({:.1}% improvement)",
-                              self.id, current_coord_load, lowest_load, load_diff_ratio * 100.0);
-                        true
-                    } else {
-                        info!("[Node {}] Skipping coordinator change: load difference {:.1}% is below 20% threshold",
-                              self.id, load_diff_ratio * 100.0);
-                        false
+
+        result.log_result();
+
+        // Log work distribution percentages
+        if total_processed > 0 {
+            info!("=== WORK DISTRIBUTION ===");
+            let mut sorted_nodes: Vec<_> = all_processed.iter().collect();
+            sorted_nodes.sort_by(|a, b| b.1.cmp(a.1)); // Sort by processed count descending
+            for (node_id, processed) in sorted_nodes {
+                let percentage = (*processed as f64 / total_processed as f64) * 100.0;
+                info!("  Node {}: {} requests ({:.1}%)", node_id, processed, percentage);
+            }
+            info!("  Total: {} requests", total_processed);
+            info!("=========================");
+        }
+
+        // No load-difference hysteresis needed here: selecting the
+        // coordinator against the agreed Rapid membership cut (above)
+        // already keeps every node's view in sync, so there's nothing left
+        // for a heuristic threshold to protect against - just change
+        // whenever the committed-set winner differs from who we think the
+        // coordinator is today.
+        let current_coordinator = manager.get_coordinator();
+        let should_change = current_coordinator != Some(lowest_node);
+        if !should_change {
+            info!("[Node {}] Node {} is already the agreed coordinator", self.id, lowest_node);
+        }
+
+        if should_change {
+            let layout_version = self.cluster_layout.read().await.version();
+
+            if manager.quorum_mode() {
+                // Don't commit the winner until a majority of the known
+                // cluster has acknowledged this exact (term, coordinator_id);
+                // a round that falls short aborts and the next periodic
+                // election will retry with a higher term.
+                let ack_self = self.clone();
+                let ack_peer = move |peer_id: NodeId| {
+                    let self_clone = ack_self.clone();
+                    async move {
+                        let message = Message::Coordinator { node_id: lowest_node, load: lowest_load, term, layout_version };
+                        matches!(self_clone.send_secure_message_to_node(peer_id, message).await, Ok(Some(Message::Ok { .. })))
                     }
-                } else {
-                    // Current coordinator not in load list (may have failed), change
-                    true
+                };
+
+                let committed = manager
+                    .commit_with_quorum(lowest_node, lowest_load, term, layout_version, ack_peer, election::DEFAULT_ELECTION_TIMEOUT)
+                    .await;
+                if !committed {
+                    warn!(
+                        "[Node {}] Quorum failed to form for Node {} as coordinator (term {}); will retry on the next election",
+                        self.id, lowest_node, term
+                    );
                 }
+            } else if lowest_node == self.id {
+                // This node should be coordinator - announce to all
+                let send_fn = |node: NodeId, msg: Message| {
+                    let self_clone = self.clone();
+                    tokio::spawn(async move {
+                        let _ = self_clone.send_secure_message_to_node(node, msg).await;
+                    });
+                    true
+                };
+                manager.announce_coordinator(current_load, term, layout_version, send_fn);
             } else {
-                // No coordinator yet, elect one
-                true
-            };
+                // Another node should be coordinator - update locally AND broadcast to all nodes
+                manager.update_coordinator(lowest_node, lowest_load, term, layout_version);
 
-            if should_change {
-                if lowest_node == self.id {
-                    // This node should be coordinator - announce to all
-                    let send_fn = |node: NodeId, msg: Message| {
-                        let self_clone = self.clone();
-                        tokio::spawn(async move {
-                            let _ = self_clone.send_message_to_node(node, msg).await;
-                        });
-                        true
+                info!("[Node {}] Broadcasting coordinator decision: Node {} with load {:.2} (term {})",
+                      self.id, lowest_node, lowest_load, term);
+
+                // Broadcast coordinator message to ALL nodes (including the winner and this node)
+                // This ensures everyone has the same view
+                for (&peer_id, _) in &self.peer_addresses {
+                    let message = Message::Coordinator {
+                        node_id: lowest_node,
+                        load: lowest_load,
+                        term,
+                        layout_version,
                     };
-                    manager.announce_coordinator(current_load, send_fn);
-                } else {
-                    // Another node should be coordinator - update locally AND broadcast to all nodes
-                    manager.update_coordinator(lowest_node, lowest_load);
-
-                    info!("[Node {}] Broadcasting coordinator decision: Node {} with load {:.2}",
-                          self.id, lowest_node, lowest_load);
-
-                    // Broadcast coordinator message to ALL nodes (including the winner and this node)
-                    // This ensures everyone has the same view
-                    for (&peer_id, _) in &self.peer_addresses {
-                        let message = Message::Coordinator {
-                            node_id: lowest_node,
-                            load: lowest_load,
-                        };
-                        let self_clone = self.clone();
-                        tokio::spawn(async move {
-                            let _ = self_clone.send_message_to_node(peer_id, message).await;
-                        });
-                    }
+                    let self_clone = self.clone();
+                    tokio::spawn(async move {
+                        let _ = self_clone.send_secure_message_to_node(peer_id, message).await;
+                    });
                 }
             }
         }
@@ -1677,36 +4375,33 @@ impl CloudNode {
 
     /// Get current node statistics
     pub async fn get_stats(&self) -> NodeStats {
+        let failed = self.failed_nodes.read().await;
+        let peer_status = self
+            .membership
+            .read()
+            .await
+            .addresses()
+            .keys()
+            .map(|&peer_id| (peer_id, !failed.contains(&peer_id)))
+            .collect();
+
+        let manager = self.election_manager.lock().await;
+        let state = self.state.read().await.clone();
+        let total_bytes =
+            (self.capability.read().await.capacity * STORE_BYTES_PER_CAPACITY_UNIT as f64).max(0.0) as u64;
+        let used_bytes = self.image_store.spilled_bytes_on_disk();
         NodeStats {
             id: self.id,
-            state: self.state.read().await.clone(),
-            load: *self.current_load.read().await,
-            queue_length: *self.active_requests.read().await,
+            draining: state == NodeState::Draining,
+            state,
+            load: self.current_load(),
+            queue_length: self.active_requests.load(Ordering::Relaxed),
             processed_requests: *self.processed_requests.read().await,
-            is_coordinator: self.election_manager.lock().await.is_coordinator(),
-        }
-    }
-}
-
-impl Clone for CloudNode {
-    fn clone(&self) -> Self {
-        Self {
-            id: self.id,
-            address: self.address.clone(),
-            state: Arc::clone(&self.state),
-            election_manager: Arc::clone(&self.election_manager),
-            current_load: Arc::clone(&self.current_load),
-            active_requests: Arc::clone(&self.active_requests),
-            peer_addresses: self.peer_addresses.clone(),
-            processed_requests: Arc::clone(&self.processed_requests),
-            active_sessions: Arc::clone(&self.active_sessions),
-            stored_images: Arc::clone(&self.stored_images),
-            chunk_reassembler: Arc::clone(&self.chunk_reassembler),
-            in_flight_requests: Arc::clone(&self.in_flight_requests),
-            chunk_cache: Arc::clone(&self.chunk_cache),
-            last_heartbeat: Arc::clone(&self.last_heartbeat),
-            failed_nodes: Arc::clone(&self.failed_nodes),
-            peer_load_cache: Arc::clone(&self.peer_load_cache),
+            is_coordinator: manager.is_coordinator(),
+            coordinator_term: manager.current_term(),
+            peer_status,
+            layout_version: self.cluster_layout.read().await.version(),
+            store_capacity_bytes: (total_bytes.saturating_sub(used_bytes), total_bytes),
         }
     }
 }
@@ -1719,4 +4414,22 @@ pub struct NodeStats {
     pub queue_length: usize,
     pub processed_requests: usize,
     pub is_coordinator: bool,
+    /// Term of the coordinator this node currently recognizes, so a
+    /// split-brain resolving (two differing terms converging to the
+    /// higher one) is visible across nodes' status printouts.
+    pub coordinator_term: u64,
+    /// (peer_id, reachable) for every peer this node currently knows about,
+    /// reachable meaning it hasn't missed enough heartbeats to be marked failed.
+    pub peer_status: Vec<(NodeId, bool)>,
+    /// Version of this node's `ClusterLayout` (see `cluster_layout.rs`),
+    /// so a status printout can spot two nodes disagreeing on placement.
+    pub layout_version: u64,
+    /// Whether this node is draining (see `NodeState::Draining` /
+    /// `start_draining`) - still up, but winding down.
+    pub draining: bool,
+    /// (available, total) bytes of data-store capacity, derived from this
+    /// node's advertised `NodeCapability::capacity`
+    /// (`STORE_BYTES_PER_CAPACITY_UNIT`) minus what `image_store` currently
+    /// has spilled to disk.
+    pub store_capacity_bytes: (u64, u64),
 }
@@ -0,0 +1,164 @@
+use crate::messages::NodeId;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A peer record as persisted to disk, so a restarted node can reconnect
+/// without being relaunched with the exact right CLI peer list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedPeer {
+    pub node_id: NodeId,
+    pub last_known_address: String,
+    pub last_seen_unix: i64,
+}
+
+/// On-disk table of every peer this node has ever discovered/connected to,
+/// serialized as a simple JSON file keyed by `NodeId`. Kept intentionally
+/// simple (no embedded SQLite dependency) in keeping with the rest of the
+/// crate's disk usage, which sticks to JSON via serde - the one deliberate
+/// exception is `log_store`, which needs real queries (ranges, ordering)
+/// over a growing history that a flat JSON file can't serve well.
+pub struct PeerStore {
+    path: PathBuf,
+    peers: HashMap<NodeId, PersistedPeer>,
+}
+
+/// Entries not seen within this window are considered stale and pruned on load.
+const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+impl PeerStore {
+    /// Load the store from `path`, merging in any CLI-provided bootstrap
+    /// addresses so a first run (with no file yet) still has somewhere to
+    /// start from.
+    pub fn load(path: &Path, bootstrap: &HashMap<NodeId, String>) -> Self {
+        let mut peers: HashMap<NodeId, PersistedPeer> = match std::fs::read_to_string(path) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(peers) => {
+                    info!("Loaded peer store from {} ({} entries)", path.display(), map_len(&peers));
+                    peers
+                }
+                Err(e) => {
+                    warn!("Peer store at {} is corrupt ({}), starting fresh", path.display(), e);
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        for (&node_id, addr) in bootstrap {
+            peers.entry(node_id).or_insert_with(|| PersistedPeer {
+                node_id,
+                last_known_address: addr.clone(),
+                last_seen_unix: now,
+            });
+        }
+
+        let mut store = Self {
+            path: path.to_path_buf(),
+            peers,
+        };
+        store.prune_stale();
+        store
+    }
+
+    /// Record that we just successfully reached `node_id` at `address`.
+    pub fn record_seen(&mut self, node_id: NodeId, address: &str) {
+        let now = chrono::Utc::now().timestamp();
+        self.peers.insert(
+            node_id,
+            PersistedPeer {
+                node_id,
+                last_known_address: address.to_string(),
+                last_seen_unix: now,
+            },
+        );
+    }
+
+    /// Peers ordered most-recently-seen first, so reconnection logic can
+    /// prioritize the addresses most likely to still be reachable.
+    pub fn peers_by_recency(&self) -> Vec<PersistedPeer> {
+        let mut peers: Vec<PersistedPeer> = self.peers.values().cloned().collect();
+        peers.sort_by(|a, b| b.last_seen_unix.cmp(&a.last_seen_unix));
+        peers
+    }
+
+    fn prune_stale(&mut self) {
+        let now = chrono::Utc::now().timestamp();
+        let cutoff = now - STALE_AFTER.as_secs() as i64;
+        let before = self.peers.len();
+        self.peers.retain(|_, p| p.last_seen_unix >= cutoff);
+        let pruned = before - self.peers.len();
+        if pruned > 0 {
+            info!("Pruned {} stale peer(s) from peer store", pruned);
+        }
+    }
+
+    /// Persist the current table to disk. Errors are logged but non-fatal:
+    /// losing the on-disk cache just means a slower rediscovery next boot.
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(&self.peers) {
+            Ok(json) => {
+                if let Some(parent) = self.path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Err(e) = std::fs::write(&self.path, json) {
+                    warn!("Failed to write peer store to {}: {}", self.path.display(), e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize peer store: {}", e),
+        }
+    }
+}
+
+fn map_len(peers: &HashMap<NodeId, PersistedPeer>) -> usize {
+    peers.len()
+}
+
+/// Default on-disk location for a node's peer store, namespaced by its own
+/// bind address so multiple local demo nodes don't clobber each other.
+pub fn default_peer_store_path(node_address: &str) -> PathBuf {
+    let safe_name = node_address.replace([':', '.'], "_");
+    PathBuf::from(format!(".peer_store_{}.json", safe_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn record_seen_then_peers_by_recency_orders_newest_first() {
+        let dir = std::env::temp_dir().join(format!("peer_store_test_{}", std::process::id()));
+        let path = dir.join("peers.json");
+
+        let bootstrap = HashMap::new();
+        let mut store = PeerStore::load(&path, &bootstrap);
+
+        store.record_seen(1, "127.0.0.1:8001");
+        std::thread::sleep(Duration::from_millis(1100));
+        store.record_seen(2, "127.0.0.1:8002");
+
+        let ordered = store.peers_by_recency();
+        assert_eq!(ordered[0].node_id, 2);
+        assert_eq!(ordered[1].node_id, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn bootstrap_seeds_entries_not_already_known() {
+        let dir = std::env::temp_dir().join(format!("peer_store_test2_{}", std::process::id()));
+        let path = dir.join("peers.json");
+
+        let mut bootstrap = HashMap::new();
+        bootstrap.insert(5, "127.0.0.1:8005".to_string());
+
+        let store = PeerStore::load(&path, &bootstrap);
+        assert_eq!(store.peers_by_recency().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,167 @@
+//! Phi-accrual failure detection (Hayashibara et al., 2004). Replaces a
+//! binary "stale for longer than X seconds => failed" check with a
+//! continuous suspicion level derived from each peer's own inter-arrival
+//! history, so a peer that's merely slow under variable network latency
+//! isn't treated the same as one that's actually gone silent.
+//!
+//! Each monitored peer gets a `PhiDetector` that remembers a sliding window
+//! of recent gossip inter-arrival times (see `merge` in `gossip.rs`, which
+//! calls `record_arrival` every time a peer's `GossipRecord` update index
+//! advances; `Node::failure_detector_task` reads the resulting `phi`) and
+//! its running mean/variance. `phi` treats those
+//! inter-arrivals as normally distributed and asks: given how long it's
+//! been since the last one, how surprising is that under the observed
+//! distribution? `phi >= PHI_FAILURE_THRESHOLD` is the new failure
+//! condition in place of the old fixed `GOSSIP_TIMEOUT`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How many recent inter-arrival samples to keep per peer. Bounded so a
+/// long-lived node doesn't grow this table forever; recent samples matter
+/// far more than ones from an hour ago anyway.
+const WINDOW_SIZE: usize = 20;
+
+/// phi crossing this value means "suspicious enough to call it": per
+/// Hayashibara et al., phi = 8 corresponds to roughly a 1-in-10^8 chance
+/// the peer is merely running late given its historical inter-arrival
+/// distribution, which tolerates occasional slow gossip rounds without
+/// false-positiving on them.
+pub const PHI_FAILURE_THRESHOLD: f64 = 8.0;
+
+/// Floor on the standard deviation used in the phi calculation. A peer
+/// that's gossiped like clockwork so far would otherwise have a variance
+/// of (near) zero, making phi blow up to infinity on the very first
+/// slightly-late arrival.
+const MIN_STD_DEV_MILLIS: f64 = 50.0;
+
+/// Tracks one peer's gossip inter-arrival history and derives a suspicion
+/// level from it.
+#[derive(Debug, Clone)]
+pub struct PhiDetector {
+    last_arrival: Option<Instant>,
+    intervals_millis: VecDeque<f64>,
+}
+
+impl PhiDetector {
+    pub fn new() -> Self {
+        Self {
+            last_arrival: None,
+            intervals_millis: VecDeque::with_capacity(WINDOW_SIZE),
+        }
+    }
+
+    /// Record that we just heard something new from this peer (its
+    /// `GossipRecord` update index advanced). Feeds the gap since the
+    /// previous arrival into the sliding window.
+    pub fn record_arrival(&mut self, now: Instant) {
+        if let Some(last) = self.last_arrival {
+            if self.intervals_millis.len() == WINDOW_SIZE {
+                self.intervals_millis.pop_front();
+            }
+            self.intervals_millis.push_back(now.duration_since(last).as_secs_f64() * 1000.0);
+        }
+        self.last_arrival = Some(now);
+    }
+
+    /// Current suspicion level given the current time. `0.0` until we have
+    /// at least one recorded interval to judge against (a peer we've never
+    /// heard from isn't "suspicious", it's just unknown - `failure_detector_task`
+    /// handles that case separately).
+    pub fn phi(&self, now: Instant) -> f64 {
+        let last = match self.last_arrival {
+            Some(last) => last,
+            None => return 0.0,
+        };
+        if self.intervals_millis.is_empty() {
+            return 0.0;
+        }
+
+        let mean = self.intervals_millis.iter().sum::<f64>() / self.intervals_millis.len() as f64;
+        let variance = self
+            .intervals_millis
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / self.intervals_millis.len() as f64;
+        let std_dev = variance.sqrt().max(MIN_STD_DEV_MILLIS);
+
+        let elapsed_millis = now.duration_since(last).as_secs_f64() * 1000.0;
+        let p_later = 1.0 - normal_cdf(elapsed_millis, mean, std_dev);
+        // p_later underflows to 0.0 once we're many std-devs past the mean,
+        // which would otherwise take -log10 to infinity/NaN; clamp it to
+        // the smallest representable positive value instead, which caps
+        // phi at a large-but-finite number.
+        -p_later.max(f64::MIN_POSITIVE).log10()
+    }
+}
+
+impl Default for PhiDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CDF of a normal distribution with the given mean/std-dev, via the
+/// complementary error function identity `Phi(x) = 0.5 * erfc(-(x-mean) / (std_dev * sqrt(2)))`.
+fn normal_cdf(x: f64, mean: f64, std_dev: f64) -> f64 {
+    0.5 * erfc(-(x - mean) / (std_dev * std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz & Stegun 7.1.26 rational approximation of the complementary
+/// error function - good to ~1.5e-7 absolute error, plenty for a suspicion
+/// score that only needs to be right to within a threshold comparison.
+fn erfc(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    const A1: f64 = 0.254829592;
+    const A2: f64 = -0.284496736;
+    const A3: f64 = 1.421413741;
+    const A4: f64 = -1.453152027;
+    const A5: f64 = 1.061405429;
+    const P: f64 = 0.3275911;
+
+    let t = 1.0 / (1.0 + P * x);
+    let poly = ((((A5 * t + A4) * t + A3) * t + A2) * t + A1) * t;
+    let erf = 1.0 - poly * (-x * x).exp();
+
+    1.0 - sign * erf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phi_is_zero_with_no_history() {
+        let detector = PhiDetector::new();
+        assert_eq!(detector.phi(Instant::now()), 0.0);
+    }
+
+    #[test]
+    fn phi_stays_low_for_on_time_arrivals() {
+        let mut detector = PhiDetector::new();
+        let base = Instant::now();
+        for i in 0..10 {
+            detector.record_arrival(base + Duration::from_millis(i * 2000));
+        }
+        let last = base + Duration::from_millis(9 * 2000);
+        // Checking right on schedule (2s after the last arrival, same as
+        // every interval so far) should look unsurprising.
+        assert!(detector.phi(last + Duration::from_millis(2000)) < PHI_FAILURE_THRESHOLD);
+    }
+
+    #[test]
+    fn phi_climbs_past_threshold_once_a_peer_goes_quiet() {
+        let mut detector = PhiDetector::new();
+        let base = Instant::now();
+        for i in 0..10 {
+            detector.record_arrival(base + Duration::from_millis(i * 2000));
+        }
+        let last = base + Duration::from_millis(9 * 2000);
+        // Ten times the regular gossip interval with no arrival at all is
+        // well past what the observed distribution would explain.
+        assert!(detector.phi(last + Duration::from_millis(20_000)) >= PHI_FAILURE_THRESHOLD);
+    }
+}
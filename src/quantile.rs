@@ -0,0 +1,242 @@
+//! P² (piecewise-parabolic) streaming quantile estimator (Jain & Chlamtac,
+//! 1985). Tracks one target quantile from a stream of samples in O(1)
+//! memory and O(1) per-sample update - five marker heights and positions -
+//! instead of keeping every sample around to sort on each read. Used by
+//! `StressTestMetrics` to track p50/p90/p95/p99 request latency without
+//! growing unbounded over a long stress run.
+
+use serde::{Deserialize, Serialize};
+
+/// Tracks a single quantile `p` (e.g. `0.95` for p95) from a stream of
+/// `f64` samples.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P2Estimator {
+    p: f64,
+    /// Samples seen so far. The first 5 seed the marker heights directly;
+    /// from the 6th sample on, markers are adjusted incrementally.
+    count: u64,
+    /// Raw samples, kept only until `count` reaches 5.
+    init_buffer: Vec<f64>,
+    /// Marker heights q[1..5] (0-indexed here as heights[0..5]).
+    heights: [f64; 5],
+    /// Marker positions n[1..5].
+    positions: [f64; 5],
+    /// Desired marker positions n'[1..5], advanced by `increments` each
+    /// sample.
+    desired_positions: [f64; 5],
+    /// Per-sample increments to `desired_positions`: 0, p/2, p, (1+p)/2, 1.
+    increments: [f64; 5],
+}
+
+impl P2Estimator {
+    pub fn new(p: f64) -> Self {
+        Self {
+            p,
+            count: 0,
+            init_buffer: Vec::with_capacity(5),
+            heights: [0.0; 5],
+            positions: [0.0; 5],
+            desired_positions: [0.0; 5],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    /// Feed in one more sample, updating the marker heights/positions.
+    pub fn observe(&mut self, x: f64) {
+        self.count += 1;
+
+        if self.count <= 5 {
+            self.init_buffer.push(x);
+            if self.count == 5 {
+                self.init_buffer.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                for i in 0..5 {
+                    self.heights[i] = self.init_buffer[i];
+                    self.positions[i] = (i + 1) as f64;
+                }
+                self.desired_positions =
+                    [1.0, 1.0 + 2.0 * self.p, 1.0 + 4.0 * self.p, 3.0 + 2.0 * self.p, 5.0];
+            }
+            return;
+        }
+
+        let k = if x < self.heights[0] {
+            self.heights[0] = x;
+            0
+        } else if x >= self.heights[4] {
+            self.heights[4] = x;
+            3
+        } else {
+            let mut cell = 3;
+            for i in 0..4 {
+                if self.heights[i] <= x && x < self.heights[i + 1] {
+                    cell = i;
+                    break;
+                }
+            }
+            cell
+        };
+
+        for position in self.positions.iter_mut().skip(k + 1) {
+            *position += 1.0;
+        }
+        for i in 0..5 {
+            self.desired_positions[i] += self.increments[i];
+        }
+
+        for i in 1..4 {
+            let d = self.desired_positions[i] - self.positions[i];
+            if (d >= 1.0 && self.positions[i + 1] - self.positions[i] > 1.0)
+                || (d <= -1.0 && self.positions[i - 1] - self.positions[i] < -1.0)
+            {
+                let sign = if d >= 0.0 { 1.0 } else { -1.0 };
+                let adjusted = self.parabolic(i, sign);
+                self.heights[i] = if self.heights[i - 1] < adjusted && adjusted < self.heights[i + 1] {
+                    adjusted
+                } else {
+                    self.linear(i, sign)
+                };
+                self.positions[i] += sign;
+            }
+        }
+    }
+
+    /// Piecewise-parabolic prediction for marker `i` moving by `d` (±1).
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    /// Linear fallback, used when the parabolic estimate would leave
+    /// marker `i` out of order relative to its neighbors.
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        let j = (i as f64 + d) as usize;
+        q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+    }
+
+    /// Fold another estimator's state into this one. P² markers summarize a
+    /// stream rather than a sample set, so they aren't exactly mergeable;
+    /// this is an approximation, not a re-derivation of the true combined
+    /// quantile:
+    /// - if one side never got past the raw-sample init buffer, its
+    ///   samples are replayed through `observe` on (or into a clone of)
+    ///   the other side, which is exact;
+    /// - once both sides have stable markers, heights are combined as a
+    ///   count-weighted average and positions/desired positions are
+    ///   rescaled to the combined sample count.
+    pub fn merge(&mut self, other: &P2Estimator) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+        if other.count < 5 {
+            for &x in &other.init_buffer {
+                self.observe(x);
+            }
+            return;
+        }
+        if self.count < 5 {
+            let buffered = self.init_buffer.clone();
+            *self = other.clone();
+            for x in buffered {
+                self.observe(x);
+            }
+            return;
+        }
+
+        let combined = self.count + other.count;
+        let (w_self, w_other) = (self.count as f64 / combined as f64, other.count as f64 / combined as f64);
+        for i in 0..5 {
+            self.heights[i] = self.heights[i] * w_self + other.heights[i] * w_other;
+            let ideal = 1.0 + (combined - 1) as f64 * self.increments[i];
+            self.positions[i] = ideal;
+            self.desired_positions[i] = ideal;
+        }
+        self.count = combined;
+    }
+
+    /// Current estimate of the target quantile, or `None` before the first
+    /// sample has been observed.
+    pub fn value(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else if self.count < 5 {
+            let mut sorted = self.init_buffer.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let idx = (((sorted.len() - 1) as f64) * self.p).round() as usize;
+            Some(sorted[idx])
+        } else {
+            Some(self.heights[2])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converges_close_to_true_percentile_on_uniform_data() {
+        let mut estimator = P2Estimator::new(0.95);
+        for i in 1..=1000u64 {
+            estimator.observe(i as f64);
+        }
+        // True p95 of 1..=1000 is 950; P² is an approximation, allow slack.
+        let value = estimator.value().unwrap();
+        assert!((value - 950.0).abs() < 15.0, "p95 estimate {} too far from 950", value);
+    }
+
+    #[test]
+    fn reports_none_before_any_samples() {
+        assert_eq!(P2Estimator::new(0.5).value(), None);
+    }
+
+    #[test]
+    fn handles_fewer_than_five_samples() {
+        let mut estimator = P2Estimator::new(0.5);
+        estimator.observe(10.0);
+        estimator.observe(20.0);
+        assert!(estimator.value().is_some());
+    }
+
+    #[test]
+    fn merging_shards_is_close_to_observing_everything_in_one() {
+        let mut whole = P2Estimator::new(0.95);
+        let mut shard_a = P2Estimator::new(0.95);
+        let mut shard_b = P2Estimator::new(0.95);
+        for i in 1..=1000u64 {
+            whole.observe(i as f64);
+            if i % 2 == 0 {
+                shard_a.observe(i as f64);
+            } else {
+                shard_b.observe(i as f64);
+            }
+        }
+        shard_a.merge(&shard_b);
+
+        let merged = shard_a.value().unwrap();
+        let reference = whole.value().unwrap();
+        assert!(
+            (merged - reference).abs() < 50.0,
+            "merged p95 {} too far from single-stream p95 {}",
+            merged,
+            reference
+        );
+    }
+
+    #[test]
+    fn merging_into_an_empty_estimator_is_a_clone() {
+        let mut empty = P2Estimator::new(0.5);
+        let mut populated = P2Estimator::new(0.5);
+        for i in 1..=10u64 {
+            populated.observe(i as f64);
+        }
+        empty.merge(&populated);
+        assert_eq!(empty.value(), populated.value());
+    }
+}
@@ -0,0 +1,550 @@
+//! Rapid-style consistent membership, replacing the ad hoc per-node
+//! coordinator hysteresis in `node.rs`'s `trigger_election` with a single
+//! agreed-upon view of the member set computed *before* any coordinator
+//! election runs. Modeled on the Rapid protocol (Suresh et al., "Rapid:
+//! Scaling Membership for Internet-Scale Clusters", 2018):
+//!
+//! - Each node is assigned `K` "subjects" to monitor in a `K`-regular
+//!   expander topology (`ExpanderTopology`), instead of every node
+//!   watching every other node.
+//! - A subject's edge status is only considered conclusive once at least
+//!   `H` of its observers report the same status (`AlertAggregator`); a
+//!   count below `L` is noise, and `[L, H)` is an "unstable" zone that
+//!   simply waits for more corroboration.
+//! - Conclusive reports accumulate into one batch (`CutBatcher`) so
+//!   simultaneously-detected changes commit together as a single
+//!   `MultiNodeCut` rather than one at a time.
+//! - The cut is committed via a fast single-decree agreement: a 3/4
+//!   fast-path quorum if the cluster responds quickly and unanimously,
+//!   classic plain-majority otherwise (`MembershipService::propose_cut`).
+//!
+//! Once a cut commits, `select_coordinator` runs the existing lowest-load
+//! selection deterministically against the committed member set, so two
+//! nodes computing it independently always agree - no load-difference
+//! hysteresis heuristic needed to prevent divergence.
+
+use crate::messages::NodeId;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// Whether an observer currently considers its subject reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeStatus {
+    Up,
+    Down,
+}
+
+/// `K`-regular-ish expander observer graph over the current member set:
+/// every node monitors up to `K` "subjects". For even `K` edges are
+/// symmetric (if A monitors B, B also monitors A), matching Rapid's use of
+/// undirected edges in practice; for odd `K` the per-node cap in `new` can
+/// leave an edge recorded on one side only. Built deterministically from the sorted
+/// member list, so every node derives the identical topology from the
+/// identical member set with no coordination needed - the only shared
+/// input is which nodes are currently believed to be members.
+pub struct ExpanderTopology {
+    k: usize,
+    edges: HashMap<NodeId, BTreeSet<NodeId>>,
+}
+
+impl ExpanderTopology {
+    /// `members` need not be sorted or deduplicated. Rebuilt from scratch
+    /// whenever the member set changes - cheap at the cluster sizes this
+    /// crate targets (O(members * k)).
+    pub fn new(members: &[NodeId], k: usize) -> Self {
+        let mut sorted: Vec<NodeId> = members.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let mut edges: HashMap<NodeId, BTreeSet<NodeId>> =
+            sorted.iter().map(|&id| (id, BTreeSet::new())).collect();
+
+        if sorted.len() < 2 {
+            return Self { k, edges };
+        }
+
+        // Seed deterministically from the member set itself so every node
+        // builds the same topology without exchanging it.
+        let mut hasher = DefaultHasher::new();
+        sorted.hash(&mut hasher);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(hasher.finish());
+
+        // Each pass lays down one random Hamiltonian cycle (a ring), which
+        // contributes up to 2 edges per node (fewer where rings reuse a
+        // pair); `k.div_ceil(2)` rings get every node close to degree `k`.
+        // For odd `k` that can reach `k + 1`, and the per-node cap below
+        // trims the extra edge from that node's own set only.
+        for _ in 0..k.div_ceil(2).max(1) {
+            let mut ring = sorted.clone();
+            ring.shuffle(&mut rng);
+            for (i, &node) in ring.iter().enumerate() {
+                let next = ring[(i + 1) % ring.len()];
+                if next != node {
+                    edges.get_mut(&node).unwrap().insert(next);
+                    edges.get_mut(&next).unwrap().insert(node);
+                }
+            }
+        }
+
+        for neighbors in edges.values_mut() {
+            while neighbors.len() > k {
+                let last = *neighbors.iter().next_back().unwrap();
+                neighbors.remove(&last);
+            }
+        }
+
+        Self { k, edges }
+    }
+
+    /// The nodes this node is responsible for monitoring (and, where edges
+    /// are symmetric - always for even `K` - the nodes monitoring it back).
+    pub fn subjects_of(&self, node_id: NodeId) -> Vec<NodeId> {
+        self.edges.get(&node_id).map(|s| s.iter().copied().collect()).unwrap_or_default()
+    }
+
+    pub fn degree(&self, node_id: NodeId) -> usize {
+        self.edges.get(&node_id).map(|s| s.len()).unwrap_or(0)
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+}
+
+/// Outcome of folding one more alert into `AlertAggregator::report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportOutcome {
+    /// Fewer than `L` distinct observers have reported this status so far.
+    Noise,
+    /// At least `L` but fewer than `H` - waiting for more corroboration.
+    Unstable,
+    /// `H` or more distinct observers agree - ready to propose. Returned
+    /// only once per subject/status transition; see `AlreadyProposed`.
+    Stable,
+    /// `H` was already reached for this exact subject/status by an
+    /// earlier call - nothing new to propose.
+    AlreadyProposed,
+}
+
+/// Accumulates alerts about one subject's edge status from its distinct
+/// observers, applying Rapid's `H` (stable) / `L` (unstable floor)
+/// thresholds.
+pub struct AlertAggregator {
+    h: usize,
+    l: usize,
+    reports: HashMap<NodeId, HashMap<EdgeStatus, HashSet<NodeId>>>,
+    already_proposed: HashMap<NodeId, EdgeStatus>,
+}
+
+impl AlertAggregator {
+    pub fn new(h: usize, l: usize) -> Self {
+        assert!(l <= h, "the unstable floor L ({l}) must not exceed the stable threshold H ({h})");
+        Self {
+            h,
+            l,
+            reports: HashMap::new(),
+            already_proposed: HashMap::new(),
+        }
+    }
+
+    pub fn report(&mut self, observer: NodeId, subject: NodeId, status: EdgeStatus) -> ReportOutcome {
+        if self.already_proposed.get(&subject) == Some(&status) {
+            return ReportOutcome::AlreadyProposed;
+        }
+
+        let observers = self.reports.entry(subject).or_default().entry(status).or_default();
+        observers.insert(observer);
+        let count = observers.len();
+
+        if count >= self.h {
+            self.already_proposed.insert(subject, status);
+            ReportOutcome::Stable
+        } else if count >= self.l {
+            ReportOutcome::Unstable
+        } else {
+            ReportOutcome::Noise
+        }
+    }
+
+    /// Drop all accumulated reports for a subject - called once a status
+    /// change for it actually commits, so stale reports from before the
+    /// change don't immediately re-trigger against the new state.
+    pub fn clear(&mut self, subject: NodeId) {
+        self.reports.remove(&subject);
+        self.already_proposed.remove(&subject);
+    }
+}
+
+/// A batch of membership changes proposed together, so simultaneously
+/// detected changes commit as one atomic view change instead of dribbling
+/// in one at a time (and risking a different coordinator decision after
+/// each).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MultiNodeCut {
+    pub to_add: BTreeSet<NodeId>,
+    pub to_remove: BTreeSet<NodeId>,
+}
+
+impl MultiNodeCut {
+    pub fn is_empty(&self) -> bool {
+        self.to_add.is_empty() && self.to_remove.is_empty()
+    }
+}
+
+/// Collects stable proposals until the round closes, so one
+/// `MultiNodeCut` carries every change that became conclusive in the same
+/// window rather than one commit round per node.
+#[derive(Debug, Default)]
+struct CutBatcher {
+    pending: MultiNodeCut,
+}
+
+impl CutBatcher {
+    fn propose_down(&mut self, subject: NodeId) {
+        self.pending.to_remove.insert(subject);
+    }
+
+    fn propose_up(&mut self, subject: NodeId) {
+        self.pending.to_add.insert(subject);
+    }
+
+    fn take(&mut self) -> MultiNodeCut {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Fast-path threshold: Rapid commits in one round trip if at least 3/4 of
+/// the member set (rounded up) proposes the identical cut.
+pub fn fast_path_quorum_size(member_count: usize) -> usize {
+    (member_count * 3).div_ceil(4).max(1)
+}
+
+/// Classic fallback threshold: a plain majority, matching
+/// `election::quorum_size`.
+pub fn classic_quorum_size(member_count: usize) -> usize {
+    member_count / 2 + 1
+}
+
+/// Pick the committed member with the lowest load (ties broken by lowest
+/// id), the same rule `trigger_election` always used - but now run only
+/// against `committed`, so two nodes with different raw `loads` maps
+/// (e.g. one hasn't heard a LoadResponse from a node the other has) still
+/// agree, as long as they agree on membership. Nodes in `loads` but not
+/// yet in `committed` (not-yet-agreed joiners) are ignored; a committed
+/// member missing from `loads` (no response this round) is also ignored,
+/// same as the old code treated no-response peers.
+pub fn select_coordinator(committed: &HashSet<NodeId>, loads: &HashMap<NodeId, f64>) -> Option<NodeId> {
+    loads
+        .iter()
+        .filter(|(id, _)| committed.contains(id))
+        // load is peer-reported, off the wire - a NaN must not panic the
+        // coordinator pick.
+        .min_by(|a, b| a.1.total_cmp(b.1).then_with(|| a.0.cmp(b.0)))
+        .map(|(&id, _)| id)
+}
+
+/// Ties the expander topology, alert aggregation, and cut batching
+/// together with the currently committed member set, so a `CloudNode` has
+/// one place to report edge alerts, propose/commit cuts, and ask what
+/// today's agreed-upon cluster is.
+pub struct MembershipService {
+    node_id: NodeId,
+    k: usize,
+    topology: ExpanderTopology,
+    aggregator: AlertAggregator,
+    batcher: CutBatcher,
+    committed: HashSet<NodeId>,
+}
+
+impl MembershipService {
+    pub fn new(node_id: NodeId, initial_members: HashSet<NodeId>, k: usize, h: usize, l: usize) -> Self {
+        let mut committed = initial_members;
+        committed.insert(node_id);
+        let members: Vec<NodeId> = committed.iter().copied().collect();
+
+        Self {
+            node_id,
+            k,
+            topology: ExpanderTopology::new(&members, k),
+            aggregator: AlertAggregator::new(h, l),
+            batcher: CutBatcher::default(),
+            committed,
+        }
+    }
+
+    /// The subjects this node is responsible for probing.
+    pub fn subjects_to_monitor(&self) -> Vec<NodeId> {
+        self.topology.subjects_of(self.node_id)
+    }
+
+    pub fn committed_members(&self) -> HashSet<NodeId> {
+        self.committed.clone()
+    }
+
+    /// Record one alert from `observer` about `subject`'s edge status,
+    /// folding it into the pending `MultiNodeCut` the moment it becomes
+    /// stable.
+    pub fn report_alert(&mut self, observer: NodeId, subject: NodeId, status: EdgeStatus) -> ReportOutcome {
+        let outcome = self.aggregator.report(observer, subject, status);
+        if outcome == ReportOutcome::Stable {
+            match status {
+                EdgeStatus::Down => self.batcher.propose_down(subject),
+                EdgeStatus::Up => self.batcher.propose_up(subject),
+            }
+        }
+        outcome
+    }
+
+    /// Take whatever proposals accumulated this round (empty if nothing
+    /// reached `H` since the last call), for the caller to broadcast as a
+    /// `MultiNodeCut` proposal.
+    pub fn take_pending_cut(&mut self) -> MultiNodeCut {
+        self.batcher.take()
+    }
+
+    /// Broadcast `cut` to every other committed member via `ack_peer` and
+    /// commit it locally once enough of them ack it back: Rapid's fast
+    /// single-decree agreement. `fast_path_quorum_size` acks within
+    /// `fast_timeout` commits immediately; otherwise the wait extends to
+    /// `classic_timeout` and `classic_quorum_size` (a plain majority)
+    /// suffices. Returns `true` if the cut was committed - callers should
+    /// retry with a fresh round on `false` rather than partially apply it.
+    pub async fn propose_cut<F, Fut>(
+        &mut self,
+        cut: MultiNodeCut,
+        ack_peer: F,
+        fast_timeout: Duration,
+        classic_timeout: Duration,
+    ) -> bool
+    where
+        F: Fn(NodeId) -> Fut,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        if cut.is_empty() {
+            return false;
+        }
+
+        let peers: Vec<NodeId> = self.committed.iter().copied().filter(|&id| id != self.node_id).collect();
+        let member_count = peers.len() + 1;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<bool>();
+        for peer in peers {
+            let tx = tx.clone();
+            let fut = ack_peer(peer);
+            tokio::spawn(async move {
+                let _ = tx.send(fut.await);
+            });
+        }
+        drop(tx);
+
+        let fast_needed = fast_path_quorum_size(member_count);
+        let classic_needed = classic_quorum_size(member_count);
+
+        let mut acked = 1; // we agree with our own proposal
+        let fast_deadline = tokio::time::Instant::now() + fast_timeout;
+        while acked < fast_needed {
+            let remaining = fast_deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Some(true)) => acked += 1,
+                Ok(Some(false)) => {}
+                _ => break,
+            }
+        }
+
+        if acked >= fast_needed {
+            self.commit(&cut);
+            return true;
+        }
+
+        let classic_deadline = tokio::time::Instant::now() + classic_timeout;
+        while acked < classic_needed {
+            let remaining = classic_deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Some(true)) => acked += 1,
+                Ok(Some(false)) => {}
+                _ => break,
+            }
+        }
+
+        if acked >= classic_needed {
+            self.commit(&cut);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Apply an agreed cut: update the member set, clear stale alert state
+    /// for every node that changed (so leftover reports from before the
+    /// change don't immediately re-trigger), and rebuild the expander
+    /// topology for the new member set.
+    pub fn commit(&mut self, cut: &MultiNodeCut) {
+        for &id in &cut.to_remove {
+            self.committed.remove(&id);
+            self.aggregator.clear(id);
+        }
+        for &id in &cut.to_add {
+            self.committed.insert(id);
+            self.aggregator.clear(id);
+        }
+
+        let members: Vec<NodeId> = self.committed.iter().copied().collect();
+        self.topology = ExpanderTopology::new(&members, self.k);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topology_is_deterministic_for_the_same_member_set() {
+        let members: Vec<NodeId> = (1..=10).collect();
+        let a = ExpanderTopology::new(&members, 4);
+        let b = ExpanderTopology::new(&members, 4);
+
+        for &id in &members {
+            assert_eq!(a.subjects_of(id), b.subjects_of(id));
+        }
+    }
+
+    #[test]
+    fn every_node_has_at_least_one_subject_and_at_most_k() {
+        let members: Vec<NodeId> = (1..=12).collect();
+        let topology = ExpanderTopology::new(&members, 4);
+
+        for &id in &members {
+            let degree = topology.degree(id);
+            assert!(degree >= 1, "node {id} has no monitors");
+            assert!(degree <= 4, "node {id} has degree {degree} > k");
+        }
+    }
+
+    #[test]
+    fn report_transitions_noise_unstable_stable_then_already_proposed() {
+        let mut aggregator = AlertAggregator::new(3, 2);
+
+        assert_eq!(aggregator.report(10, 1, EdgeStatus::Down), ReportOutcome::Noise);
+        assert_eq!(aggregator.report(11, 1, EdgeStatus::Down), ReportOutcome::Unstable);
+        assert_eq!(aggregator.report(12, 1, EdgeStatus::Down), ReportOutcome::Stable);
+        assert_eq!(aggregator.report(13, 1, EdgeStatus::Down), ReportOutcome::AlreadyProposed);
+    }
+
+    #[test]
+    fn clearing_a_subject_resets_its_reports() {
+        let mut aggregator = AlertAggregator::new(2, 1);
+        assert_eq!(aggregator.report(1, 5, EdgeStatus::Down), ReportOutcome::Unstable);
+        assert_eq!(aggregator.report(2, 5, EdgeStatus::Down), ReportOutcome::Stable);
+
+        aggregator.clear(5);
+        assert_eq!(aggregator.report(3, 5, EdgeStatus::Down), ReportOutcome::Unstable);
+    }
+
+    #[test]
+    fn service_folds_stable_reports_into_the_pending_cut() {
+        let mut service = MembershipService::new(1, HashSet::from([2, 3, 4]), 4, 2, 1);
+
+        assert_eq!(service.report_alert(2, 4, EdgeStatus::Down), ReportOutcome::Unstable);
+        assert_eq!(service.report_alert(3, 4, EdgeStatus::Down), ReportOutcome::Stable);
+
+        let cut = service.take_pending_cut();
+        assert!(cut.to_remove.contains(&4));
+        assert!(cut.to_add.is_empty());
+
+        // Taking again without new reports yields an empty batch.
+        assert!(service.take_pending_cut().is_empty());
+    }
+
+    #[test]
+    fn commit_updates_membership_and_rebuilds_topology() {
+        let mut service = MembershipService::new(1, HashSet::from([2, 3, 4]), 4, 2, 1);
+        let mut cut = MultiNodeCut::default();
+        cut.to_remove.insert(4);
+
+        service.commit(&cut);
+
+        let members = service.committed_members();
+        assert!(!members.contains(&4));
+        assert!(members.contains(&2) && members.contains(&3) && members.contains(&1));
+    }
+
+    #[test]
+    fn fast_path_and_classic_quorum_sizes() {
+        assert_eq!(fast_path_quorum_size(4), 3);
+        assert_eq!(fast_path_quorum_size(8), 6);
+        assert_eq!(classic_quorum_size(4), 3);
+        assert_eq!(classic_quorum_size(5), 3);
+    }
+
+    #[test]
+    fn select_coordinator_ignores_nodes_outside_the_committed_set() {
+        let committed = HashSet::from([1, 2]);
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.5);
+        loads.insert(2, 0.1);
+        loads.insert(3, 0.01); // lowest load, but not a committed member
+
+        assert_eq!(select_coordinator(&committed, &loads), Some(2));
+    }
+
+    #[tokio::test]
+    async fn propose_cut_commits_on_fast_path_unanimous_acks() {
+        let mut service = MembershipService::new(1, HashSet::from([2, 3, 4]), 4, 2, 1);
+        let mut cut = MultiNodeCut::default();
+        cut.to_remove.insert(4);
+
+        let ack_peer = |_peer_id: NodeId| async move { true };
+        let committed = service
+            .propose_cut(cut, ack_peer, Duration::from_millis(200), Duration::from_millis(200))
+            .await;
+
+        assert!(committed);
+        assert!(!service.committed_members().contains(&4));
+    }
+
+    #[tokio::test]
+    async fn propose_cut_fails_without_a_classic_majority() {
+        // 5-member cluster: fast path needs 4, classic needs 3. Only one
+        // of the four peers acks, so together with self that's 2/5 - short
+        // of both quorums, so the cut is not committed.
+        let mut service = MembershipService::new(1, HashSet::from([2, 3, 4, 5]), 4, 2, 1);
+        let mut cut = MultiNodeCut::default();
+        cut.to_remove.insert(5);
+
+        let ack_peer = |peer_id: NodeId| async move { peer_id == 2 };
+        let committed = service
+            .propose_cut(cut, ack_peer, Duration::from_millis(50), Duration::from_millis(50))
+            .await;
+
+        assert!(!committed);
+        assert!(service.committed_members().contains(&5));
+    }
+
+    #[tokio::test]
+    async fn propose_cut_commits_on_classic_majority_when_fast_path_misses() {
+        let mut service = MembershipService::new(1, HashSet::from([2, 3, 4, 5]), 4, 2, 1);
+        let mut cut = MultiNodeCut::default();
+        cut.to_remove.insert(5);
+
+        // 2 of the 4 peers ack: with self that's 3/5 - short of the fast-path
+        // quorum of 4, but enough for the classic majority of 3.
+        let ack_peer = |peer_id: NodeId| async move { peer_id == 2 || peer_id == 3 };
+        let committed = service
+            .propose_cut(cut, ack_peer, Duration::from_millis(50), Duration::from_millis(200))
+            .await;
+
+        assert!(committed);
+        assert!(!service.committed_members().contains(&5));
+    }
+}
@@ -0,0 +1,178 @@
+//! Replica placement and Merkle-bucket anti-entropy for `stored_images`.
+//!
+//! Today an image only lives on whichever node processed the
+//! `SendImage`/encryption request that created it, so losing that node
+//! loses the image and its `remaining_views` quota. This module provides
+//! the two pieces `CloudNode` needs to keep `REPLICATION_FACTOR` copies
+//! alive across the cluster:
+//!
+//! - `replica_nodes`: consistent hashing over the live `NodeId` set, so an
+//!   image's replica set is deterministic and only reshuffles minimally as
+//!   nodes join/leave, rather than being a random pick every time.
+//! - Merkle-bucket hashing (`bucket_of`/`bucket_hashes`/`root_hash`): a
+//!   fixed two-level tree (root -> `BUCKET_COUNT` buckets -> leaves) rather
+//!   than an arbitrary-depth recursive one. A sync round compares the
+//!   (small, fixed-size) bucket hash map directly and only exchanges the
+//!   full records of buckets that disagree; `root_hash` condenses the map
+//!   into one value, but the sync round does not consult it. A fixed
+//!   shallow depth keeps a sync round at exactly one request/response
+//!   instead of a multi-round recursive descent, at the cost of
+//!   transferring a whole bucket's records instead of only the individual
+//!   stale leaves within it - a reasonable trade given buckets are small.
+//!
+//! Reconciliation itself (newest `timestamp` wins, `remaining_views` takes
+//! the minimum of the two sides to never hand back views that were
+//! already spent against either copy) lives in `CloudNode`'s
+//! `ReplicationSync`/`ReplicationPush` handlers, since it needs to merge
+//! into `stored_images` directly - this module only computes placement
+//! and hashes.
+
+use crate::messages::NodeId;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Number of replica nodes each image is pushed/synced to, including the
+/// node that originally processed the request.
+pub const REPLICATION_FACTOR: usize = 2;
+
+/// Minimum number of copies (including the one stored locally before any
+/// replica push goes out) required before a `SendImage` write is treated
+/// as durable - a simple majority of `REPLICATION_FACTOR`.
+pub fn write_quorum(replication_factor: usize) -> usize {
+    replication_factor / 2 + 1
+}
+
+/// Number of Merkle buckets the key space is partitioned into.
+pub const BUCKET_COUNT: u32 = 16;
+
+fn hash_u64<T: Hash>(value: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Pick up to `replication_factor` distinct nodes from `live_nodes` to hold
+/// a copy of `image_id`, via consistent hashing: every node's hash gives it
+/// a point on a ring, the image's hash gives it a point on the same ring,
+/// and the replicas are the next distinct nodes found walking the ring
+/// clockwise from that point. Deterministic for a given `image_id` and live
+/// set, and only reshuffles the handful of images whose ring neighborhood
+/// changes when a node joins or leaves - not the whole key space.
+pub fn replica_nodes(image_id: &str, live_nodes: &[NodeId], replication_factor: usize) -> Vec<NodeId> {
+    if live_nodes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ring: Vec<(u64, NodeId)> = live_nodes.iter().map(|&id| (hash_u64(id), id)).collect();
+    ring.sort_unstable_by_key(|&(point, _)| point);
+
+    let target = hash_u64(image_id);
+    let start = ring.partition_point(|&(point, _)| point < target);
+
+    let mut replicas = Vec::with_capacity(replication_factor.min(ring.len()));
+    for i in 0..ring.len() {
+        if replicas.len() == replication_factor {
+            break;
+        }
+        let (_, node_id) = ring[(start + i) % ring.len()];
+        if !replicas.contains(&node_id) {
+            replicas.push(node_id);
+        }
+    }
+    replicas
+}
+
+/// Leaf hash for a single replicated record, per the request: hash of
+/// `(image_id, remaining_views, timestamp)`.
+pub fn leaf_hash(image_id: &str, remaining_views: u32, timestamp: i64) -> u64 {
+    hash_u64((image_id, remaining_views, timestamp))
+}
+
+/// Which bucket a `(username, image_id)` key falls into.
+pub fn bucket_of(username: &str, image_id: &str) -> u32 {
+    (hash_u64((username, image_id)) % BUCKET_COUNT as u64) as u32
+}
+
+/// Build the bucket-level hash map from every local `(username, image_id,
+/// leaf_hash)` triple: each bucket's hash is the hash of its member leaf
+/// hashes sorted, so it's independent of insertion order.
+pub fn bucket_hashes(entries: &[(String, String, u64)]) -> HashMap<u32, u64> {
+    let mut buckets: HashMap<u32, Vec<u64>> = HashMap::new();
+    for (username, image_id, leaf) in entries {
+        buckets.entry(bucket_of(username, image_id)).or_default().push(*leaf);
+    }
+
+    buckets
+        .into_iter()
+        .map(|(bucket, mut leaves)| {
+            leaves.sort_unstable();
+            (bucket, hash_u64(leaves))
+        })
+        .collect()
+}
+
+/// Root hash over every bucket, in bucket-index order, so the whole table
+/// can be compared with a single value. Missing buckets hash to 0, so an
+/// empty table and a table with only empty buckets agree.
+pub fn root_hash(buckets: &HashMap<u32, u64>) -> u64 {
+    let ordered: Vec<u64> = (0..BUCKET_COUNT).map(|b| buckets.get(&b).copied().unwrap_or(0)).collect();
+    hash_u64(ordered)
+}
+
+/// Every bucket whose hash differs between `ours` and `theirs`, a bucket
+/// missing from either map counting as hash 0 (so one present on only one
+/// side is included) - the subtrees a `ReplicationSync` should
+/// ask the peer for full records of.
+pub fn mismatched_buckets(ours: &HashMap<u32, u64>, theirs: &HashMap<u32, u64>) -> Vec<u32> {
+    (0..BUCKET_COUNT)
+        .filter(|b| ours.get(b).copied().unwrap_or(0) != theirs.get(b).copied().unwrap_or(0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replica_nodes_is_deterministic_for_the_same_live_set() {
+        let live = vec![1, 2, 3, 4, 5];
+        let a = replica_nodes("image-123", &live, 2);
+        let b = replica_nodes("image-123", &live, 2);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 2);
+    }
+
+    #[test]
+    fn replica_nodes_never_returns_duplicates_or_more_than_available() {
+        let live = vec![7];
+        let replicas = replica_nodes("image-xyz", &live, 2);
+        assert_eq!(replicas, vec![7]);
+    }
+
+    #[test]
+    fn root_hash_agrees_for_two_empty_tables() {
+        let a: HashMap<u32, u64> = HashMap::new();
+        let b: HashMap<u32, u64> = HashMap::new();
+        assert_eq!(root_hash(&a), root_hash(&b));
+    }
+
+    #[test]
+    fn write_quorum_is_a_simple_majority() {
+        assert_eq!(write_quorum(1), 1);
+        assert_eq!(write_quorum(2), 2);
+        assert_eq!(write_quorum(3), 2);
+    }
+
+    #[test]
+    fn mismatched_buckets_finds_only_the_differing_bucket() {
+        let entries_a = vec![("alice".to_string(), "img-1".to_string(), 111u64)];
+        let entries_b = vec![("alice".to_string(), "img-1".to_string(), 222u64)];
+
+        let hashes_a = bucket_hashes(&entries_a);
+        let hashes_b = bucket_hashes(&entries_b);
+
+        let mismatched = mismatched_buckets(&hashes_a, &hashes_b);
+        assert_eq!(mismatched, vec![bucket_of("alice", "img-1")]);
+    }
+}
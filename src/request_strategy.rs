@@ -0,0 +1,172 @@
+//! Generic concurrent fan-out to a set of peers, so a caller that needs a
+//! response from (or a vote from a quorum of) several peers doesn't have to
+//! hand-roll its own per-peer socket-and-timeout loop. Modeled on garage's
+//! `rpc_helper` `RequestStrategy`/`try_call_many`: `broadcast_request` kicks
+//! off every peer's request concurrently via a `FuturesUnordered` and
+//! collects whatever arrives before a deadline, instead of blocking on each
+//! peer one at a time and letting a single slow node stall the whole round.
+//!
+//! `node::leave_and_exit` uses this to send `Leave` to every live peer in
+//! parallel and collect their `LeaveAck`s; `election::ElectionManager::start_election` predates this
+//! module and hand-rolls an equivalent spawn+channel fan-out of its own for
+//! the same reason - both exist side by side rather than forcing the older
+//! one through this newer generic path.
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::time::Duration;
+
+/// How a `broadcast_request` round should behave as responses arrive.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestStrategy {
+    /// Hard wall-clock deadline for the whole round - whichever peers
+    /// haven't responded by then are simply absent from the result map.
+    pub timeout: Duration,
+    /// Stop collecting once this many responses have arrived, rather than
+    /// always waiting out `timeout`. `None` waits for every peer to either
+    /// respond or fall out at the deadline.
+    pub quorum: Option<usize>,
+    /// Once `quorum` is reached, whether to stop immediately (dropping the
+    /// remaining in-flight requests) rather than keep waiting out `timeout`
+    /// for stragglers. Ignored when `quorum` is `None`.
+    pub interrupt_after_quorum: bool,
+}
+
+impl RequestStrategy {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout, quorum: None, interrupt_after_quorum: false }
+    }
+
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    pub fn interrupt_after_quorum(mut self, interrupt: bool) -> Self {
+        self.interrupt_after_quorum = interrupt;
+        self
+    }
+}
+
+/// Dispatch `send_one(peer)` to every entry in `peers` concurrently and
+/// collect whichever responses (`send_one` returning `Some`) arrive before
+/// `strategy.timeout`, stopping early once `strategy.quorum` responses have
+/// landed if `strategy.interrupt_after_quorum` is set. Peers that time out,
+/// error, or whose `send_one` future resolves to `None` are simply absent
+/// from the returned map rather than failing the whole round.
+pub async fn broadcast_request<P, T, F, Fut>(peers: &[P], strategy: RequestStrategy, send_one: F) -> HashMap<P, T>
+where
+    P: Copy + Eq + Hash,
+    F: Fn(P) -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    let mut in_flight: FuturesUnordered<_> = peers
+        .iter()
+        .copied()
+        .map(|peer| {
+            let response = send_one(peer);
+            async move { (peer, response.await) }
+        })
+        .collect();
+
+    let mut results = HashMap::new();
+    let deadline = tokio::time::sleep(strategy.timeout);
+    tokio::pin!(deadline);
+
+    loop {
+        if in_flight.is_empty() {
+            break;
+        }
+        tokio::select! {
+            _ = &mut deadline => break,
+            next = in_flight.next() => {
+                let Some((peer, response)) = next else { break };
+                if let Some(value) = response {
+                    results.insert(peer, value);
+                    if strategy.interrupt_after_quorum {
+                        if let Some(quorum) = strategy.quorum {
+                            if results.len() >= quorum {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn collects_all_responses_within_timeout() {
+        let peers = [1, 2, 3];
+        let strategy = RequestStrategy::with_timeout(Duration::from_millis(200));
+
+        let results = broadcast_request(&peers, strategy, |peer: i32| async move { Some(peer * 10) }).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.get(&2), Some(&20));
+    }
+
+    #[tokio::test]
+    async fn drops_peers_that_return_none() {
+        let peers = [1, 2, 3];
+        let strategy = RequestStrategy::with_timeout(Duration::from_millis(200));
+
+        let results =
+            broadcast_request(&peers, strategy, |peer: i32| async move { if peer == 2 { None } else { Some(peer) } })
+                .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(!results.contains_key(&2));
+    }
+
+    #[tokio::test]
+    async fn stops_early_once_quorum_is_met() {
+        let peers = [1, 2, 3, 4, 5];
+        let strategy = RequestStrategy::with_timeout(Duration::from_secs(5))
+            .with_quorum(2)
+            .interrupt_after_quorum(true);
+
+        let start = tokio::time::Instant::now();
+        let results = broadcast_request(&peers, strategy, |peer: i32| async move {
+            if peer <= 2 {
+                Some(peer)
+            } else {
+                // Would outlast the test if the round didn't interrupt early.
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Some(peer)
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn slow_peers_fall_out_at_the_deadline() {
+        let peers = [1, 2];
+        let strategy = RequestStrategy::with_timeout(Duration::from_millis(50));
+
+        let results = broadcast_request(&peers, strategy, |peer: i32| async move {
+            if peer == 1 {
+                Some(peer)
+            } else {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Some(peer)
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results.contains_key(&1));
+    }
+}
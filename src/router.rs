@@ -0,0 +1,99 @@
+use crate::messages::NodeId;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Smooths out a node reporting exactly zero load so it doesn't get an
+/// infinite (and therefore always-winning) weight.
+const LOAD_EPSILON: f64 = 0.01;
+
+/// Weighted-random node selection for client-side load balancing.
+///
+/// Each node's selection weight is the inverse of its reported load, so
+/// idle nodes are proportionally far more likely to be picked than busy
+/// ones, while every live node still has a chance (unlike always routing
+/// to the single lowest-load node). Selection uses Efraimidis-Spirakis
+/// weighted sampling without replacement: draw `u_i` uniform in (0, 1) for
+/// each candidate, compute the key `k_i = u_i.powf(1.0 / w_i)`, and the
+/// node(s) with the largest key win.
+fn weighted_keys(loads: &HashMap<NodeId, f64>) -> Vec<(NodeId, f64)> {
+    let mut rng = rand::thread_rng();
+
+    loads
+        .iter()
+        .map(|(&node_id, &load)| {
+            let weight = 1.0 / (load + LOAD_EPSILON);
+            let u: f64 = rng.gen_range(0.0..1.0);
+            let key = u.powf(1.0 / weight);
+            (node_id, key)
+        })
+        .collect()
+}
+
+/// Pick a single node, favoring lower-load ones in proportion to their
+/// spare capacity. Returns `None` if `loads` is empty.
+pub fn select_node(loads: &HashMap<NodeId, f64>) -> Option<NodeId> {
+    weighted_keys(loads)
+        .into_iter()
+        // load is peer-reported, so a NaN key (e.g. from a lying/buggy
+        // peer's load) must not panic the comparator here.
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(node_id, _)| node_id)
+}
+
+/// Pick up to `k` distinct nodes, ordered highest-key (most preferred)
+/// first, for callers that want replication or sequential retry targets
+/// rather than a single destination.
+pub fn select_nodes(loads: &HashMap<NodeId, f64>, k: usize) -> Vec<NodeId> {
+    let mut keyed = weighted_keys(loads);
+    keyed.sort_by(|a, b| b.1.total_cmp(&a.1));
+    keyed.into_iter().take(k).map(|(node_id, _)| node_id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_node_returns_none_for_empty_loads() {
+        let loads = HashMap::new();
+        assert_eq!(select_node(&loads), None);
+    }
+
+    #[test]
+    fn select_node_picks_the_only_candidate() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.5);
+        assert_eq!(select_node(&loads), Some(1));
+    }
+
+    #[test]
+    fn select_nodes_returns_distinct_nodes_up_to_k() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.1);
+        loads.insert(2, 0.5);
+        loads.insert(3, 0.9);
+
+        let picked = select_nodes(&loads, 2);
+        assert_eq!(picked.len(), 2);
+        assert_ne!(picked[0], picked[1]);
+    }
+
+    #[test]
+    fn select_nodes_caps_at_available_candidates() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.1);
+
+        let picked = select_nodes(&loads, 5);
+        assert_eq!(picked, vec![1]);
+    }
+
+    #[test]
+    fn idle_node_wins_far_more_often_than_a_saturated_one() {
+        let mut loads = HashMap::new();
+        loads.insert(1, 0.01); // nearly idle
+        loads.insert(2, 50.0); // heavily loaded
+
+        let idle_wins = (0..200).filter(|_| select_node(&loads) == Some(1)).count();
+        assert!(idle_wins > 150, "idle node only won {}/200 selections", idle_wins);
+    }
+}
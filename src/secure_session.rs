@@ -0,0 +1,416 @@
+//! Authenticated, encrypted UDP session layer.
+//!
+//! Pairing (`identity::PairingProof`) proves a peer owns its long-term node
+//! key; this module builds an ephemeral, forward-secret session on top of
+//! that trust. A handshake performs an X25519 key exchange where each side
+//! signs its ephemeral public key with its static node key (preventing a
+//! MITM from swapping in its own ephemeral key), HKDFs the shared secret
+//! into two directional keys, and hands back a `SecureSession` sealed with
+//! ChaCha20-Poly1305.
+//!
+//! This is the payload-confidentiality layer, not the framing layer:
+//! `SessionWriter::seal`/`SessionReader::open` operate on whole `Message`
+//! bytes, the same unit `ChunkedMessage::fragment`/`ChunkReassembler`
+//! already fragment and reassemble - a sealed payload is expected to be
+//! fragmented same as a plaintext one is today. `node::CloudNode` wires this
+//! in for election/gossip/replication traffic via `secure_sessions` and
+//! `send_secure_message_to_node`.
+//!
+//! `SessionWriter::rotate`/`SessionReader::rotate` ratchet a direction's key
+//! forward with a one-way KDF (see `ratchet_key`) so a key captured after
+//! rotation can't decrypt anything sealed before it. `node::key_rotation_task`
+//! drives this periodically per peer: the initiating side seals a
+//! `Message::KeyRotation` marker under its current send key, rotates its
+//! writer once the send succeeds, and the peer rotates its matching reader
+//! once it successfully opens that marker - each direction ratchets
+//! independently, in lockstep with its counterpart on the other side.
+
+use crate::identity::NodeIdentity;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::collections::HashSet;
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+/// How far a received nonce counter may trail the highest one seen before
+/// it's rejected outright, rather than checked against the seen-set. Lets a
+/// handful of reordered (not lost) datagrams through without opening the
+/// window up to a true replay attack.
+const REPLAY_WINDOW: u64 = 64;
+
+const HANDSHAKE_CONTEXT: &[u8] = b"distributed-image-cloud/secure-session/v1";
+const INITIATOR_TO_RESPONDER_LABEL: &[u8] = b"initiator->responder";
+const RESPONDER_TO_INITIATOR_LABEL: &[u8] = b"responder->initiator";
+
+/// Ratchets a directional key forward: one-way (the old key can't be
+/// recovered from the new one), so traffic captured before a rotation stays
+/// unreadable even if a later key is somehow exposed. Each direction ratchets
+/// independently of the other, same as the two directions already use
+/// independent keys from the initial handshake.
+const ROTATION_CONTEXT: &[u8] = b"distributed-image-cloud/secure-session/rotate-v1";
+
+fn ratchet_key(current: &[u8; 32]) -> [u8; 32] {
+    let hk = Hkdf::<Sha256>::new(None, current);
+    let mut next = [0u8; 32];
+    hk.expand(ROTATION_CONTEXT, &mut next).expect("32 bytes is a valid HKDF output length");
+    next
+}
+
+/// One side's ephemeral handshake offer: its X25519 public key, signed with
+/// its long-term Ed25519 node key. A peer that can't verify this signature
+/// against the static key it already trusts (via `PairingProof`) must not
+/// proceed - an attacker without that private key can't forge this, even
+/// if it can inject its own ephemeral key into the exchange.
+#[derive(Debug, Clone)]
+pub struct HandshakeMessage {
+    pub ephemeral_public: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl HandshakeMessage {
+    fn verify(&self, peer_static_public_key: &[u8; 32]) -> Result<(), String> {
+        let verifying_key = VerifyingKey::from_bytes(peer_static_public_key)
+            .map_err(|e| format!("Invalid peer public key: {}", e))?;
+        let signature = Signature::from_bytes(&self.signature);
+        verifying_key
+            .verify(&self.ephemeral_public, &signature)
+            .map_err(|_| "Handshake signature verification failed".to_string())
+    }
+}
+
+/// Which side of the handshake this node played. Decides which of the two
+/// HKDF-derived directional keys is used for sending vs receiving, so the
+/// initiator's send key is the responder's receive key and vice versa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Initiator,
+    Responder,
+}
+
+/// In-progress handshake state: the ephemeral keypair this side generated,
+/// held until `finish` consumes it to derive the shared session keys.
+pub struct HandshakeState {
+    ephemeral_secret: EphemeralSecret,
+    ephemeral_public: PublicKey,
+}
+
+impl HandshakeState {
+    /// Start a handshake: generate an ephemeral X25519 keypair and sign the
+    /// public half with `identity`'s static node key. Send the returned
+    /// `HandshakeMessage` to the peer and pass its reply to `finish`.
+    pub fn begin(identity: &NodeIdentity) -> (Self, HandshakeMessage) {
+        let ephemeral_secret = EphemeralSecret::random_from_rng(rand::rngs::OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral_secret);
+        let signature = identity.sign(ephemeral_public.as_bytes());
+
+        let message = HandshakeMessage {
+            ephemeral_public: ephemeral_public.to_bytes(),
+            signature,
+        };
+
+        (Self { ephemeral_secret, ephemeral_public }, message)
+    }
+
+    /// Verify the peer's offer against its already-trusted static public
+    /// key, perform the X25519 exchange, and HKDF the shared secret into a
+    /// `SecureSession`. `role` must be the opposite of whatever role the
+    /// peer passes for its own `finish` call, or the two sides derive
+    /// mismatched directional keys.
+    pub fn finish(
+        self,
+        role: Role,
+        peer_static_public_key: &[u8; 32],
+        peer_message: &HandshakeMessage,
+    ) -> Result<SecureSession, String> {
+        peer_message.verify(peer_static_public_key)?;
+
+        let peer_ephemeral_public = PublicKey::from(peer_message.ephemeral_public);
+        if peer_ephemeral_public.as_bytes() == self.ephemeral_public.as_bytes() {
+            return Err("Peer's ephemeral key is identical to ours - refusing a degenerate handshake".to_string());
+        }
+
+        let shared_secret = self.ephemeral_secret.diffie_hellman(&peer_ephemeral_public);
+
+        let hk = Hkdf::<Sha256>::new(Some(HANDSHAKE_CONTEXT), shared_secret.as_bytes());
+        let mut initiator_to_responder = [0u8; 32];
+        let mut responder_to_initiator = [0u8; 32];
+        hk.expand(INITIATOR_TO_RESPONDER_LABEL, &mut initiator_to_responder)
+            .map_err(|e| format!("HKDF expand failed: {}", e))?;
+        hk.expand(RESPONDER_TO_INITIATOR_LABEL, &mut responder_to_initiator)
+            .map_err(|e| format!("HKDF expand failed: {}", e))?;
+
+        let (send_key, recv_key) = match role {
+            Role::Initiator => (initiator_to_responder, responder_to_initiator),
+            Role::Responder => (responder_to_initiator, initiator_to_responder),
+        };
+
+        Ok(SecureSession {
+            send_key,
+            recv_key,
+            send_cipher: ChaCha20Poly1305::new(Key::from_slice(&send_key)),
+            recv_cipher: ChaCha20Poly1305::new(Key::from_slice(&recv_key)),
+        })
+    }
+}
+
+/// An established session: one cipher per direction, derived so that this
+/// side's send key is the peer's receive key. Call `split` to hand the two
+/// directions to independent reader/writer tasks.
+pub struct SecureSession {
+    send_key: [u8; 32],
+    recv_key: [u8; 32],
+    send_cipher: ChaCha20Poly1305,
+    recv_cipher: ChaCha20Poly1305,
+}
+
+impl SecureSession {
+    /// Split into independent halves so the send and receive directions
+    /// can each be driven from their own task for full-duplex operation -
+    /// the writer encrypts+fragments outbound `Message`s, the reader
+    /// reassembles+decrypts inbound ones.
+    pub fn split(self) -> (SessionReader, SessionWriter) {
+        (
+            SessionReader {
+                cipher: self.recv_cipher,
+                key: self.recv_key,
+                highest_counter: None,
+                seen: HashSet::new(),
+            },
+            SessionWriter {
+                cipher: self.send_cipher,
+                key: self.send_key,
+                counter: 0,
+            },
+        )
+    }
+}
+
+fn nonce_from_counter(counter: u64) -> [u8; 12] {
+    let mut bytes = [0u8; 12];
+    bytes[4..].copy_from_slice(&counter.to_be_bytes());
+    bytes
+}
+
+/// Owns the send direction: a monotonically increasing nonce counter and
+/// the cipher derived for this side's outbound traffic.
+pub struct SessionWriter {
+    cipher: ChaCha20Poly1305,
+    key: [u8; 32],
+    counter: u64,
+}
+
+impl SessionWriter {
+    /// Seal `plaintext`, returning `[8-byte counter][ciphertext+tag]`. The
+    /// counter prefix lets the receiver reconstruct the nonce without a
+    /// side channel; fragment the returned bytes the same way a plaintext
+    /// message would be.
+    pub fn seal(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce = nonce_from_counter(self.counter);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|e| format!("AEAD encryption failed: {}", e))?;
+
+        let mut out = Vec::with_capacity(8 + ciphertext.len());
+        out.extend_from_slice(&self.counter.to_be_bytes());
+        out.extend_from_slice(&ciphertext);
+
+        self.counter += 1;
+        Ok(out)
+    }
+
+    /// Ratchet this direction's key forward and reset the nonce counter -
+    /// safe since a fresh key means counter 0 has never been used under it.
+    /// Call after successfully sending the `Message::KeyRotation` marker
+    /// that tells the peer to ratchet its matching `SessionReader` too.
+    pub fn rotate(&mut self) {
+        self.key = ratchet_key(&self.key);
+        self.cipher = ChaCha20Poly1305::new(Key::from_slice(&self.key));
+        self.counter = 0;
+    }
+}
+
+/// Owns the receive direction: the cipher derived for this side's inbound
+/// traffic, plus enough replay-window state to reject reused or
+/// too-far-behind nonce counters.
+pub struct SessionReader {
+    cipher: ChaCha20Poly1305,
+    key: [u8; 32],
+    highest_counter: Option<u64>,
+    /// Counters within `REPLAY_WINDOW` of `highest_counter` that have
+    /// already been accepted, so an exact resend of one is caught.
+    seen: HashSet<u64>,
+}
+
+impl SessionReader {
+    /// Open a buffer produced by the peer's `SessionWriter::seal`,
+    /// rejecting it if the nonce counter is a reuse or has fallen further
+    /// behind the highest one seen than `REPLAY_WINDOW` allows.
+    pub fn open(&mut self, sealed: &[u8]) -> Result<Vec<u8>, String> {
+        if sealed.len() < 8 {
+            return Err("Sealed message too short to contain a nonce counter".to_string());
+        }
+        let counter = u64::from_be_bytes(sealed[..8].try_into().unwrap());
+        let ciphertext = &sealed[8..];
+
+        if let Some(highest) = self.highest_counter {
+            if counter + REPLAY_WINDOW < highest {
+                return Err(format!(
+                    "Nonce counter {} is outside the replay window behind highest seen {}",
+                    counter, highest
+                ));
+            }
+            if counter <= highest && self.seen.contains(&counter) {
+                return Err(format!("Nonce counter {} already seen (replay)", counter));
+            }
+        }
+
+        let nonce = nonce_from_counter(counter);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext)
+            .map_err(|_| "AEAD decryption failed (wrong key or tampered ciphertext)".to_string())?;
+
+        let is_new_high = match self.highest_counter {
+            Some(highest) => counter > highest,
+            None => true,
+        };
+        if is_new_high {
+            self.highest_counter = Some(counter);
+            let floor = counter.saturating_sub(REPLAY_WINDOW);
+            self.seen.retain(|&c| c >= floor);
+        }
+        self.seen.insert(counter);
+
+        Ok(plaintext)
+    }
+
+    /// Ratchet this direction's key forward and reset replay-window state -
+    /// call after successfully opening a `Message::KeyRotation` marker from
+    /// the peer, so both sides' matching directional keys advance in lockstep.
+    pub fn rotate(&mut self) {
+        self.key = ratchet_key(&self.key);
+        self.cipher = ChaCha20Poly1305::new(Key::from_slice(&self.key));
+        self.highest_counter = None;
+        self.seen.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_identity() -> NodeIdentity {
+        NodeIdentity {
+            signing_key: ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng),
+        }
+    }
+
+    fn established_pair() -> (SecureSession, SecureSession) {
+        let alice_identity = test_identity();
+        let bob_identity = test_identity();
+
+        let (alice_state, alice_offer) = HandshakeState::begin(&alice_identity);
+        let (bob_state, bob_offer) = HandshakeState::begin(&bob_identity);
+
+        let alice_session = alice_state
+            .finish(Role::Initiator, &bob_identity.public_key_bytes(), &bob_offer)
+            .unwrap();
+        let bob_session = bob_state
+            .finish(Role::Responder, &alice_identity.public_key_bytes(), &alice_offer)
+            .unwrap();
+
+        (alice_session, bob_session)
+    }
+
+    #[test]
+    fn handshake_derives_matching_directional_keys() {
+        let (alice, bob) = established_pair();
+        let (mut alice_reader, mut alice_writer) = alice.split();
+        let (mut bob_reader, mut bob_writer) = bob.split();
+
+        let sealed = alice_writer.seal(b"hello bob").unwrap();
+        assert_eq!(bob_reader.open(&sealed).unwrap(), b"hello bob");
+
+        let sealed = bob_writer.seal(b"hello alice").unwrap();
+        assert_eq!(alice_reader.open(&sealed).unwrap(), b"hello alice");
+    }
+
+    #[test]
+    fn handshake_rejects_forged_ephemeral_signature() {
+        let alice_identity = test_identity();
+        let bob_identity = test_identity();
+        let mallory_identity = test_identity();
+
+        let (alice_state, _) = HandshakeState::begin(&alice_identity);
+        // Mallory signs her own ephemeral key, but Alice verifies against
+        // Bob's (already-trusted) static public key.
+        let (_, mallory_offer) = HandshakeState::begin(&mallory_identity);
+
+        let result = alice_state.finish(Role::Initiator, &bob_identity.public_key_bytes(), &mallory_offer);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn replayed_counter_is_rejected() {
+        let (alice, bob) = established_pair();
+        let (mut bob_reader, mut alice_writer) = (bob.split().0, alice.split().1);
+
+        let sealed = alice_writer.seal(b"once").unwrap();
+        assert!(bob_reader.open(&sealed).is_ok());
+        assert!(bob_reader.open(&sealed).is_err());
+    }
+
+    #[test]
+    fn counter_beyond_replay_window_is_rejected() {
+        let (alice, bob) = established_pair();
+        let (mut bob_reader, mut alice_writer) = (bob.split().0, alice.split().1);
+
+        let first = alice_writer.seal(b"first").unwrap();
+        for _ in 0..(REPLAY_WINDOW + 5) {
+            let sealed = alice_writer.seal(b"filler").unwrap();
+            bob_reader.open(&sealed).unwrap();
+        }
+
+        assert!(bob_reader.open(&first).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails_decryption() {
+        let (alice, bob) = established_pair();
+        let (mut bob_reader, mut alice_writer) = (bob.split().0, alice.split().1);
+
+        let mut sealed = alice_writer.seal(b"integrity matters").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0xFF;
+
+        assert!(bob_reader.open(&sealed).is_err());
+    }
+
+    #[test]
+    fn rotated_writer_and_reader_still_agree() {
+        let (alice, bob) = established_pair();
+        let (mut bob_reader, mut alice_writer) = (bob.split().0, alice.split().1);
+
+        alice_writer.rotate();
+        bob_reader.rotate();
+
+        let sealed = alice_writer.seal(b"post-rotation").unwrap();
+        assert_eq!(bob_reader.open(&sealed).unwrap(), b"post-rotation");
+    }
+
+    #[test]
+    fn old_ciphertext_is_unreadable_after_rotation() {
+        let (alice, bob) = established_pair();
+        let (mut bob_reader, mut alice_writer) = (bob.split().0, alice.split().1);
+
+        let sealed_before = alice_writer.seal(b"pre-rotation").unwrap();
+
+        alice_writer.rotate();
+        bob_reader.rotate();
+
+        assert!(bob_reader.open(&sealed_before).is_err());
+    }
+}
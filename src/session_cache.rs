@@ -0,0 +1,139 @@
+use crate::gui_client::RequestHistoryItem;
+use crate::messages::ReceivedImageInfo;
+use aes_gcm_siv::aead::{Aead, KeyInit};
+use aes_gcm_siv::{Aes256GcmSiv, Key, Nonce};
+use log::info;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// scrypt parameters: N = 2^15, r = 8, p = 1, matching the repo's chosen
+/// work factor for local password hardening.
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// Everything kept in a client's local cache so it survives a restart:
+/// request history, received-image metadata, and decrypted-image thumbnails
+/// keyed by image_id.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionCacheData {
+    pub request_history: Vec<RequestHistoryItem>,
+    pub received_images: Vec<ReceivedImageInfo>,
+    pub thumbnails: HashMap<String, Vec<u8>>,
+}
+
+/// Derive a 256-bit key from `passphrase` and `salt` using scrypt.
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 32)
+        .map_err(|e| format!("Invalid scrypt parameters: {}", e))?;
+
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+
+    Ok(key)
+}
+
+/// Default on-disk location for a client's encrypted cache, namespaced by
+/// client ID so multiple local clients don't clobber each other.
+pub fn default_cache_path(client_id: &str) -> PathBuf {
+    PathBuf::from(format!(".session_cache_{}.enc", client_id))
+}
+
+/// Whether an encrypted cache already exists for this client.
+pub fn cache_exists(client_id: &str) -> bool {
+    default_cache_path(client_id).exists()
+}
+
+/// Encrypt and persist `data` under `passphrase`. A fresh salt and nonce are
+/// generated every save (required for nonce-misuse resistance to actually
+/// matter), stored in the clear alongside the ciphertext: `salt || nonce || ciphertext`.
+pub fn save(client_id: &str, passphrase: &str, data: &SessionCacheData) -> Result<(), String> {
+    let mut salt = [0u8; SALT_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
+
+    let key_bytes = derive_key(passphrase, &salt)?;
+    let cipher = Aes256GcmSiv::new(Key::<Aes256GcmSiv>::from_slice(&key_bytes));
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let plaintext = serde_json::to_vec(data).map_err(|e| format!("Failed to serialize session cache: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext.as_ref())
+        .map_err(|e| format!("Failed to encrypt session cache: {}", e))?;
+
+    let mut contents = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    contents.extend_from_slice(&salt);
+    contents.extend_from_slice(&nonce_bytes);
+    contents.extend_from_slice(&ciphertext);
+
+    std::fs::write(default_cache_path(client_id), contents)
+        .map_err(|e| format!("Failed to write session cache: {}", e))?;
+
+    info!("Saved encrypted session cache for client {}", client_id);
+    Ok(())
+}
+
+/// Load and decrypt the cache for `client_id` under `passphrase`. Fails
+/// closed (returns an error, no partial data) on a wrong passphrase since
+/// that shows up as an AEAD tag mismatch.
+pub fn load(client_id: &str, passphrase: &str) -> Result<SessionCacheData, String> {
+    let contents = std::fs::read(default_cache_path(client_id))
+        .map_err(|e| format!("Failed to read session cache: {}", e))?;
+
+    if contents.len() < SALT_LEN + NONCE_LEN {
+        return Err("Session cache file is corrupt".to_string());
+    }
+
+    let (salt, rest) = contents.split_at(SALT_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let key_bytes = derive_key(passphrase, salt)?;
+    let cipher = Aes256GcmSiv::new(Key::<Aes256GcmSiv>::from_slice(&key_bytes));
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| "Incorrect passphrase or corrupted session cache".to_string())?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse session cache: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_save_and_load() {
+        let client_id = format!("test_client_{}", std::process::id());
+        let data = SessionCacheData {
+            request_history: vec![],
+            received_images: vec![],
+            thumbnails: HashMap::new(),
+        };
+
+        save(&client_id, "correct horse battery staple", &data).unwrap();
+        let loaded = load(&client_id, "correct horse battery staple").unwrap();
+        assert_eq!(loaded.thumbnails.len(), 0);
+
+        let _ = std::fs::remove_file(default_cache_path(&client_id));
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_closed() {
+        let client_id = format!("test_client_wrong_pw_{}", std::process::id());
+        let data = SessionCacheData::default();
+
+        save(&client_id, "right-passphrase", &data).unwrap();
+        let result = load(&client_id, "wrong-passphrase");
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_file(default_cache_path(&client_id));
+    }
+}
@@ -0,0 +1,232 @@
+//! Streaming export of cluster-health events - heartbeats, failure
+//! detection, election outcomes, and the periodic load-distribution table -
+//! to Kafka, as an operator-facing alternative to grepping the `tracing`
+//! log for the same information in real time. Mirrors `metrics_sink.rs`'s
+//! shape (a `Sink` trait plus one `rdkafka`-backed implementation) but for
+//! node-level cluster events rather than stress-test request timing.
+
+use crate::messages::NodeId;
+use log::warn;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What kind of cluster event a `ClusterEvent` records. Kept as a plain,
+/// serde-tagged enum (rather than splitting into separate event structs) so
+/// a single Kafka topic carries every event kind and a consumer can filter
+/// on `"kind"` however it likes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClusterEventKind {
+    Heartbeat,
+    FailureDetected,
+    ElectionCompleted,
+    LoadReport,
+}
+
+/// One cluster-health event, published to the telemetry topic as a JSON
+/// record. Not every `kind` populates every field (e.g. a `Heartbeat` has
+/// no `coordinator_id` verdict); irrelevant fields are left `None` rather
+/// than given a misleading default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterEvent {
+    pub node_id: NodeId,
+    pub timestamp_ms: u128,
+    pub kind: ClusterEventKind,
+    pub load: Option<f64>,
+    pub processed_count: Option<usize>,
+    pub queue_length: Option<usize>,
+    pub coordinator_id: Option<NodeId>,
+}
+
+impl ClusterEvent {
+    pub fn new(node_id: NodeId, kind: ClusterEventKind) -> Self {
+        Self {
+            node_id,
+            timestamp_ms: now_ms(),
+            kind,
+            load: None,
+            processed_count: None,
+            queue_length: None,
+            coordinator_id: None,
+        }
+    }
+
+    pub fn with_load(mut self, load: f64) -> Self {
+        self.load = Some(load);
+        self
+    }
+
+    pub fn with_processed_count(mut self, processed_count: usize) -> Self {
+        self.processed_count = Some(processed_count);
+        self
+    }
+
+    pub fn with_queue_length(mut self, queue_length: usize) -> Self {
+        self.queue_length = Some(queue_length);
+        self
+    }
+
+    pub fn with_coordinator_id(mut self, coordinator_id: NodeId) -> Self {
+        self.coordinator_id = Some(coordinator_id);
+        self
+    }
+}
+
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Something a `CloudNode` can publish cluster events into. `Send + Sync`
+/// so it can be shared behind an `Arc` across every task a node spawns.
+/// Deliberately synchronous (unlike `MetricsSink`): publishing must never
+/// make a consensus task await a broker round trip, so the one concrete
+/// implementation below fires-and-forgets instead of awaiting delivery.
+pub trait ClusterTelemetrySink: Send + Sync {
+    fn publish(&self, event: ClusterEvent);
+}
+
+fn default_client_id() -> String {
+    "distributed-image-cloud-cluster-telemetry".to_string()
+}
+
+fn default_buffer_size() -> usize {
+    1000
+}
+
+/// Config for `KafkaClusterTelemetrySink`, parsed from JSON the same way
+/// the rest of the crate loads config (see `bootstrap::ClusterConfig::load`
+/// and `metrics_sink::ProducerConfig`), plus a `from_env` constructor for
+/// operators who'd rather set a few environment variables on the node
+/// binary than manage a separate config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub brokers: String,
+    pub topic: String,
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+    /// Upper bound on messages rdkafka will queue locally before `send`
+    /// starts rejecting new ones - see `KafkaClusterTelemetrySink::publish`'s
+    /// drop-and-count handling of that case.
+    #[serde(default = "default_buffer_size")]
+    pub buffer_size: usize,
+}
+
+impl TelemetryConfig {
+    /// Parse a JSON config file in the same shape this struct derives.
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read telemetry config {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse telemetry config {}: {}", path.display(), e))
+    }
+
+    /// Build a config from `CLUSTER_TELEMETRY_KAFKA_BROKERS` and
+    /// `CLUSTER_TELEMETRY_KAFKA_TOPIC` (required), plus optional
+    /// `CLUSTER_TELEMETRY_KAFKA_CLIENT_ID` and
+    /// `CLUSTER_TELEMETRY_KAFKA_BUFFER_SIZE` overrides. Returns `Err` (not a
+    /// no-op sink) when the required variables are unset, so callers decide
+    /// for themselves whether an absent config means "run without
+    /// telemetry" - the crate never silently guesses.
+    pub fn from_env() -> Result<Self, String> {
+        let brokers = std::env::var("CLUSTER_TELEMETRY_KAFKA_BROKERS")
+            .map_err(|_| "CLUSTER_TELEMETRY_KAFKA_BROKERS is not set".to_string())?;
+        let topic = std::env::var("CLUSTER_TELEMETRY_KAFKA_TOPIC")
+            .map_err(|_| "CLUSTER_TELEMETRY_KAFKA_TOPIC is not set".to_string())?;
+        let client_id =
+            std::env::var("CLUSTER_TELEMETRY_KAFKA_CLIENT_ID").unwrap_or_else(|_| default_client_id());
+        let buffer_size = std::env::var("CLUSTER_TELEMETRY_KAFKA_BUFFER_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(default_buffer_size);
+
+        Ok(Self {
+            brokers,
+            topic,
+            client_id,
+            buffer_size,
+        })
+    }
+}
+
+/// Publishes `ClusterEvent`s to a Kafka topic as JSON records, keyed by the
+/// originating node id so a consumer can partition (or simply order) by
+/// node.
+pub struct KafkaClusterTelemetrySink {
+    producer: FutureProducer,
+    topic: String,
+    /// Events dropped because the local rdkafka send queue was full -
+    /// logged periodically rather than per-drop so a prolonged broker
+    /// outage doesn't itself flood the log.
+    dropped: AtomicU64,
+}
+
+impl KafkaClusterTelemetrySink {
+    pub fn new(config: &TelemetryConfig) -> Result<Self, String> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &config.brokers)
+            .set("client.id", &config.client_id)
+            .set("queue.buffering.max.messages", &config.buffer_size.to_string())
+            .create()
+            .map_err(|e| format!("Failed to create Kafka producer: {}", e))?;
+
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+            dropped: AtomicU64::new(0),
+        })
+    }
+}
+
+impl ClusterTelemetrySink for KafkaClusterTelemetrySink {
+    /// Fire-and-forget publish: `send_result` hands the record to rdkafka's
+    /// local queue and returns immediately rather than awaiting the broker's
+    /// ack, so a slow or unreachable Kafka cluster can never stall the
+    /// consensus task that's reporting an event. If the local queue is
+    /// already full, the record is dropped and counted instead of blocking.
+    fn publish(&self, event: ClusterEvent) {
+        let payload = match serde_json::to_string(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize cluster telemetry event: {}", e);
+                return;
+            }
+        };
+        let key = event.node_id.to_string();
+        let record = FutureRecord::to(&self.topic).key(&key).payload(&payload);
+
+        if let Err((e, _)) = self.producer.send_result(record) {
+            let total_dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                "Dropped cluster telemetry event (local send queue full, {} dropped so far): {}",
+                total_dropped, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_builders_only_set_the_requested_field() {
+        let event = ClusterEvent::new(1, ClusterEventKind::Heartbeat).with_load(0.5);
+        assert_eq!(event.load, Some(0.5));
+        assert_eq!(event.processed_count, None);
+        assert_eq!(event.queue_length, None);
+        assert_eq!(event.coordinator_id, None);
+    }
+
+    #[test]
+    fn serializes_with_snake_case_kind() {
+        let event = ClusterEvent::new(2, ClusterEventKind::FailureDetected);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"failure_detected\""));
+    }
+}
@@ -0,0 +1,276 @@
+//! An optional QUIC-backed transport (via `quinn`), as an alternative to
+//! the hand-rolled UDP chunking/retransmission layer in `chunking.rs` and
+//! `node.rs`'s `handle_datagram`/`send_response_to_client`. QUIC's own
+//! stream flow control and loss recovery make `ChunkReassembler`,
+//! `chunk_cache`, and `RetransmitRequest` unnecessary for anything carried
+//! over a `QuicEndpoint`, and removes the 65535-byte raw-UDP ceiling.
+//!
+//! This module is additive and opt-in: it doesn't yet replace
+//! `CloudNode`'s `recv_from` loop or `Client::send_to_node` (that's a
+//! large, mechanical migration across both files, deferred to a focused
+//! follow-on so it can be done - and verified - on its own rather than
+//! blindly alongside introducing the transport itself). What's here is the
+//! actual QUIC plumbing that follow-on would build on: a bound endpoint
+//! with a self-signed certificate, a bounded connection cache, and
+//! `send_message`/an incoming-message stream using `Message` (see
+//! `messages.rs`) as the application payload, unchanged.
+//!
+//! Peer identity is intentionally NOT established via the TLS handshake:
+//! server certificate verification is disabled (`SkipServerVerification`
+//! below), and node identity instead continues to rely on the existing
+//! application-layer `PairingRequest`/`PairingResponse` exchange (see
+//! `identity.rs`) exchanged as ordinary `Message`s once a QUIC stream is
+//! open. QUIC here is purely a reliable-transport upgrade, not a new trust
+//! boundary.
+
+use crate::messages::Message;
+use log::{debug, error, warn};
+use quinn::{ClientConfig, Endpoint, ServerConfig};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::Instant;
+
+/// Connections beyond this count are evicted (least-recently-used first)
+/// on the next `get_or_connect`, so a node talking to a large, churning
+/// cluster doesn't keep every connection it's ever opened alive forever.
+const MAX_CACHED_CONNECTIONS: usize = 256;
+
+/// A cached outbound connection, plus when it was last used - the basis
+/// for the cache's least-recently-used eviction. Connections accepted by
+/// `incoming_messages` are not cached.
+struct CachedConnection {
+    connection: quinn::Connection,
+    last_used: Instant,
+}
+
+/// A bound QUIC endpoint plus a cache of live connections to other nodes,
+/// keyed by address. One `QuicEndpoint` is created per `CloudNode`.
+pub struct QuicEndpoint {
+    endpoint: Endpoint,
+    connections: Arc<RwLock<HashMap<SocketAddr, CachedConnection>>>,
+}
+
+impl QuicEndpoint {
+    /// Bind a QUIC endpoint on `bind_addr` with a self-signed certificate
+    /// generated fresh for this process (identity is established above the
+    /// transport layer - see the module doc comment - so the certificate
+    /// itself doesn't need to be pinned or persisted across restarts).
+    pub async fn bind(bind_addr: SocketAddr) -> Result<Self, String> {
+        let rcgen::CertifiedKey { cert, signing_key } =
+            rcgen::generate_simple_self_signed(vec!["cloud-node".to_string()])
+                .map_err(|e| format!("Failed to generate self-signed certificate: {}", e))?;
+        let cert_der = cert.der().clone();
+        let key_der = quinn::rustls::pki_types::PrivatePkcs8KeyDer::from(signing_key.serialize_der());
+
+        let server_config = ServerConfig::with_single_cert(vec![cert_der], key_der.into())
+            .map_err(|e| format!("Failed to build QUIC server config: {}", e))?;
+
+        let mut endpoint = Endpoint::server(server_config, bind_addr)
+            .map_err(|e| format!("Failed to bind QUIC endpoint on {}: {}", bind_addr, e))?;
+        endpoint.set_default_client_config(Self::insecure_client_config());
+
+        Ok(Self {
+            endpoint,
+            connections: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    /// A `ClientConfig` that accepts any server certificate. Safe only
+    /// because peer identity is verified at the application layer - see
+    /// the module doc comment.
+    fn insecure_client_config() -> ClientConfig {
+        let crypto = quinn::rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+            .with_no_client_auth();
+        ClientConfig::new(Arc::new(
+            quinn::crypto::rustls::QuicClientConfig::try_from(crypto)
+                .expect("rustls provider supports QUIC"),
+        ))
+    }
+
+    /// Send one `Message` to `addr` over a fresh unidirectional QUIC
+    /// stream, reusing a cached connection when one is already open.
+    /// Length-prefixed JSON, same encoding `Message` already uses
+    /// elsewhere in the crate - QUIC just carries it reliably instead of
+    /// over raw chunked UDP datagrams.
+    pub async fn send_message(&self, addr: SocketAddr, message: &Message) -> Result<(), String> {
+        let connection = self.get_or_connect(addr).await?;
+
+        let mut stream = connection
+            .open_uni()
+            .await
+            .map_err(|e| format!("Failed to open QUIC stream to {}: {}", addr, e))?;
+
+        let payload = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+        let len = (payload.len() as u32).to_be_bytes();
+
+        stream
+            .write_all(&len)
+            .await
+            .map_err(|e| format!("Failed to write to {}: {}", addr, e))?;
+        stream
+            .write_all(&payload)
+            .await
+            .map_err(|e| format!("Failed to write to {}: {}", addr, e))?;
+        stream
+            .finish()
+            .map_err(|e| format!("Failed to finish stream to {}: {}", addr, e))?;
+
+        Ok(())
+    }
+
+    /// Accept inbound connections and streams indefinitely, pushing each
+    /// fully received `Message` (paired with the sender's address) onto
+    /// the returned channel. Intended to eventually replace `CloudNode`'s
+    /// `recv_from` loop - see the module doc comment for why that
+    /// migration is deferred rather than wired in here.
+    pub fn incoming_messages(self: Arc<Self>) -> mpsc::UnboundedReceiver<(SocketAddr, Message)> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let endpoint = self.endpoint.clone();
+
+        tokio::spawn(async move {
+            while let Some(incoming) = endpoint.accept().await {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let connection = match incoming.await {
+                        Ok(c) => c,
+                        Err(e) => {
+                            warn!("[QuicEndpoint] Inbound connection failed: {}", e);
+                            return;
+                        }
+                    };
+                    let addr = connection.remote_address();
+
+                    loop {
+                        let mut stream = match connection.accept_uni().await {
+                            Ok(s) => s,
+                            Err(e) => {
+                                debug!("[QuicEndpoint] Connection from {} closed: {}", addr, e);
+                                return;
+                            }
+                        };
+
+                        let tx = tx.clone();
+                        tokio::spawn(async move {
+                            match Self::read_message(&mut stream).await {
+                                Ok(message) => {
+                                    let _ = tx.send((addr, message));
+                                }
+                                Err(e) => error!("[QuicEndpoint] Failed to read message from {}: {}", addr, e),
+                            }
+                        });
+                    }
+                });
+            }
+        });
+
+        rx
+    }
+
+    async fn read_message(stream: &mut quinn::RecvStream) -> Result<Message, String> {
+        let mut len_bytes = [0u8; 4];
+        stream
+            .read_exact(&mut len_bytes)
+            .await
+            .map_err(|e| format!("Failed to read length prefix: {}", e))?;
+        let len = u32::from_be_bytes(len_bytes) as usize;
+
+        let mut payload = vec![0u8; len];
+        stream
+            .read_exact(&mut payload)
+            .await
+            .map_err(|e| format!("Failed to read payload: {}", e))?;
+
+        serde_json::from_slice(&payload).map_err(|e| format!("Failed to parse message: {}", e))
+    }
+
+    /// Reuse a cached connection to `addr` if one is still open, otherwise
+    /// dial a fresh one. Evicts the least-recently-used cached connection
+    /// first if the cache is at capacity.
+    async fn get_or_connect(&self, addr: SocketAddr) -> Result<quinn::Connection, String> {
+        {
+            let mut connections = self.connections.write().await;
+            if let Some(cached) = connections.get_mut(&addr) {
+                if cached.connection.close_reason().is_none() {
+                    cached.last_used = Instant::now();
+                    return Ok(cached.connection.clone());
+                }
+                connections.remove(&addr);
+            }
+        }
+
+        let connecting = self
+            .endpoint
+            .connect(addr, "cloud-node")
+            .map_err(|e| format!("Failed to start QUIC connection to {}: {}", addr, e))?;
+        let connection = connecting
+            .await
+            .map_err(|e| format!("Failed to establish QUIC connection to {}: {}", addr, e))?;
+
+        let mut connections = self.connections.write().await;
+        if connections.len() >= MAX_CACHED_CONNECTIONS {
+            if let Some(lru_addr) = connections
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(addr, _)| *addr)
+            {
+                connections.remove(&lru_addr);
+            }
+        }
+        connections.insert(
+            addr,
+            CachedConnection {
+                connection: connection.clone(),
+                last_used: Instant::now(),
+            },
+        );
+
+        Ok(connection)
+    }
+}
+
+/// Disables QUIC/TLS server-certificate verification. Peer identity is
+/// established at the application layer instead - see the module doc
+/// comment - so this is a deliberate choice, not an oversight.
+#[derive(Debug)]
+struct SkipServerVerification;
+
+impl quinn::rustls::client::danger::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &quinn::rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[quinn::rustls::pki_types::CertificateDer<'_>],
+        _server_name: &quinn::rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: quinn::rustls::pki_types::UnixTime,
+    ) -> Result<quinn::rustls::client::danger::ServerCertVerified, quinn::rustls::Error> {
+        Ok(quinn::rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &quinn::rustls::pki_types::CertificateDer<'_>,
+        _dss: &quinn::rustls::DigitallySignedStruct,
+    ) -> Result<quinn::rustls::client::danger::HandshakeSignatureValid, quinn::rustls::Error> {
+        Ok(quinn::rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &quinn::rustls::pki_types::CertificateDer<'_>,
+        _dss: &quinn::rustls::DigitallySignedStruct,
+    ) -> Result<quinn::rustls::client::danger::HandshakeSignatureValid, quinn::rustls::Error> {
+        Ok(quinn::rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<quinn::rustls::SignatureScheme> {
+        quinn::rustls::crypto::ring::default_provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
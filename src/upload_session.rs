@@ -0,0 +1,188 @@
+//! Temp-file staging for the `BeginUpload`/`UploadPart`/`CompleteUpload`
+//! multipart protocol (see the matching `Message` variants and their
+//! handlers in `node.rs`). An `EncryptionRequest` carries a whole image in
+//! one message, so a large upload is fully materialized in memory at every
+//! hop between the client and whichever node ends up encrypting it. Parts
+//! are instead written straight to a temp file as they arrive - one part
+//! held in memory at a time - and only read back in full once, at
+//! `CompleteUpload`, where `encryption::encrypt_image`'s whole-image LSB
+//! steganography leaves no way around needing the complete buffer anyway.
+
+use crate::compression::CompressionCodec;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// An in-progress multipart upload: the eventual `EncryptionRequest` fields
+/// it'll be replayed as, plus the temp file its parts are being appended
+/// to. Parts must arrive in order - an out-of-order `part_number` is
+/// rejected so the sender can retry from the right place, rather than this
+/// needing to buffer/reorder them itself.
+pub struct UploadSession {
+    pub client_username: String,
+    pub usernames: Vec<String>,
+    pub quota: u32,
+    pub codec: CompressionCodec,
+    pub client_address: Option<String>,
+    path: PathBuf,
+    file: Option<File>,
+    next_part: u32,
+    created_at: Instant,
+}
+
+/// Whether `segment` is safe to use as a single path component (e.g. joined
+/// onto a store directory as `{segment}.ext`) - non-empty and restricted to
+/// `[A-Za-z0-9_-]`. Several wire-supplied ids end up in a filename this way
+/// (`request_id` here, `image_id` in `image_store.rs`) and `PathBuf::join`
+/// replaces the whole path outright if the joined component is absolute, so
+/// an unchecked id like `/etc/cron.d/evil` would let a remote peer write a
+/// file anywhere on disk the node's user can write to, not just `../`
+/// traverse out of the intended directory.
+pub(crate) fn is_valid_path_segment(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+impl UploadSession {
+    pub fn create(
+        dir: &std::path::Path,
+        request_id: &str,
+        client_username: String,
+        usernames: Vec<String>,
+        quota: u32,
+        codec: CompressionCodec,
+        client_address: Option<String>,
+    ) -> Result<Self, String> {
+        if !is_valid_path_segment(request_id) {
+            return Err(format!("Invalid request_id '{}': must be non-empty and match [A-Za-z0-9_-]+", request_id));
+        }
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create upload temp directory: {}", e))?;
+        let path = dir.join(format!("{}.part", request_id));
+        let file = File::create(&path).map_err(|e| format!("Failed to create temp upload file: {}", e))?;
+
+        Ok(Self {
+            client_username,
+            usernames,
+            quota,
+            codec,
+            client_address,
+            path,
+            file: Some(file),
+            next_part: 0,
+            created_at: Instant::now(),
+        })
+    }
+
+    /// Append one part's bytes, rejecting anything out of order.
+    pub fn write_part(&mut self, part_number: u32, data: &[u8]) -> Result<(), String> {
+        if part_number != self.next_part {
+            return Err(format!("Expected part {}, got {}", self.next_part, part_number));
+        }
+        let file = self.file.as_mut().ok_or("Upload session's temp file is already closed")?;
+        file.write_all(data).map_err(|e| format!("Failed to write part {}: {}", part_number, e))?;
+        self.next_part += 1;
+        Ok(())
+    }
+
+    /// Close the temp file, read every part back as one buffer, and remove
+    /// it - the one point this upload is fully materialized in memory.
+    pub fn finalize(mut self) -> Result<Vec<u8>, String> {
+        self.file.take();
+        let data = std::fs::read(&self.path).map_err(|e| format!("Failed to read staged upload: {}", e))?;
+        let _ = std::fs::remove_file(&self.path);
+        Ok(data)
+    }
+
+    /// Whether this session has sat unfinished longer than `ttl`, i.e. its
+    /// client abandoned the upload partway through.
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.created_at.elapsed() > ttl
+    }
+}
+
+impl Drop for UploadSession {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Default on-disk directory for a node's in-progress upload temp files,
+/// namespaced by its own bind address - same convention as
+/// `peer_store::default_peer_store_path`.
+pub fn default_upload_temp_dir(node_address: &str) -> PathBuf {
+    let safe_name = node_address.replace([':', '.'], "_");
+    PathBuf::from(format!(".upload_tmp_{}", safe_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("upload_session_test_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn parts_written_in_order_round_trip() {
+        let dir = test_dir("in_order");
+        let mut session = UploadSession::create(
+            &dir,
+            "req-1",
+            "alice".to_string(),
+            vec!["bob".to_string()],
+            3,
+            CompressionCodec::None,
+            None,
+        )
+        .unwrap();
+
+        session.write_part(0, b"hello, ").unwrap();
+        session.write_part(1, b"world").unwrap();
+
+        let data = session.finalize().unwrap();
+        assert_eq!(data, b"hello, world");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn out_of_order_part_is_rejected() {
+        let dir = test_dir("out_of_order");
+        let mut session = UploadSession::create(
+            &dir,
+            "req-2",
+            "alice".to_string(),
+            vec!["bob".to_string()],
+            1,
+            CompressionCodec::None,
+            None,
+        )
+        .unwrap();
+
+        assert!(session.write_part(1, b"data").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn absolute_or_traversing_request_id_is_rejected() {
+        let dir = test_dir("malicious_request_id");
+        for request_id in ["/etc/cron.d/evil", "../../etc/passwd", "", "foo/bar"] {
+            assert!(
+                UploadSession::create(
+                    &dir,
+                    request_id,
+                    "alice".to_string(),
+                    vec!["bob".to_string()],
+                    1,
+                    CompressionCodec::None,
+                    None,
+                )
+                .is_err(),
+                "expected '{}' to be rejected",
+                request_id
+            );
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,212 @@
+use hkdf::Hkdf;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// scrypt work factor for password verifiers - same parameters as
+/// `session_cache`'s passphrase-derived cache key, for consistency.
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// A salted scrypt verifier for one username's password (see
+/// `Message::ChangePassword`). The plaintext password never reaches this
+/// node: the client derives the verifier locally with `derive_verifier` and
+/// only ever proves knowledge of it via `derive_proof`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PasswordProtection {
+    salt: [u8; 16],
+    verifier: [u8; 32],
+}
+
+/// On-disk shape of the directory, kept separate from `UserDirectory` so
+/// adding `passwords` didn't disturb the plain `HashSet<String>` field
+/// layout callers already depend on.
+#[derive(Default, Serialize, Deserialize)]
+struct OnDisk {
+    usernames: HashSet<String>,
+    #[serde(default)]
+    passwords: HashMap<String, PasswordProtection>,
+}
+
+/// On-disk record of every username that has ever registered a session with
+/// this node, so the directory (and its offline entries) survives restarts.
+/// Kept as a plain JSON set, in keeping with the rest of the crate's disk
+/// usage (see `peer_store::PeerStore`).
+pub struct UserDirectory {
+    path: PathBuf,
+    usernames: HashSet<String>,
+    passwords: HashMap<String, PasswordProtection>,
+}
+
+impl UserDirectory {
+    /// Load the directory from `path`, starting empty if the file doesn't
+    /// exist yet or is corrupt.
+    pub fn load(path: &Path) -> Self {
+        let on_disk: OnDisk = match std::fs::read_to_string(path) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(on_disk) => {
+                    info!("Loaded user directory from {}", path.display());
+                    on_disk
+                }
+                Err(e) => {
+                    warn!("User directory at {} is corrupt ({}), starting fresh", path.display(), e);
+                    OnDisk::default()
+                }
+            },
+            Err(_) => OnDisk::default(),
+        };
+
+        Self {
+            path: path.to_path_buf(),
+            usernames: on_disk.usernames,
+            passwords: on_disk.passwords,
+        }
+    }
+
+    /// Record that `username` has registered at least once, returning
+    /// whether it was newly added (so the caller can decide to flush).
+    pub fn record(&mut self, username: &str) -> bool {
+        self.usernames.insert(username.to_string())
+    }
+
+    /// Every username ever registered, regardless of current online status.
+    pub fn usernames(&self) -> Vec<String> {
+        self.usernames.iter().cloned().collect()
+    }
+
+    /// Whether `username` currently requires a password proof (see
+    /// `Message::AuthChallenge`/`AuthProve`) before acting on its behalf.
+    pub fn is_protected(&self, username: &str) -> bool {
+        self.passwords.contains_key(username)
+    }
+
+    /// The salt `username`'s verifier was derived with, so a client can
+    /// recompute it locally via `derive_verifier`. `None` if unprotected.
+    pub fn salt(&self, username: &str) -> Option<[u8; 16]> {
+        self.passwords.get(username).map(|protection| protection.salt)
+    }
+
+    /// Replace (or set, if unprotected so far) `username`'s password
+    /// verifier. Does not check a prior proof itself - callers (see
+    /// `node`'s `ChangePassword` handler) must verify the old password
+    /// first whenever `is_protected` was already true.
+    pub fn set_verifier(&mut self, username: &str, salt: [u8; 16], verifier: [u8; 32]) {
+        self.usernames.insert(username.to_string());
+        self.passwords.insert(username.to_string(), PasswordProtection { salt, verifier });
+    }
+
+    /// Check a client-supplied proof against `username`'s stored verifier
+    /// for the given challenge `nonce`. Returns `false` for an unprotected
+    /// or unknown username rather than erroring - callers check
+    /// `is_protected` first if that distinction matters.
+    pub fn verify_proof(&self, username: &str, nonce: &[u8; 16], proof: &[u8; 32]) -> bool {
+        match self.passwords.get(username) {
+            Some(protection) => derive_proof(&protection.verifier, nonce) == *proof,
+            None => false,
+        }
+    }
+
+    /// Persist the current set to disk. Errors are logged but non-fatal:
+    /// losing the on-disk directory just means previously-offline users
+    /// disappear from the list until they register again.
+    pub fn save(&self) {
+        let on_disk = OnDisk {
+            usernames: self.usernames.clone(),
+            passwords: self.passwords.clone(),
+        };
+        match serde_json::to_string_pretty(&on_disk) {
+            Ok(json) => {
+                if let Some(parent) = self.path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Err(e) = std::fs::write(&self.path, json) {
+                    warn!("Failed to write user directory to {}: {}", self.path.display(), e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize user directory: {}", e),
+        }
+    }
+}
+
+/// Derive a 256-bit scrypt verifier from a password and salt, run
+/// client-side so the plaintext password never leaves the client (see
+/// `Client::authenticate_password`/`change_password`).
+pub fn derive_verifier(password: &str, salt: &[u8; 16]) -> Result<[u8; 32], String> {
+    let params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 32)
+        .map_err(|e| format!("Invalid scrypt parameters: {}", e))?;
+    let mut verifier = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut verifier)
+        .map_err(|e| format!("scrypt derivation failed: {}", e))?;
+    Ok(verifier)
+}
+
+/// Derive a nonce-bound proof of knowledge of `verifier` without ever
+/// sending the verifier itself: HKDF-expand over the verifier keyed by the
+/// nonce, the same HKDF(SHA-256) construction `secure_session` uses to turn
+/// a shared secret into directional keys. A fresh nonce per challenge makes
+/// a captured proof useless for anything but the challenge it answered.
+pub fn derive_proof(verifier: &[u8; 32], nonce: &[u8; 16]) -> [u8; 32] {
+    let hk = Hkdf::<Sha256>::new(Some(nonce), verifier);
+    let mut proof = [0u8; 32];
+    hk.expand(b"auth-proof", &mut proof).expect("32 bytes is a valid HKDF-SHA256 output length");
+    proof
+}
+
+/// Default on-disk location for a node's user directory, namespaced by its
+/// own bind address so multiple local demo nodes don't clobber each other.
+pub fn default_user_directory_path(node_address: &str) -> PathBuf {
+    let safe_name = node_address.replace([':', '.'], "_");
+    PathBuf::from(format!(".user_directory_{}.json", safe_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_persists_across_load() {
+        let dir = std::env::temp_dir().join(format!("user_directory_test_{}", std::process::id()));
+        let path = dir.join("directory.json");
+
+        let mut directory = UserDirectory::load(&path);
+        directory.record("alice");
+        directory.record("bob");
+        directory.save();
+
+        let reloaded = UserDirectory::load(&path);
+        let mut usernames = reloaded.usernames();
+        usernames.sort();
+        assert_eq!(usernames, vec!["alice".to_string(), "bob".to_string()]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn password_proof_round_trips_and_persists() {
+        let dir = std::env::temp_dir().join(format!("user_directory_test_pw_{}", std::process::id()));
+        let path = dir.join("directory.json");
+
+        let salt: [u8; 16] = [3u8; 16];
+        let verifier = derive_verifier("hunter2", &salt).unwrap();
+
+        let mut directory = UserDirectory::load(&path);
+        assert!(!directory.is_protected("alice"));
+        directory.set_verifier("alice", salt, verifier);
+        assert!(directory.is_protected("alice"));
+
+        let nonce: [u8; 16] = [9u8; 16];
+        let proof = derive_proof(&verifier, &nonce);
+        assert!(directory.verify_proof("alice", &nonce, &proof));
+        assert!(!directory.verify_proof("alice", &nonce, &[0u8; 32]));
+
+        directory.save();
+        let reloaded = UserDirectory::load(&path);
+        assert!(reloaded.is_protected("alice"));
+        assert!(reloaded.verify_proof("alice", &nonce, &proof));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
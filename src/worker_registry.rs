@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+
+/// Lifecycle status of a registered background worker, as shown in the
+/// Workers tab.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerStatus {
+    /// Actively doing work right now (mid-poll, mid-write, mid-request).
+    Active,
+    /// Alive but waiting for its next tick or message.
+    Idle,
+    /// The task loop has exited, whether cancelled or crashed.
+    Dead,
+}
+
+/// Commands an operator can send a running worker over its per-worker
+/// control channel. Not every worker honors every command - e.g. the
+/// admin HTTP server has no meaningful "pause", since a listening socket
+/// is either serving or shut down.
+#[derive(Clone, Copy, Debug)]
+pub enum WorkerControl {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+/// A worker's current row in the Workers tab.
+#[derive(Clone, Debug)]
+pub struct WorkerInfo {
+    pub name: String,
+    pub status: WorkerStatus,
+    pub last_error: Option<String>,
+    /// Count of units of work done (polls run, entries written, requests
+    /// served) since the worker started - a heartbeat for the UI, not a
+    /// precise metric.
+    pub progress: u64,
+}
+
+/// Handle the Workers tab uses to send control commands to a running
+/// worker. Cheap to clone; holds only a sender.
+#[derive(Clone)]
+pub struct WorkerHandle {
+    tx: mpsc::UnboundedSender<WorkerControl>,
+}
+
+impl WorkerHandle {
+    pub fn pause(&self) {
+        let _ = self.tx.send(WorkerControl::Pause);
+    }
+
+    pub fn resume(&self) {
+        let _ = self.tx.send(WorkerControl::Resume);
+    }
+
+    pub fn cancel(&self) {
+        let _ = self.tx.send(WorkerControl::Cancel);
+    }
+}
+
+/// The write half a worker task holds to publish its own status as it
+/// runs, obtained from `WorkerRegistry::register`.
+#[derive(Clone)]
+pub struct WorkerReporter {
+    name: String,
+    workers: Arc<RwLock<HashMap<String, WorkerInfo>>>,
+}
+
+impl WorkerReporter {
+    pub async fn set_status(&self, status: WorkerStatus) {
+        if let Some(info) = self.workers.write().await.get_mut(&self.name) {
+            info.status = status;
+        }
+    }
+
+    pub async fn report_error(&self, error: impl ToString) {
+        if let Some(info) = self.workers.write().await.get_mut(&self.name) {
+            info.last_error = Some(error.to_string());
+        }
+    }
+
+    /// Bump the progress counter by one unit of work done.
+    pub async fn tick(&self) {
+        if let Some(info) = self.workers.write().await.get_mut(&self.name) {
+            info.progress += 1;
+        }
+    }
+}
+
+/// Central registry every long-running task in the monitor reports into:
+/// a name, a status, the last error it hit (if any), and a progress
+/// counter. `ServerMonitorApp`'s Workers tab reads `snapshot()` to render
+/// the table and looks up a `WorkerHandle` via `handle_for()` to wire up
+/// the pause/resume/cancel buttons.
+#[derive(Clone)]
+pub struct WorkerRegistry {
+    workers: Arc<RwLock<HashMap<String, WorkerInfo>>>,
+    handles: Arc<RwLock<HashMap<String, WorkerHandle>>>,
+}
+
+impl Default for WorkerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorkerRegistry {
+    pub fn new() -> Self {
+        Self {
+            workers: Arc::new(RwLock::new(HashMap::new())),
+            handles: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Register a new worker under `name`, returning the `WorkerReporter`
+    /// the task uses to publish status/error/progress, and the receiving
+    /// end of its control channel to `select!` on alongside its own work.
+    /// The `WorkerHandle` (the sending end) is kept in the registry for
+    /// the Workers tab to find later via `handle_for`.
+    pub async fn register(&self, name: impl Into<String>) -> (WorkerReporter, mpsc::UnboundedReceiver<WorkerControl>) {
+        let name = name.into();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        self.workers.write().await.insert(
+            name.clone(),
+            WorkerInfo { name: name.clone(), status: WorkerStatus::Idle, last_error: None, progress: 0 },
+        );
+        self.handles.write().await.insert(name.clone(), WorkerHandle { tx });
+
+        (WorkerReporter { name, workers: self.workers.clone() }, rx)
+    }
+
+    /// All registered workers, sorted by name for a stable Workers tab.
+    pub async fn snapshot(&self) -> Vec<WorkerInfo> {
+        let mut workers: Vec<WorkerInfo> = self.workers.read().await.values().cloned().collect();
+        workers.sort_by(|a, b| a.name.cmp(&b.name));
+        workers
+    }
+
+    pub async fn handle_for(&self, name: &str) -> Option<WorkerHandle> {
+        self.handles.read().await.get(name).cloned()
+    }
+}